@@ -0,0 +1,33 @@
+-- Full-text search index for posts
+-- Indexes title, summary and the markdown body. The body lives in R2, so
+-- publishers (xtask post publish and the write API) fill in the `body`
+-- column; triggers keep title and summary in sync with the posts table.
+
+CREATE VIRTUAL TABLE IF NOT EXISTS posts_fts USING fts5(
+    content_id UNINDEXED,
+    title,
+    summary,
+    body,
+    tokenize = 'porter unicode61'
+);
+
+-- Seed the index with existing posts. Bodies start empty, so existing posts are
+-- not searchable by body until `cargo xtask post reindex` (add `--remote` for
+-- production) fills them in from R2, or until each post is published again.
+INSERT INTO posts_fts (content_id, title, summary, body)
+SELECT content_id, title, COALESCE(summary, ''), '' FROM posts;
+
+-- Keep metadata columns in sync with the posts table
+CREATE TRIGGER IF NOT EXISTS posts_fts_insert AFTER INSERT ON posts BEGIN
+    INSERT INTO posts_fts (content_id, title, summary, body)
+    VALUES (new.content_id, new.title, COALESCE(new.summary, ''), '');
+END;
+
+CREATE TRIGGER IF NOT EXISTS posts_fts_update AFTER UPDATE OF title, summary ON posts BEGIN
+    UPDATE posts_fts SET title = new.title, summary = COALESCE(new.summary, '')
+    WHERE content_id = new.content_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS posts_fts_delete AFTER DELETE ON posts BEGIN
+    DELETE FROM posts_fts WHERE content_id = old.content_id;
+END;
@@ -0,0 +1,13 @@
+-- Post status model
+-- draft:     never visible through the public API
+-- scheduled: listed once published_at has passed
+-- published: listed once published_at has passed
+-- unlisted:  reachable by slug, never listed
+
+ALTER TABLE posts ADD COLUMN status TEXT NOT NULL DEFAULT 'published'
+    CHECK(status IN ('draft', 'scheduled', 'published', 'unlisted'));
+
+-- Future-dated posts were previously hidden by date alone
+UPDATE posts SET status = 'scheduled' WHERE published_at > datetime('now');
+
+CREATE INDEX IF NOT EXISTS idx_posts_status_published_at ON posts(status, published_at);
@@ -0,0 +1,5 @@
+-- Reading stats, computed from the markdown body when a post is written
+-- NULL until the post is republished or `cargo xtask post reindex` runs
+
+ALTER TABLE posts ADD COLUMN word_count INTEGER;
+ALTER TABLE posts ADD COLUMN read_time_minutes INTEGER;
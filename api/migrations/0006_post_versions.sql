@@ -0,0 +1,17 @@
+-- Post version history
+-- Each row snapshots a post's metadata just before it was overwritten; the
+-- matching markdown is kept in R2 at post-versions/{post_id}/{version}.md
+
+CREATE TABLE IF NOT EXISTS post_versions (
+    post_id TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    summary TEXT,
+    status TEXT NOT NULL,
+    published_at TEXT NOT NULL,
+    external_url TEXT,
+    tags TEXT NOT NULL DEFAULT '[]',  -- JSON array of tag names
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (post_id, version),
+    FOREIGN KEY (post_id) REFERENCES posts(content_id) ON DELETE CASCADE
+);
@@ -0,0 +1,23 @@
+-- Slug aliases
+-- Old slugs of renamed posts and projects. Aliases point at the row id
+-- (posts.content_id / projects.id), so a chain of renames always resolves
+-- to the current slug; the API answers alias lookups with a 301.
+
+CREATE TABLE IF NOT EXISTS slug_aliases (
+    kind TEXT NOT NULL CHECK(kind IN ('post', 'project')),
+    old_slug TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (kind, old_slug)
+);
+
+CREATE INDEX IF NOT EXISTS idx_slug_aliases_target ON slug_aliases(kind, target_id);
+
+-- Aliases can reference either table, so deletes are cascaded by triggers
+CREATE TRIGGER IF NOT EXISTS posts_delete_aliases AFTER DELETE ON posts BEGIN
+    DELETE FROM slug_aliases WHERE kind = 'post' AND target_id = old.content_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS projects_delete_aliases AFTER DELETE ON projects BEGIN
+    DELETE FROM slug_aliases WHERE kind = 'project' AND target_id = old.id;
+END;
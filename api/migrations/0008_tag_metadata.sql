@@ -0,0 +1,8 @@
+-- Tag metadata
+-- Optional presentation fields for the tags shared by posts and projects.
+-- `name` stays the canonical lowercase identifier used in URLs and filters.
+
+ALTER TABLE tags ADD COLUMN display_name TEXT;
+ALTER TABLE tags ADD COLUMN description TEXT;
+ALTER TABLE tags ADD COLUMN color TEXT
+    CHECK(color IS NULL OR color GLOB '#[0-9a-fA-F][0-9a-fA-F][0-9a-fA-F][0-9a-fA-F][0-9a-fA-F][0-9a-fA-F]');
@@ -0,0 +1,14 @@
+-- Tag aliases
+-- Other spellings of a tag ("webdev" for "web-dev"), recorded by
+-- `xtask tags merge` and `xtask tags rename`. Aliases point at the tag id,
+-- so renaming a tag keeps its aliases working; writes and filters that name
+-- an alias use the tag it points at.
+
+CREATE TABLE IF NOT EXISTS tag_aliases (
+    alias TEXT PRIMARY KEY,
+    tag_id INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_tag_aliases_tag_id ON tag_aliases(tag_id);
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use worker::*;
 
+use crate::logging;
+
 /// API error response
 /// Note: Request IDs are included in the X-Request-ID response header, not in the body
 #[derive(Debug, Serialize, Deserialize)]
@@ -29,6 +31,25 @@ impl ApiError {
         Self::new("BAD_REQUEST", message)
     }
 
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new("UNAUTHORIZED", message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new("CONFLICT", message)
+    }
+
+    pub fn too_many_requests(message: impl Into<String>) -> Self {
+        Self::new("TOO_MANY_REQUESTS", message)
+    }
+
+    /// Log this error with the request it belongs to and the underlying cause
+    ///
+    /// The cause stays server-side; clients only see `code` and `message`.
+    pub fn log(&self, request_id: &str, cause: &str) {
+        logging::log_error(request_id, &format!("{}: {}", self.code, self.message), Some(cause));
+    }
+
     pub fn to_response(&self, status: u16) -> Result<Response> {
         let body = serde_json::json!({
             "error": self
@@ -3,17 +3,16 @@ use worker::*;
 mod errors;
 mod logging;
 mod middleware;
-mod models;
 mod openapi;
 mod routes;
 mod storage;
 
 #[event(fetch)]
-async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
+async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
     let start = Date::now().as_millis();
 
-    // Generate request ID
-    let request_id = middleware::generate_request_id();
+    // Reuse the caller's or Cloudflare's request ID when there is one
+    let request_id = middleware::request_id(&req);
 
     // Log incoming request
     let method = req.method().to_string();
@@ -23,17 +22,78 @@ async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
 
     // Get origin for CORS
     let origin = req.headers().get("Origin").ok().flatten();
+    let cors = middleware::CorsPolicy::from_env(&env);
 
-    // Handle OPTIONS preflight
-    if req.method() == Method::Options {
-        let response = middleware::handle_options()?;
-        let duration_ms = (Date::now().as_millis() - start) as u64;
-        logging::log_response(&request_id, 204, duration_ms);
-        return Ok(response);
+    // Preflights are answered here; everything else goes through the pipeline
+    let response = if req.method() == Method::Options {
+        cors.preflight(&req)?
+    } else {
+        handle(req, env, &ctx, &path, &request_id).await?
+    };
+
+    // Apply middleware to response
+    let response = cors.apply(response, origin.as_deref())?;
+    let response = middleware::add_request_id_header(response, &request_id)?;
+
+    // Log response
+    let duration_ms = Date::now().as_millis() - start;
+    let status = response.status_code();
+    logging::log_response(&request_id, status, duration_ms);
+
+    Ok(response)
+}
+
+/// Rate limit, then serve a request from the cache or its route handler
+async fn handle(req: Request, env: Env, ctx: &Context, path: &str, request_id: &str) -> Result<Response> {
+    let url = req.url()?;
+    let is_write = !matches!(req.method(), Method::Get | Method::Head);
+    let preconditions = middleware::Preconditions::from_request(&req)?;
+
+    // Rate limiting, before any storage is touched
+    let rate_limit = match middleware::rate_budget(&req.method(), &url) {
+        Some((name, budget)) => middleware::check_rate_limit(&req, &env, name, budget).await?,
+        None => None,
+    };
+
+    match rate_limit {
+        Some(decision) if !decision.allowed => middleware::too_many_requests(&decision),
+        _ => {
+            // Authorized reads may include drafts, so they bypass the cache
+            let cache_policy = match req.method() {
+                Method::Get if !req.headers().has("Authorization")? => middleware::cache_policy(path),
+                _ => None,
+            };
+
+            let data = middleware::RequestContext { request_id: request_id.to_string() };
+            let response = match cache_policy {
+                Some(policy) => {
+                    let route = move |req, env| route(req, env, data.clone());
+                    middleware::serve_cached(req, env, ctx, policy, route).await?
+                }
+                None => route(req, env, data).await?,
+            };
+
+            // Successful writes purge the cached responses they change
+            if is_write && (200..300).contains(&response.status_code()) {
+                let tags = middleware::purged_cache_tags(path);
+                if let Err(e) = middleware::purge_cache(&url, tags).await {
+                    logging::log_error(request_id, &format!("Failed to purge cache tags {:?}", tags), Some(&format!("{:?}", e)));
+                }
+            }
+
+            // Conditional GET, using the validators set by the handlers
+            let response = if is_write { response } else { middleware::conditional_get(response, &preconditions)? };
+            match &rate_limit {
+                Some(decision) => middleware::add_rate_limit_headers(response, decision),
+                None => Ok(response),
+            }
+        }
     }
+}
 
-    // Initialize router
-    let response = Router::new()
+/// Dispatch a request to its route handler
+async fn route(req: Request, env: Env, data: middleware::RequestContext) -> Result<Response> {
+    Router::with_data(data)
         // Meta endpoints (unversioned)
         .get_async("/", |req, ctx| async move { routes::meta::handle_root(req, ctx).await })
         .get_async("/openapi.json", |req, ctx| async move { routes::meta::handle_openapi_spec(req, ctx).await })
@@ -42,22 +102,19 @@ async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
         .get_async("/v1/health", |req, ctx| async move { routes::meta::handle_health(req, ctx).await })
         .get_async("/v1/posts", |req, ctx| async move { routes::posts::handle_list_posts(req, ctx).await })
         .get_async("/v1/posts/:slug", |req, ctx| async move { routes::posts::handle_get_post(req, ctx).await })
-        .get_async("/v1/tags", |req, ctx| async move { routes::posts::handle_get_tags(req, ctx).await })
+        .post_async("/v1/posts", |req, ctx| async move { routes::posts::handle_create_post(req, ctx).await })
+        .put_async("/v1/posts/:slug", |req, ctx| async move { routes::posts::handle_update_post(req, ctx).await })
+        .delete_async("/v1/posts/:slug", |req, ctx| async move { routes::posts::handle_delete_post(req, ctx).await })
+        .get_async("/v1/feed.xml", |req, ctx| async move { routes::feeds::handle_rss_feed(req, ctx).await })
+        .get_async("/v1/feed.atom", |req, ctx| async move { routes::feeds::handle_atom_feed(req, ctx).await })
+        .get_async("/v1/feed.json", |req, ctx| async move { routes::feeds::handle_json_feed(req, ctx).await })
+        .get_async("/v1/tags", |req, ctx| async move { routes::tags::handle_list_tags(req, ctx).await })
+        .get_async("/v1/tags/:name", |req, ctx| async move { routes::tags::handle_get_tag(req, ctx).await })
         .get_async("/v1/projects", |req, ctx| async move { routes::projects::handle_list_projects(req, ctx).await })
         .get_async("/v1/projects/:slug", |req, ctx| async move { routes::projects::handle_get_project(req, ctx).await })
+        .patch_async("/v1/projects/:slug", |req, ctx| async move { routes::projects::handle_patch_project(req, ctx).await })
         .get_async("/v1/resume", |req, ctx| async move { routes::resume::handle_get_resume(req, ctx).await })
 
         .run(req, env)
-        .await?;
-
-    // Apply middleware to response
-    let response = middleware::add_cors_headers(response, origin.as_deref())?;
-    let response = middleware::add_request_id_header(response, &request_id)?;
-
-    // Log response
-    let duration_ms = (Date::now().as_millis() - start) as u64;
-    let status = response.status_code();
-    logging::log_response(&request_id, status, duration_ms);
-
-    Ok(response)
-}
\ No newline at end of file
+        .await
+}
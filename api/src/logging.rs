@@ -1,7 +1,7 @@
 /// Structured logging utilities for Cloudflare Workers
 ///
 /// Provides macros for structured logging with automatic request_id correlation
-use worker::console_log;
+use worker::{console_error, console_log};
 
 /// Log levels
 #[derive(Debug, Clone, Copy)]
@@ -40,7 +40,10 @@ pub fn log(level: LogLevel, request_id: &str, message: &str, context: Option<&st
         )
     };
 
-    console_log!("{}", log_entry);
+    match level {
+        LogLevel::Error => console_error!("{}", log_entry),
+        _ => console_log!("{}", log_entry),
+    }
 }
 
 /// Log an incoming request
@@ -65,7 +68,6 @@ pub fn log_response(request_id: &str, status: u16, duration_ms: u64) {
 }
 
 /// Log an error
-#[allow(dead_code)]
 pub fn log_error(request_id: &str, error: &str, context: Option<&str>) {
     log(LogLevel::Error, request_id, error, context);
 }
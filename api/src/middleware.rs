@@ -1,64 +1,226 @@
+use chrono::{DateTime, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
 use worker::*;
+use crate::errors::ApiError;
 
-/// Generate a unique request ID
-pub fn generate_request_id() -> String {
-    // Use a simple counter-based ID for now
-    // In production, Workers provides request IDs automatically
-    use std::sync::atomic::{AtomicU64, Ordering};
-    static COUNTER: AtomicU64 = AtomicU64::new(0);
-    let id = COUNTER.fetch_add(1, Ordering::SeqCst);
-    format!("req_{:x}", id)
+/// Longest incoming `X-Request-ID` that is reused
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Per-request data handed to route handlers through `RouteContext`
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub request_id: String,
 }
 
-/// Add CORS headers to response
-pub fn add_cors_headers(response: Response, origin: Option<&str>) -> Result<Response> {
-    let headers = response.headers();
+/// Request ID for a request: the caller's `X-Request-ID`, then Cloudflare's `cf-ray`,
+/// otherwise a random UUID
+pub fn request_id(req: &Request) -> String {
+    let headers = req.headers();
+    let incoming = headers.get("X-Request-ID").ok().flatten();
+    let cf_ray = headers.get("cf-ray").ok().flatten();
+    pick_request_id(incoming.as_deref(), cf_ray.as_deref())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+fn pick_request_id(incoming: Option<&str>, cf_ray: Option<&str>) -> Option<String> {
+    [incoming, cf_ray]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|id| is_valid_request_id(id))
+        .map(str::to_string)
+}
+
+/// IDs end up in logs and response headers, so only short, plain tokens are accepted
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+/// Worker vars configuring CORS (see `[vars]` in wrangler.toml)
+const CORS_ALLOWED_ORIGINS_VAR: &str = "CORS_ALLOWED_ORIGINS";
+const CORS_ALLOWED_METHODS_VAR: &str = "CORS_ALLOWED_METHODS";
+const CORS_ALLOWED_HEADERS_VAR: &str = "CORS_ALLOWED_HEADERS";
+const CORS_EXPOSED_HEADERS_VAR: &str = "CORS_EXPOSED_HEADERS";
+const CORS_ALLOW_CREDENTIALS_VAR: &str = "CORS_ALLOW_CREDENTIALS";
+const CORS_MAX_AGE_VAR: &str = "CORS_MAX_AGE";
+
+/// Defaults for vars that are not set
+const DEFAULT_ALLOWED_ORIGINS: &str = "https://werdxz.info, https://*.werdxz.info";
+const DEFAULT_ALLOWED_METHODS: &str = "GET, HEAD, POST, PUT, PATCH, DELETE, OPTIONS";
+const DEFAULT_ALLOWED_HEADERS: &str = "Authorization, Content-Type, X-Request-ID";
+const DEFAULT_EXPOSED_HEADERS: &str =
+    "ETag, X-Request-ID, X-Cache, RateLimit-Limit, RateLimit-Remaining, RateLimit-Reset, RateLimit-Policy, Retry-After";
+const DEFAULT_MAX_AGE: u32 = 86400;
 
-    // Check if origin is from allowed domains
-    let allowed_origin = match origin {
-        Some(o) if is_allowed_origin(o) => o,
-        _ => {
-            // Reject unrecognized origins by not setting CORS headers
-            // Browser will block the request
-            return Ok(response);
+/// An entry of `CORS_ALLOWED_ORIGINS`
+#[derive(Debug, Clone, PartialEq)]
+enum OriginPattern {
+    /// `*`: any origin
+    Any,
+    /// `https://werdxz.info`
+    Exact(String),
+    /// `https://*.werdxz.info`: one or more subdomain labels in place of `*`
+    Subdomains { prefix: String, suffix: String },
+}
+
+impl OriginPattern {
+    fn parse(pattern: &str) -> Self {
+        let pattern = pattern.trim().trim_end_matches('/').to_ascii_lowercase();
+        if pattern == "*" {
+            return Self::Any;
         }
-    };
+        match pattern.split_once('*') {
+            Some((prefix, suffix)) => Self::Subdomains { prefix: prefix.to_string(), suffix: suffix.to_string() },
+            None => Self::Exact(pattern),
+        }
+    }
 
-    headers.set("Access-Control-Allow-Origin", allowed_origin)?;
-    headers.set("Access-Control-Allow-Methods", "GET, OPTIONS")?;
-    headers.set("Access-Control-Allow-Headers", "Content-Type, X-Request-ID")?;
-    headers.set("Access-Control-Max-Age", "86400")?;
+    fn matches(&self, origin: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Exact(exact) => origin.eq_ignore_ascii_case(exact),
+            Self::Subdomains { prefix, suffix } => {
+                let origin = origin.to_ascii_lowercase();
+                let Some(labels) = origin.strip_prefix(prefix.as_str()).and_then(|rest| rest.strip_suffix(suffix.as_str())) else {
+                    return false;
+                };
+                // Only host labels may stand in for `*`, never a port, path or userinfo
+                !labels.is_empty()
+                    && !labels.starts_with('.')
+                    && labels.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+            }
+        }
+    }
+}
 
-    Ok(response)
+/// CORS policy, loaded from Worker vars on each request
+#[derive(Debug, Clone)]
+pub struct CorsPolicy {
+    origins: Vec<OriginPattern>,
+    methods: Vec<String>,
+    headers: String,
+    exposed_headers: String,
+    allow_credentials: bool,
+    max_age: u32,
 }
 
-/// Check if origin is allowed
-/// Default: *.werdxz.info and werdxz.info (HTTPS only)
-/// Opt-in via compile-time env: ALLOWED_ORIGINS (comma-separated)
-fn is_allowed_origin(origin: &str) -> bool {
-    // Always allow werdxz.info domains (HTTPS only for security)
-    if origin.ends_with(".werdxz.info") || origin == "https://werdxz.info" {
-        return true;
+impl CorsPolicy {
+    pub fn from_env(env: &Env) -> Self {
+        Self::from_vars(|name| env.var(name).ok().map(|var| var.to_string()))
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let list = |name, default: &str| -> Vec<String> {
+            var(name).unwrap_or_else(|| default.to_string())
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+
+        Self {
+            origins: list(CORS_ALLOWED_ORIGINS_VAR, DEFAULT_ALLOWED_ORIGINS).iter().map(|o| OriginPattern::parse(o)).collect(),
+            methods: list(CORS_ALLOWED_METHODS_VAR, DEFAULT_ALLOWED_METHODS).iter().map(|m| m.to_ascii_uppercase()).collect(),
+            headers: list(CORS_ALLOWED_HEADERS_VAR, DEFAULT_ALLOWED_HEADERS).join(", "),
+            exposed_headers: list(CORS_EXPOSED_HEADERS_VAR, DEFAULT_EXPOSED_HEADERS).join(", "),
+            allow_credentials: var(CORS_ALLOW_CREDENTIALS_VAR).is_some_and(|v| v.trim().eq_ignore_ascii_case("true")),
+            max_age: var(CORS_MAX_AGE_VAR).and_then(|v| v.trim().parse().ok()).unwrap_or(DEFAULT_MAX_AGE),
+        }
+    }
+
+    /// Whether responses are the same for every origin (`*` without credentials)
+    fn is_public(&self) -> bool {
+        !self.allow_credentials && self.origins.contains(&OriginPattern::Any)
+    }
+
+    /// `Access-Control-Allow-Origin` for a request's `Origin`, or `None` to withhold CORS headers
+    ///
+    /// Credentialed responses must name the origin, so `*` is only sent without credentials.
+    fn allow_origin(&self, origin: Option<&str>) -> Option<String> {
+        if self.is_public() {
+            return Some("*".to_string());
+        }
+        let origin = origin?;
+        self.origins.iter().any(|pattern| pattern.matches(origin)).then(|| origin.to_string())
+    }
+
+    /// CORS headers for an actual (non-preflight) response
+    fn response_headers(&self, origin: Option<&str>) -> Vec<(&'static str, String)> {
+        let mut headers = Vec::new();
+        if let Some(allow_origin) = self.allow_origin(origin) {
+            headers.push(("Access-Control-Allow-Origin", allow_origin));
+            if self.allow_credentials {
+                headers.push(("Access-Control-Allow-Credentials", "true".to_string()));
+            }
+            if !self.exposed_headers.is_empty() {
+                headers.push(("Access-Control-Expose-Headers", self.exposed_headers.clone()));
+            }
+        }
+        headers
     }
 
-    // Check compile-time ALLOWED_ORIGINS environment variable
-    // Set via: ALLOWED_ORIGINS="http://localhost:3000,http://127.0.0.1:3000" cargo build
-    // Or in wrangler.toml: [env.dev.build] command = "ALLOWED_ORIGINS=... worker-build"
-    if let Some(allowed_origins) = option_env!("ALLOWED_ORIGINS") {
-        let origins: Vec<&str> = allowed_origins.split(',').map(|s| s.trim()).collect();
-        if origins.contains(&origin) {
-            return true;
+    /// CORS headers for a preflight asking to use `method`
+    ///
+    /// Disallowed origins and methods get none, so the browser blocks the request.
+    fn preflight_headers(&self, origin: Option<&str>, method: Option<&str>) -> Vec<(&'static str, String)> {
+        let allowed_method = method.is_some_and(|method| self.methods.iter().any(|m| m.eq_ignore_ascii_case(method.trim())));
+        let Some(allow_origin) = self.allow_origin(origin).filter(|_| allowed_method) else {
+            return Vec::new();
+        };
+
+        let mut headers = vec![
+            ("Access-Control-Allow-Origin", allow_origin),
+            ("Access-Control-Allow-Methods", self.methods.join(", ")),
+            ("Access-Control-Allow-Headers", self.headers.clone()),
+            ("Access-Control-Max-Age", self.max_age.to_string()),
+        ];
+        if self.allow_credentials {
+            headers.push(("Access-Control-Allow-Credentials", "true".to_string()));
         }
+        headers
     }
 
-    false
+    /// Add CORS headers to a response, and `Vary: Origin` when they depend on the origin
+    pub fn apply(&self, mut response: Response, origin: Option<&str>) -> Result<Response> {
+        let headers = response.headers_mut();
+        for (name, value) in self.response_headers(origin) {
+            headers.set(name, &value)?;
+        }
+        if !self.is_public() {
+            append_vary(headers, "Origin")?;
+        }
+        Ok(response)
+    }
+
+    /// Answer an `OPTIONS` request, which never reaches the router
+    pub fn preflight(&self, req: &Request) -> Result<Response> {
+        let origin = req.headers().get("Origin")?;
+        let method = req.headers().get("Access-Control-Request-Method")?;
+
+        let mut response = Response::empty()?.with_status(204);
+        let headers = response.headers_mut();
+        for (name, value) in self.preflight_headers(origin.as_deref(), method.as_deref()) {
+            headers.set(name, &value)?;
+        }
+        let vary = if self.is_public() { "" } else { "Origin, " };
+        append_vary(headers, &format!("{}Access-Control-Request-Method, Access-Control-Request-Headers", vary))?;
+        Ok(response)
+    }
 }
 
-/// Handle OPTIONS preflight requests
-pub fn handle_options() -> Result<Response> {
-    let mut response = Response::empty()?;
-    response = add_cors_headers(response, None)?;
-    Ok(response.with_status(204))
+/// Add names to `Vary`, keeping the ones already there
+fn append_vary(headers: &mut Headers, names: &str) -> Result<()> {
+    let vary = match headers.get("Vary")? {
+        Some(existing) if !existing.is_empty() => format!("{}, {}", existing, names),
+        _ => names.to_string(),
+    };
+    headers.set("Vary", &vary)
 }
 
 /// Add request ID header to response
@@ -66,3 +228,824 @@ pub fn add_request_id_header(mut response: Response, request_id: &str) -> Result
     response.headers_mut().set("X-Request-ID", request_id)?;
     Ok(response)
 }
+
+/// Cache tags; stored responses carry them in `Cache-Tag`, so `xtask` can purge
+/// them across every data center through the Cloudflare API
+pub const CACHE_TAG_POSTS: &str = "api-posts";
+pub const CACHE_TAG_PROJECTS: &str = "api-projects";
+pub const CACHE_TAG_TAGS: &str = "api-tags";
+pub const CACHE_TAG_RESUME: &str = "api-resume";
+
+/// Edge caching rules for a read endpoint
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CachePolicy {
+    /// Seconds a response is served without revalidating
+    pub max_age: u64,
+    /// Further seconds a stale response is served while it is refreshed in the background
+    pub stale_while_revalidate: u64,
+    /// Data the response depends on; writes to any of it purge the response
+    pub tags: &'static [&'static str],
+}
+
+impl CachePolicy {
+    /// Seconds the cached copy is kept in `caches.default`
+    fn stored_for(&self) -> u64 {
+        self.max_age + self.stale_while_revalidate
+    }
+}
+
+/// Longest `stored_for` of any policy; purge markers must outlive cached responses
+const PURGE_MARKER_MAX_AGE: u64 = 2 * 86400;
+
+/// Cache policy of a GET endpoint, or `None` if it is not cached
+pub fn cache_policy(path: &str) -> Option<CachePolicy> {
+    let path = path.trim_end_matches('/');
+    let policy = |max_age, stale_while_revalidate, tags| Some(CachePolicy { max_age, stale_while_revalidate, tags });
+
+    match path {
+        "/v1/posts" => policy(300, 3600, &[CACHE_TAG_POSTS]),
+        "/v1/feed.xml" | "/v1/feed.atom" | "/v1/feed.json" => policy(900, 3600, &[CACHE_TAG_POSTS]),
+        "/v1/projects" => policy(300, 3600, &[CACHE_TAG_PROJECTS]),
+        "/v1/tags" => policy(300, 3600, &[CACHE_TAG_TAGS, CACHE_TAG_POSTS, CACHE_TAG_PROJECTS]),
+        "/v1/resume" => policy(3600, 86400, &[CACHE_TAG_RESUME]),
+        _ if path.starts_with("/v1/posts/") => policy(300, 3600, &[CACHE_TAG_POSTS]),
+        _ if path.starts_with("/v1/projects/") => policy(300, 3600, &[CACHE_TAG_PROJECTS]),
+        _ if path.starts_with("/v1/tags/") => policy(300, 3600, &[CACHE_TAG_TAGS, CACHE_TAG_POSTS, CACHE_TAG_PROJECTS]),
+        _ => None,
+    }
+}
+
+/// Cache tags a successful write to `path` invalidates
+pub fn purged_cache_tags(path: &str) -> &'static [&'static str] {
+    if path.starts_with("/v1/posts") {
+        &[CACHE_TAG_POSTS]
+    } else if path.starts_with("/v1/projects") {
+        &[CACHE_TAG_PROJECTS]
+    } else {
+        &[]
+    }
+}
+
+/// Cache key for a request URL: query parameters sorted, empty ones and the fragment dropped
+///
+/// `?limit=10&page=2` and `?page=2&limit=10&tags=` share one entry.
+pub fn cache_key(url: &Url) -> String {
+    let mut pairs: Vec<(String, String)> = url.query_pairs()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    pairs.sort();
+
+    let mut key = url.clone();
+    key.set_fragment(None);
+    key.set_query(None);
+    if !pairs.is_empty() {
+        key.query_pairs_mut().extend_pairs(pairs);
+    }
+    if key.path().len() > 1 && key.path().ends_with('/') {
+        let path = key.path().trim_end_matches('/').to_string();
+        key.set_path(&path);
+    }
+    key.into()
+}
+
+/// Key of the marker recording when `tag` was last purged in this data center
+fn purge_marker_key(url: &Url, tag: &str) -> String {
+    format!("{}://{}/__cache/purged/{}", url.scheme(), url.host_str().unwrap_or_default(), tag)
+}
+
+/// How a cached response may be used
+#[derive(Debug, PartialEq)]
+enum Freshness {
+    Fresh,
+    /// Served, then refreshed in the background
+    Stale,
+    /// Purged or past its stale window; fetched again
+    Expired,
+}
+
+fn freshness(policy: &CachePolicy, stored_at: u64, purged_at: u64, now: u64) -> Freshness {
+    let age = now.saturating_sub(stored_at) / 1000;
+    if stored_at <= purged_at || age >= policy.stored_for() {
+        Freshness::Expired
+    } else if age < policy.max_age {
+        Freshness::Fresh
+    } else {
+        Freshness::Stale
+    }
+}
+
+/// Header holding the time (ms since epoch) a response was cached
+const STORED_AT_HEADER: &str = "X-Cache-Stored-At";
+
+/// Serve a GET request from `caches.default`, running `route` on a miss
+///
+/// The Workers Cache API ignores `stale-while-revalidate`, so the cached copy
+/// is kept for the whole stale window and its age is checked here. Stale
+/// responses are refreshed with `ctx.wait_until`. Cache failures fall back to
+/// `route`, so the cache can only make a request faster, never fail it.
+pub async fn serve_cached<F, Fut>(req: Request, env: Env, ctx: &Context, policy: CachePolicy, route: F) -> Result<Response>
+where
+    F: Fn(Request, Env) -> Fut + 'static,
+    Fut: Future<Output = Result<Response>> + 'static,
+{
+    let url = req.url()?;
+    let key = cache_key(&url);
+    let cache = Cache::default();
+
+    match lookup(&cache, &url, &key, &policy).await {
+        Ok(Some((Freshness::Fresh, response))) => {
+            return with_cache_headers(response, &policy, "HIT");
+        }
+        Ok(Some((Freshness::Stale, response))) => {
+            ctx.wait_until(async move {
+                let result = match route(req, env).await {
+                    Ok(fresh) => store(&cache, &key, fresh, &policy).await.map(|_| ()),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    console_error!("Failed to revalidate {}: {:?}", key, e);
+                }
+            });
+            return with_cache_headers(response, &policy, "STALE");
+        }
+        Ok(_) => {}
+        Err(e) => console_error!("Cache lookup failed for {}: {:?}", key, e),
+    }
+
+    let response = route(req, env).await?;
+    if response.status_code() != 200 {
+        return Ok(response);
+    }
+
+    let (response, stored) = split_for_cache(response, &policy).await?;
+    ctx.wait_until(async move {
+        if let Err(e) = cache.put(key.as_str(), stored).await {
+            console_error!("Failed to cache {}: {:?}", key, e);
+        }
+    });
+    with_cache_headers(response, &policy, "MISS")
+}
+
+/// Cached response for `key` with its freshness, or `None` on a miss
+async fn lookup(cache: &Cache, url: &Url, key: &str, policy: &CachePolicy) -> Result<Option<(Freshness, Response)>> {
+    let Some(cached) = cache.get(key, false).await? else {
+        return Ok(None);
+    };
+
+    let stored_at = cached.headers().get(STORED_AT_HEADER)?
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(0);
+    let mut purged_at = 0;
+    for tag in policy.tags {
+        if let Some(mut marker) = cache.get(purge_marker_key(url, tag), false).await? {
+            purged_at = purged_at.max(marker.text().await?.parse::<u64>().unwrap_or(0));
+        }
+    }
+
+    let freshness = freshness(policy, stored_at, purged_at, Date::now().as_millis());
+    // Responses from the cache have immutable headers
+    let (response, _) = copy_response(cached).await?;
+    Ok(Some((freshness, response)))
+}
+
+/// Cache a fresh response from a background revalidation
+async fn store(cache: &Cache, key: &str, response: Response, policy: &CachePolicy) -> Result<()> {
+    if response.status_code() != 200 {
+        return Ok(());
+    }
+    let (_, stored) = split_for_cache(response, policy).await?;
+    cache.put(key, stored).await
+}
+
+/// Split a response into the copy returned to the client and the copy cached
+async fn split_for_cache(response: Response, policy: &CachePolicy) -> Result<(Response, Response)> {
+    let (response, body) = copy_response(response).await?;
+
+    let headers = response.headers().clone();
+    headers.set("Cache-Control", &format!("public, max-age={}", policy.stored_for()))?;
+    headers.set("Cache-Tag", &policy.tags.join(","))?;
+    headers.set(STORED_AT_HEADER, &Date::now().as_millis().to_string())?;
+    let stored = Response::from_bytes(body)?
+        .with_headers(headers)
+        .with_status(response.status_code());
+
+    Ok((response, stored))
+}
+
+/// Rebuild a response with mutable headers, also returning its body
+async fn copy_response(mut response: Response) -> Result<(Response, Vec<u8>)> {
+    let body = response.bytes().await?;
+    let copy = Response::from_bytes(body.clone())?
+        .with_headers(response.headers().clone())
+        .with_status(response.status_code());
+    Ok((copy, body))
+}
+
+fn with_cache_headers(mut response: Response, policy: &CachePolicy, status: &str) -> Result<Response> {
+    let headers = response.headers_mut();
+    headers.set(
+        "Cache-Control",
+        &format!("public, max-age={}, stale-while-revalidate={}", policy.max_age, policy.stale_while_revalidate),
+    )?;
+    headers.set("X-Cache", status)?;
+    headers.delete(STORED_AT_HEADER)?;
+    headers.delete("Cache-Tag")?;
+    Ok(response)
+}
+
+/// Purge cached responses carrying any of `tags` in this data center
+///
+/// Cached entries cannot be listed, so a marker with the purge time is stored
+/// per tag and older entries are treated as expired. Other data centers keep
+/// their copies until they expire or `xtask` purges the tags zone-wide.
+pub async fn purge_cache(url: &Url, tags: &[&str]) -> Result<()> {
+    let cache = Cache::default();
+    let now = Date::now().as_millis().to_string();
+    for tag in tags {
+        let mut marker = Response::ok(now.clone())?;
+        marker.headers_mut().set("Cache-Control", &format!("public, max-age={}", PURGE_MARKER_MAX_AGE))?;
+        cache.put(purge_marker_key(url, tag), marker).await?;
+    }
+    Ok(())
+}
+
+/// Strong ETag over the parts a response is built from (64-bit FNV-1a)
+pub fn etag(parts: &[&[u8]]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for (i, part) in parts.iter().enumerate() {
+        // Separate parts so ("ab", "c") and ("a", "bc") differ
+        let separator: &[u8] = if i == 0 { &[] } else { &[0xff] };
+        for byte in separator.iter().chain(part.iter()) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    format!("\"{:016x}\"", hash)
+}
+
+/// JSON response with an ETag hashed from its body
+///
+/// Used for responses built from D1 results, where the body is the result.
+pub fn json_with_etag<T: Serialize>(value: &T) -> Result<Response> {
+    let body = serde_json::to_vec(value)?;
+    let etag = etag(&[&body]);
+    let mut response = Response::from_bytes(body)?;
+    response.headers_mut().set("Content-Type", "application/json")?;
+    response.headers_mut().set("ETag", &etag)?;
+    Ok(response)
+}
+
+/// Set `Last-Modified` from a D1 timestamp (`YYYY-MM-DD HH:MM:SS`, UTC)
+pub fn set_last_modified(response: &mut Response, timestamp: &str) -> Result<()> {
+    if let Some(date) = http_date(timestamp) {
+        response.headers_mut().set("Last-Modified", &date)?;
+    }
+    Ok(())
+}
+
+fn http_date(timestamp: &str) -> Option<String> {
+    let naive = NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok()?;
+    Some(naive.and_utc().format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+}
+
+/// Request headers of a conditional GET
+#[derive(Debug, Default)]
+pub struct Preconditions {
+    pub if_none_match: Option<String>,
+    pub if_modified_since: Option<String>,
+}
+
+impl Preconditions {
+    pub fn from_request(req: &Request) -> Result<Self> {
+        Ok(Self {
+            if_none_match: req.headers().get("If-None-Match")?,
+            if_modified_since: req.headers().get("If-Modified-Since")?,
+        })
+    }
+
+    /// Whether a response with these validators is unchanged for the client
+    ///
+    /// `If-Modified-Since` only counts when `If-None-Match` is absent (RFC 9110).
+    fn not_modified(&self, etag: Option<&str>, last_modified: Option<&str>) -> bool {
+        if let Some(if_none_match) = &self.if_none_match {
+            return etag.is_some_and(|etag| etag_matches(if_none_match, etag));
+        }
+        match (&self.if_modified_since, last_modified) {
+            (Some(since), Some(last_modified)) => not_modified_since(since, last_modified),
+            _ => false,
+        }
+    }
+}
+
+/// Answer `304 Not Modified` if the client's copy of a successful GET response is current
+///
+/// The 304 keeps the validators and caching headers; CORS and request-id
+/// headers are added to it like to any other response.
+pub fn conditional_get(response: Response, preconditions: &Preconditions) -> Result<Response> {
+    if response.status_code() != 200 {
+        return Ok(response);
+    }
+
+    let headers = response.headers();
+    let etag = headers.get("ETag")?;
+    let last_modified = headers.get("Last-Modified")?;
+    if !preconditions.not_modified(etag.as_deref(), last_modified.as_deref()) {
+        return Ok(response);
+    }
+
+    let not_modified = Headers::new();
+    for name in ["ETag", "Last-Modified", "Cache-Control", "Vary", "X-Cache"] {
+        if let Some(value) = headers.get(name)? {
+            not_modified.set(name, &value)?;
+        }
+    }
+    Ok(Response::empty()?.with_headers(not_modified).with_status(304))
+}
+
+/// `If-None-Match` against an ETag, using the weak comparison GET requires
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag);
+    if_none_match.split(',').any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
+
+/// Whether `Last-Modified` is no later than `If-Modified-Since`; unparseable dates never match
+fn not_modified_since(if_modified_since: &str, last_modified: &str) -> bool {
+    match (DateTime::parse_from_rfc2822(if_modified_since), DateTime::parse_from_rfc2822(last_modified)) {
+        (Ok(since), Ok(modified)) => modified <= since,
+        _ => false,
+    }
+}
+
+/// Requests a client may make per window; the bucket refills continuously
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateBudget {
+    pub limit: u32,
+    pub window_secs: u32,
+}
+
+/// Outcome of taking a token from a bucket
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateDecision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the next request is allowed when throttled, otherwise until the bucket is full
+    pub reset_secs: u32,
+    pub window_secs: u32,
+}
+
+/// Token bucket for one client and budget
+#[derive(Debug, Clone, Copy)]
+pub struct TokenBucket {
+    tokens: f64,
+    updated_at: u64,
+}
+
+impl TokenBucket {
+    pub fn full(budget: &RateBudget, now: u64) -> Self {
+        Self { tokens: f64::from(budget.limit), updated_at: now }
+    }
+
+    /// Refill for the time since the last request, then take a token if one is left
+    pub fn take(&mut self, budget: &RateBudget, now: u64) -> RateDecision {
+        let limit = f64::from(budget.limit);
+        let per_ms = limit / (f64::from(budget.window_secs) * 1000.0);
+
+        let elapsed = now.saturating_sub(self.updated_at) as f64;
+        self.tokens = (self.tokens + elapsed * per_ms).min(limit);
+        self.updated_at = now;
+
+        let allowed = self.tokens >= 1.0;
+        if allowed {
+            self.tokens -= 1.0;
+        }
+        let missing = if allowed { limit - self.tokens } else { 1.0 - self.tokens };
+
+        RateDecision {
+            allowed,
+            limit: budget.limit,
+            remaining: self.tokens.floor() as u32,
+            reset_secs: (missing / per_ms / 1000.0).ceil() as u32,
+            window_secs: budget.window_secs,
+        }
+    }
+
+    /// Whether the bucket has refilled completely, so forgetting it changes nothing
+    pub fn is_full(&self, budget: &RateBudget, now: u64) -> bool {
+        let refilled = now.saturating_sub(self.updated_at) >= u64::from(budget.window_secs) * 1000;
+        refilled || self.tokens >= f64::from(budget.limit)
+    }
+}
+
+/// Rate limit bucket and budget of a request, or `None` if it is not limited
+///
+/// Post search ranks FTS5 `MATCH` results by bm25 and builds snippets, and project
+/// search scans with `LIKE`; both cost D1 far more than a plain read, so search gets
+/// a much smaller budget than other reads.
+pub fn rate_budget(method: &Method, url: &Url) -> Option<(&'static str, RateBudget)> {
+    let budget = |name, limit, window_secs| Some((name, RateBudget { limit, window_secs }));
+    let path = url.path().trim_end_matches('/');
+    let is_search = url.query_pairs().any(|(key, value)| key == "search" && !value.trim().is_empty());
+
+    match method {
+        Method::Options => None,
+        Method::Get | Method::Head => match path {
+            "" | "/openapi.json" | "/v1/health" => None,
+            "/v1/posts" | "/v1/projects" if is_search => budget("search", 20, 60),
+            _ => budget("read", 300, 60),
+        },
+        _ => budget("write", 30, 60),
+    }
+}
+
+/// Durable Object namespace holding one `RateLimiter` per client IP
+const RATE_LIMITER_BINDING: &str = "RATE_LIMITER";
+
+/// Take a token for the client of `req`, or `None` if the client is unknown
+///
+/// Buckets live in the `RateLimiter` Durable Object, so every isolate sees the
+/// same counts. If the binding is missing or the object fails, the isolate's
+/// own buckets are used instead: limits then apply per isolate, which still
+/// stops a single client hammering one location. KV is not used as a fallback
+/// because it allows one write per key per second and is eventually consistent.
+pub async fn check_rate_limit(req: &Request, env: &Env, name: &str, budget: RateBudget) -> Result<Option<RateDecision>> {
+    let Some(ip) = req.headers().get("CF-Connecting-IP")? else {
+        return Ok(None);
+    };
+
+    match take_from_durable_object(env, &ip, name, budget).await {
+        Ok(decision) => Ok(Some(decision)),
+        Err(e) => {
+            console_error!("Rate limiter unavailable, using local buckets: {:?}", e);
+            Ok(Some(take_local(&format!("{}:{}", ip, name), budget)))
+        }
+    }
+}
+
+/// Request sent to the `RateLimiter` Durable Object
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TakeRequest {
+    pub bucket: String,
+    pub budget: RateBudget,
+}
+
+async fn take_from_durable_object(env: &Env, ip: &str, name: &str, budget: RateBudget) -> Result<RateDecision> {
+    let stub = env.durable_object(RATE_LIMITER_BINDING)?.id_from_name(ip)?.get_stub()?;
+
+    let body = serde_json::to_string(&TakeRequest { bucket: name.to_string(), budget })?;
+    let mut init = RequestInit::new();
+    init.with_method(Method::Post).with_body(Some(body.into()));
+    let request = Request::new_with_init("https://rate-limiter/take", &init)?;
+
+    let mut response = stub.fetch_with_request(request).await?;
+    if response.status_code() != 200 {
+        return Err(Error::RustError(format!("Rate limiter returned {}", response.status_code())));
+    }
+    response.json().await
+}
+
+/// Buckets an isolate keeps before it forgets the full ones
+const MAX_LOCAL_BUCKETS: usize = 10_000;
+
+thread_local! {
+    static LOCAL_BUCKETS: RefCell<HashMap<String, (TokenBucket, RateBudget)>> = RefCell::new(HashMap::new());
+}
+
+fn take_local(key: &str, budget: RateBudget) -> RateDecision {
+    let now = Date::now().as_millis();
+    LOCAL_BUCKETS.with(|buckets| {
+        let mut buckets = buckets.borrow_mut();
+        if buckets.len() >= MAX_LOCAL_BUCKETS {
+            buckets.retain(|_, (bucket, budget)| !bucket.is_full(budget, now));
+        }
+        let (bucket, _) = buckets.entry(key.to_string())
+            .or_insert_with(|| (TokenBucket::full(&budget, now), budget));
+        bucket.take(&budget, now)
+    })
+}
+
+/// Add the `RateLimit-*` headers (IETF draft) describing a decision
+pub fn add_rate_limit_headers(mut response: Response, decision: &RateDecision) -> Result<Response> {
+    let headers = response.headers_mut();
+    headers.set("RateLimit-Limit", &decision.limit.to_string())?;
+    headers.set("RateLimit-Remaining", &decision.remaining.to_string())?;
+    headers.set("RateLimit-Reset", &decision.reset_secs.to_string())?;
+    headers.set("RateLimit-Policy", &format!("{};w={}", decision.limit, decision.window_secs))?;
+    Ok(response)
+}
+
+/// `429 Too Many Requests` for a throttled client
+pub fn too_many_requests(decision: &RateDecision) -> Result<Response> {
+    let error = ApiError::too_many_requests(format!(
+        "Rate limit of {} requests per {} seconds exceeded",
+        decision.limit, decision.window_secs
+    ));
+    let mut response = add_rate_limit_headers(error.to_response(429)?, decision)?;
+    response.headers_mut().set("Retry-After", &decision.reset_secs.to_string())?;
+    Ok(response)
+}
+
+/// Worker secret holding the bearer token for write endpoints
+/// Set via: wrangler secret put API_TOKEN
+const API_TOKEN_SECRET: &str = "API_TOKEN";
+
+/// Check the `Authorization: Bearer <token>` header against the API_TOKEN secret
+///
+/// Fails closed: if the secret is not configured, every write request is rejected.
+pub fn authorize(req: &Request, env: &Env) -> std::result::Result<(), ApiError> {
+    let expected = match env.secret(API_TOKEN_SECRET) {
+        Ok(secret) => secret.to_string(),
+        Err(_) => {
+            console_error!("{} secret is not configured; rejecting write request", API_TOKEN_SECRET);
+            return Err(ApiError::unauthorized("Write API is not configured"));
+        }
+    };
+
+    let header = req.headers().get("Authorization").ok().flatten();
+    match header.as_deref().and_then(parse_bearer_token) {
+        Some(token) if !expected.is_empty() && constant_time_eq(token.as_bytes(), expected.as_bytes()) => Ok(()),
+        Some(_) => Err(ApiError::unauthorized("Invalid API token")),
+        None => Err(ApiError::unauthorized("Missing bearer token")),
+    }
+}
+
+/// Extract the token from an `Authorization: Bearer <token>` header value
+fn parse_bearer_token(header: &str) -> Option<&str> {
+    let (scheme, token) = header.trim().split_once(' ')?;
+    let token = token.trim();
+    if scheme.eq_ignore_ascii_case("bearer") && !token.is_empty() {
+        Some(token)
+    } else {
+        None
+    }
+}
+
+/// Compare two byte strings without short-circuiting on the first difference
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bearer_token() {
+        assert_eq!(parse_bearer_token("Bearer abc123"), Some("abc123"));
+        assert_eq!(parse_bearer_token("bearer abc123"), Some("abc123"));
+        assert_eq!(parse_bearer_token("  Bearer   abc123  "), Some("abc123"));
+
+        assert_eq!(parse_bearer_token("Bearer"), None); // Missing token
+        assert_eq!(parse_bearer_token("Bearer "), None); // Empty token
+        assert_eq!(parse_bearer_token("Basic dXNlcjpwYXNz"), None); // Wrong scheme
+        assert_eq!(parse_bearer_token("abc123"), None); // No scheme
+    }
+
+    #[test]
+    fn test_cache_key_normalizes_query() {
+        let url = Url::parse("https://api.werdxz.info/v1/posts?page=2&tags=&limit=10#top").unwrap();
+        assert_eq!(cache_key(&url), "https://api.werdxz.info/v1/posts?limit=10&page=2");
+
+        let url = Url::parse("https://api.werdxz.info/v1/posts/?tags=web%20dev").unwrap();
+        assert_eq!(cache_key(&url), "https://api.werdxz.info/v1/posts?tags=web+dev");
+
+        let url = Url::parse("https://api.werdxz.info/v1/tags?").unwrap();
+        assert_eq!(cache_key(&url), "https://api.werdxz.info/v1/tags");
+    }
+
+    #[test]
+    fn test_cache_policy() {
+        assert_eq!(cache_policy("/v1/posts").unwrap().tags, &[CACHE_TAG_POSTS]);
+        assert_eq!(cache_policy("/v1/posts/hello-world").unwrap().tags, &[CACHE_TAG_POSTS]);
+        assert_eq!(cache_policy("/v1/projects/").unwrap().tags, &[CACHE_TAG_PROJECTS]);
+        assert!(cache_policy("/v1/tags/rust").unwrap().tags.contains(&CACHE_TAG_POSTS));
+        assert_eq!(cache_policy("/v1/resume").unwrap().max_age, 3600);
+
+        assert!(cache_policy("/v1/health").is_none());
+        assert!(cache_policy("/openapi.json").is_none());
+        assert!(cache_policy("/v1/postsx").is_none());
+    }
+
+    #[test]
+    fn test_purged_cache_tags() {
+        assert_eq!(purged_cache_tags("/v1/posts/hello"), &[CACHE_TAG_POSTS]);
+        assert_eq!(purged_cache_tags("/v1/projects/crate"), &[CACHE_TAG_PROJECTS]);
+        assert!(purged_cache_tags("/v1/health").is_empty());
+    }
+
+    #[test]
+    fn test_freshness() {
+        let policy = CachePolicy { max_age: 60, stale_while_revalidate: 600, tags: &[] };
+        let stored_at = 1_000_000;
+
+        assert_eq!(freshness(&policy, stored_at, 0, stored_at + 59_000), Freshness::Fresh);
+        assert_eq!(freshness(&policy, stored_at, 0, stored_at + 60_000), Freshness::Stale);
+        assert_eq!(freshness(&policy, stored_at, 0, stored_at + 660_000), Freshness::Expired);
+        // Purged after it was stored
+        assert_eq!(freshness(&policy, stored_at, stored_at + 1, stored_at + 1_000), Freshness::Expired);
+        // Stored after the last purge
+        assert_eq!(freshness(&policy, stored_at, stored_at - 1, stored_at + 1_000), Freshness::Fresh);
+    }
+
+    #[test]
+    fn test_etag() {
+        assert_eq!(etag(&[b"hello"]), etag(&[b"hello"]));
+        assert_ne!(etag(&[b"hello"]), etag(&[b"hellO"]));
+        assert_ne!(etag(&[b"ab", b"c"]), etag(&[b"a", b"bc"]));
+
+        let tag = etag(&[b"{}"]);
+        assert!(tag.starts_with('"') && tag.ends_with('"'));
+        assert_eq!(tag.len(), 18);
+    }
+
+    #[test]
+    fn test_http_date() {
+        assert_eq!(http_date("2025-01-02 03:04:05").as_deref(), Some("Thu, 02 Jan 2025 03:04:05 GMT"));
+        assert_eq!(http_date("2025-01-02"), None);
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        assert!(etag_matches("\"abc\"", "\"abc\""));
+        assert!(etag_matches("\"x\", W/\"abc\"", "\"abc\""));
+        assert!(etag_matches("*", "\"abc\""));
+        assert!(!etag_matches("\"abd\"", "\"abc\""));
+    }
+
+    #[test]
+    fn test_not_modified() {
+        let last_modified = "Thu, 02 Jan 2025 03:04:05 GMT";
+        let since = |value: &str| Preconditions { if_modified_since: Some(value.to_string()), ..Default::default() };
+
+        assert!(since(last_modified).not_modified(None, Some(last_modified)));
+        assert!(since("Fri, 03 Jan 2025 00:00:00 GMT").not_modified(None, Some(last_modified)));
+        assert!(!since("Wed, 01 Jan 2025 00:00:00 GMT").not_modified(None, Some(last_modified)));
+        assert!(!since("yesterday").not_modified(None, Some(last_modified)));
+
+        // If-None-Match takes precedence over If-Modified-Since
+        let both = Preconditions {
+            if_none_match: Some("\"old\"".to_string()),
+            if_modified_since: Some(last_modified.to_string()),
+        };
+        assert!(!both.not_modified(Some("\"new\""), Some(last_modified)));
+
+        assert!(!Preconditions::default().not_modified(Some("\"abc\""), Some(last_modified)));
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let budget = RateBudget { limit: 2, window_secs: 60 };
+        let mut bucket = TokenBucket::full(&budget, 0);
+
+        let first = bucket.take(&budget, 0);
+        assert!(first.allowed);
+        assert_eq!(first.remaining, 1);
+        assert_eq!(first.reset_secs, 30);
+
+        assert!(bucket.take(&budget, 0).allowed);
+
+        let throttled = bucket.take(&budget, 1_000);
+        assert!(!throttled.allowed);
+        assert_eq!(throttled.remaining, 0);
+        assert_eq!(throttled.reset_secs, 29);
+
+        // One token refills every 30 seconds
+        assert!(bucket.take(&budget, 30_000).allowed);
+        assert!(!bucket.take(&budget, 30_000).allowed);
+        assert!(bucket.is_full(&budget, 90_000));
+    }
+
+    #[test]
+    fn test_rate_budget() {
+        let url = |path: &str| Url::parse(&format!("https://api.werdxz.info{}", path)).unwrap();
+
+        assert_eq!(rate_budget(&Method::Get, &url("/v1/posts?search=rust")).unwrap().0, "search");
+        assert_eq!(rate_budget(&Method::Get, &url("/v1/projects?search=cli")).unwrap().0, "search");
+        assert_eq!(rate_budget(&Method::Get, &url("/v1/posts?search=")).unwrap().0, "read");
+        assert_eq!(rate_budget(&Method::Get, &url("/v1/posts/hello?search=x")).unwrap().0, "read");
+        assert_eq!(rate_budget(&Method::Put, &url("/v1/posts/hello")).unwrap().0, "write");
+
+        assert!(rate_budget(&Method::Options, &url("/v1/posts")).is_none());
+        assert!(rate_budget(&Method::Get, &url("/v1/health")).is_none());
+    }
+
+    #[test]
+    fn test_pick_request_id() {
+        assert_eq!(pick_request_id(Some("abc-123"), Some("8f1a2b3c4d5e6f70-SJC")).as_deref(), Some("abc-123"));
+        assert_eq!(pick_request_id(None, Some("8f1a2b3c4d5e6f70-SJC")).as_deref(), Some("8f1a2b3c4d5e6f70-SJC"));
+        assert_eq!(pick_request_id(Some("  trace:1.2_x  "), None).as_deref(), Some("trace:1.2_x"));
+        assert_eq!(pick_request_id(None, None), None);
+
+        // Unusable incoming IDs fall through to cf-ray
+        assert_eq!(pick_request_id(Some(""), Some("ray")).as_deref(), Some("ray"));
+        assert_eq!(pick_request_id(Some("a b"), Some("ray")).as_deref(), Some("ray"));
+        assert_eq!(pick_request_id(Some("a\nINFO forged"), None), None);
+        assert_eq!(pick_request_id(Some(&"x".repeat(MAX_REQUEST_ID_LEN + 1)), None), None);
+        assert!(pick_request_id(Some(&"x".repeat(MAX_REQUEST_ID_LEN)), None).is_some());
+    }
+
+    fn cors(vars: &[(&str, &str)]) -> CorsPolicy {
+        CorsPolicy::from_vars(|name| vars.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string()))
+    }
+
+    #[test]
+    fn test_cors_default_origins() {
+        let policy = cors(&[]);
+        let allow = |origin| policy.allow_origin(Some(origin));
+
+        assert_eq!(allow("https://werdxz.info").as_deref(), Some("https://werdxz.info"));
+        assert_eq!(allow("https://blog.werdxz.info").as_deref(), Some("https://blog.werdxz.info"));
+        assert_eq!(allow("https://a.b.werdxz.info").as_deref(), Some("https://a.b.werdxz.info"));
+
+        assert_eq!(allow("http://blog.werdxz.info"), None); // HTTPS only
+        assert_eq!(allow("https://evilwerdxz.info"), None);
+        assert_eq!(allow("https://werdxz.info.evil.com"), None);
+        assert_eq!(allow("https://.werdxz.info"), None);
+        assert_eq!(allow("https://evil.com/.werdxz.info"), None);
+        assert_eq!(allow("https://user@x.werdxz.info"), None);
+        assert_eq!(policy.allow_origin(None), None);
+    }
+
+    #[test]
+    fn test_cors_configured_origins() {
+        let policy = cors(&[("CORS_ALLOWED_ORIGINS", "http://localhost:3000, https://*.pages.dev")]);
+        assert!(policy.allow_origin(Some("http://localhost:3000")).is_some());
+        assert!(policy.allow_origin(Some("https://preview.pages.dev")).is_some());
+        assert!(policy.allow_origin(Some("http://localhost:3001")).is_none());
+        assert!(policy.allow_origin(Some("https://werdxz.info")).is_none());
+
+        // Wildcards never stand in for a port
+        let policy = cors(&[("CORS_ALLOWED_ORIGINS", "http://localhost*")]);
+        assert!(policy.allow_origin(Some("http://localhost:8787")).is_none());
+    }
+
+    #[test]
+    fn test_cors_any_origin_and_credentials() {
+        let policy = cors(&[("CORS_ALLOWED_ORIGINS", "*")]);
+        assert_eq!(policy.allow_origin(Some("https://example.com")).as_deref(), Some("*"));
+        assert_eq!(policy.allow_origin(None).as_deref(), Some("*"));
+        assert!(policy.is_public());
+
+        // Credentialed responses must echo the origin instead of `*`
+        let policy = cors(&[("CORS_ALLOWED_ORIGINS", "*"), ("CORS_ALLOW_CREDENTIALS", "true")]);
+        assert_eq!(policy.allow_origin(Some("https://example.com")).as_deref(), Some("https://example.com"));
+        assert_eq!(policy.allow_origin(None), None);
+        assert!(!policy.is_public());
+
+        let headers = policy.response_headers(Some("https://example.com"));
+        assert!(headers.contains(&("Access-Control-Allow-Credentials", "true".to_string())));
+    }
+
+    #[test]
+    fn test_cors_response_headers() {
+        let policy = cors(&[]);
+        let headers = policy.response_headers(Some("https://werdxz.info"));
+        assert!(headers.contains(&("Access-Control-Allow-Origin", "https://werdxz.info".to_string())));
+        assert!(headers.iter().any(|(name, value)| *name == "Access-Control-Expose-Headers" && value.contains("ETag")));
+        assert!(!headers.iter().any(|(name, _)| *name == "Access-Control-Allow-Credentials"));
+
+        assert!(policy.response_headers(Some("https://example.com")).is_empty());
+        assert!(policy.response_headers(None).is_empty());
+    }
+
+    #[test]
+    fn test_cors_preflight_headers() {
+        let policy = cors(&[("CORS_ALLOWED_METHODS", "GET, PATCH, OPTIONS"), ("CORS_MAX_AGE", "600")]);
+        let origin = Some("https://werdxz.info");
+
+        let headers = policy.preflight_headers(origin, Some("patch"));
+        assert_eq!(headers, vec![
+            ("Access-Control-Allow-Origin", "https://werdxz.info".to_string()),
+            ("Access-Control-Allow-Methods", "GET, PATCH, OPTIONS".to_string()),
+            ("Access-Control-Allow-Headers", "Authorization, Content-Type, X-Request-ID".to_string()),
+            ("Access-Control-Max-Age", "600".to_string()),
+        ]);
+
+        assert!(policy.preflight_headers(origin, Some("DELETE")).is_empty()); // Method not allowed
+        assert!(policy.preflight_headers(origin, None).is_empty()); // Not a preflight
+        assert!(policy.preflight_headers(Some("https://example.com"), Some("GET")).is_empty());
+        assert!(policy.preflight_headers(None, Some("GET")).is_empty());
+    }
+
+    #[test]
+    fn test_cors_default_preflight_allows_writes() {
+        // The write endpoints need their methods and `Authorization` allowed by default
+        let headers = cors(&[]).preflight_headers(Some("https://werdxz.info"), Some("DELETE"));
+        let header = |name| headers.iter().find(|(n, _)| *n == name).map(|(_, v)| v.as_str());
+        assert_eq!(header("Access-Control-Allow-Methods"), Some("GET, HEAD, POST, PUT, PATCH, DELETE, OPTIONS"));
+        assert_eq!(header("Access-Control-Allow-Headers"), Some("Authorization, Content-Type, X-Request-ID"));
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+}
@@ -1,4 +0,0 @@
-pub mod post;
-pub mod project;
-pub mod resume;
-pub mod tag;
@@ -1,9 +1,11 @@
-use utoipa::OpenApi;
-use crate::models::post::{Post, PostListItem, PostsResponse, Pagination};
-use crate::models::project::{Project as ProjectModel, ProjectUrl, ProjectsResponse};
-use crate::models::resume::{Resume, Personal, Experience, Education, Project, Extracurricular};
-use crate::models::tag::TagWithCount;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use models::post::{CreatePostRequest, Post, PostInput, PostListItem, PostStatus, PostsResponse, Pagination, TocEntry};
+use models::project::{Project as ProjectModel, ProjectPatch, ProjectStage, ProjectUrl, ProjectsResponse};
+use models::resume::{Resume, Personal, Experience, Education, Project, Extracurricular};
+use models::tag::{TagDetail, TagWithCount};
 use crate::routes::meta::{HealthResponse, ServiceStatus, ApiInfoResponse, ApiEndpoints};
+use crate::routes::SlugRedirect;
 
 #[derive(OpenApi)]
 #[openapi(
@@ -25,19 +27,35 @@ use crate::routes::meta::{HealthResponse, ServiceStatus, ApiInfoResponse, ApiEnd
         crate::routes::meta::handle_health,
         crate::routes::posts::handle_list_posts,
         crate::routes::posts::handle_get_post,
-        crate::routes::posts::handle_get_tags,
+        crate::routes::posts::handle_create_post,
+        crate::routes::posts::handle_update_post,
+        crate::routes::posts::handle_delete_post,
+        crate::routes::feeds::handle_rss_feed,
+        crate::routes::feeds::handle_atom_feed,
+        crate::routes::feeds::handle_json_feed,
         crate::routes::projects::handle_list_projects,
         crate::routes::projects::handle_get_project,
+        crate::routes::projects::handle_patch_project,
+        crate::routes::tags::handle_list_tags,
+        crate::routes::tags::handle_get_tag,
         crate::routes::resume::handle_get_resume,
     ),
     components(
         schemas(
             Post,
+            PostStatus,
+            TocEntry,
             PostListItem,
             PostsResponse,
             Pagination,
+            PostInput,
+            CreatePostRequest,
             TagWithCount,
+            TagDetail,
+            SlugRedirect,
             ProjectModel,
+            ProjectStage,
+            ProjectPatch,
             ProjectUrl,
             ProjectsResponse,
             Resume,
@@ -52,15 +70,31 @@ use crate::routes::meta::{HealthResponse, ServiceStatus, ApiInfoResponse, ApiEnd
             ApiEndpoints,
         )
     ),
+    modifiers(&SecurityAddon),
     tags(
         (name = "meta", description = "API metadata and health endpoints"),
         (name = "posts", description = "Blog post management"),
         (name = "projects", description = "Open-source projects and documentation"),
+        (name = "tags", description = "Tags shared by posts and projects"),
         (name = "resume", description = "Resume data and filtering")
     )
 )]
 pub struct ApiDoc;
 
+/// Registers the bearer token scheme used by write endpoints
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer_auth",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+    }
+}
+
 pub fn get_openapi_spec() -> String {
     ApiDoc::openapi().to_pretty_json().unwrap_or_else(|_| {
         r#"{"error": "Failed to generate OpenAPI specification"}"#.to_string()
@@ -0,0 +1,493 @@
+use worker::*;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::Serialize;
+use crate::storage::d1::ListPostsParams;
+use crate::storage::blog::{list_posts_with_pagination, get_post_content};
+use crate::storage::markdown::render_markdown;
+use crate::errors::ApiError;
+use crate::logging::log_error;
+use crate::middleware::{RequestContext, etag};
+use models::post::PostListItem;
+use models::tag::Tag;
+
+/// Public blog frontend, used for post permalinks
+const BLOG_BASE_URL: &str = "https://blog.werdxz.info";
+
+const FEED_TITLE: &str = "werdxz blog";
+const FEED_DESCRIPTION: &str = "Writing on software engineering by WERDXZ";
+const FEED_AUTHOR: &str = "WERDXZ";
+
+/// Number of posts in a feed unless `?limit=` says otherwise
+const DEFAULT_FEED_LIMIT: u32 = 20;
+
+/// Syndication format served by a feed endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Rss => "application/rss+xml; charset=utf-8",
+            Self::Atom => "application/atom+xml; charset=utf-8",
+            Self::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
+
+/// Query parameters accepted by the feed endpoints
+#[derive(Debug, Default)]
+struct FeedParams {
+    limit: Option<u32>,
+    tags: Option<Vec<String>>,
+    full: bool,
+}
+
+/// Feed-ready view of a post
+struct FeedEntry {
+    url: String,
+    title: String,
+    summary: String,
+    published: DateTime<Utc>,
+    tags: Vec<String>,
+    external_url: Option<String>,
+    /// Rendered and sanitized body, only present for `?full=true`
+    content_html: Option<String>,
+}
+
+/// Channel-level metadata shared by all formats
+struct Feed {
+    self_url: String,
+    updated: DateTime<Utc>,
+    entries: Vec<FeedEntry>,
+}
+
+/// RSS 2.0 feed of recent posts
+#[utoipa::path(
+    get,
+    path = "/v1/feed.xml",
+    tag = "posts",
+    params(
+        ("tags" = Option<String>, Query, description = "Only include posts with these tags (comma-separated)"),
+        ("full" = Option<bool>, Query, description = "Include the full rendered HTML body of each post (default: false)"),
+        ("limit" = Option<u32>, Query, description = "Number of posts (default: 20, max: 50)"),
+    ),
+    responses(
+        (status = 200, description = "RSS 2.0 feed", content_type = "application/rss+xml", body = String)
+    )
+)]
+pub async fn handle_rss_feed(req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
+    handle_feed(req, ctx, FeedFormat::Rss).await
+}
+
+/// Atom 1.0 feed of recent posts
+#[utoipa::path(
+    get,
+    path = "/v1/feed.atom",
+    tag = "posts",
+    params(
+        ("tags" = Option<String>, Query, description = "Only include posts with these tags (comma-separated)"),
+        ("full" = Option<bool>, Query, description = "Include the full rendered HTML body of each post (default: false)"),
+        ("limit" = Option<u32>, Query, description = "Number of posts (default: 20, max: 50)"),
+    ),
+    responses(
+        (status = 200, description = "Atom 1.0 feed", content_type = "application/atom+xml", body = String)
+    )
+)]
+pub async fn handle_atom_feed(req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
+    handle_feed(req, ctx, FeedFormat::Atom).await
+}
+
+/// JSON Feed 1.1 of recent posts
+#[utoipa::path(
+    get,
+    path = "/v1/feed.json",
+    tag = "posts",
+    params(
+        ("tags" = Option<String>, Query, description = "Only include posts with these tags (comma-separated)"),
+        ("full" = Option<bool>, Query, description = "Include the full rendered HTML body of each post (default: false)"),
+        ("limit" = Option<u32>, Query, description = "Number of posts (default: 20, max: 50)"),
+    ),
+    responses(
+        (status = 200, description = "JSON Feed 1.1", content_type = "application/feed+json", body = String)
+    )
+)]
+pub async fn handle_json_feed(req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
+    handle_feed(req, ctx, FeedFormat::Json).await
+}
+
+async fn handle_feed(req: Request, ctx: RouteContext<RequestContext>, format: FeedFormat) -> Result<Response> {
+    let db = ctx.env.d1("DB")?;
+
+    let url = req.url()?;
+    let params = parse_feed_params(&url);
+
+    let list_params = ListPostsParams {
+        limit: params.limit.unwrap_or(DEFAULT_FEED_LIMIT),
+        tags: params.tags,
+        ..Default::default()
+    };
+
+    let posts = match list_posts_with_pagination(&db, &list_params).await {
+        Ok(response) => response.posts,
+        Err(e) => {
+            let error = ApiError::internal_error("Unable to load feed");
+            error.log(&ctx.data.request_id, &format!("Failed to list posts for feed: {:?}", e));
+            return error.to_response(500);
+        }
+    };
+
+    // Full-content feeds pull each body from R2
+    let bucket = if params.full {
+        Some(ctx.env.bucket("CONTENT_BUCKET")?)
+    } else {
+        None
+    };
+
+    let mut entries = Vec::with_capacity(posts.len());
+    for post in posts {
+        let content_html = match &bucket {
+            Some(bucket) => match get_post_content(bucket, &post.content_id, &post.slug).await {
+                Ok(content) => content.map(|markdown| render_markdown(&markdown).html),
+                Err(e) => {
+                    // A missing body degrades to a summary-only entry rather than failing the feed
+                    log_error(&ctx.data.request_id, &format!("Failed to load content for feed entry '{}': {:?}", post.slug, e), None);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let mut entry = FeedEntry::from(post);
+        entry.content_html = content_html;
+        entries.push(entry);
+    }
+
+    let feed = Feed {
+        self_url: url.to_string(),
+        updated: entries.first().map(|e| e.published).unwrap_or_else(Utc::now),
+        entries,
+    };
+
+    let body = match format {
+        FeedFormat::Rss => render_rss(&feed),
+        FeedFormat::Atom => render_atom(&feed),
+        FeedFormat::Json => render_json_feed(&feed)?,
+    };
+
+    let etag = etag(&[body.as_bytes()]);
+    let mut response = Response::ok(body)?;
+    response.headers_mut().set("Content-Type", format.content_type())?;
+    response.headers_mut().set("ETag", &etag)?;
+    Ok(response)
+}
+
+impl From<PostListItem> for FeedEntry {
+    fn from(post: PostListItem) -> Self {
+        Self {
+            url: format!("{}/posts/{}", BLOG_BASE_URL, post.slug),
+            published: parse_timestamp(&post.published_at).unwrap_or_else(Utc::now),
+            title: post.title,
+            summary: post.summary,
+            tags: post.tags,
+            external_url: post.external_url,
+            content_html: None,
+        }
+    }
+}
+
+/// Parse feed query parameters, ignoring invalid values
+fn parse_feed_params(url: &Url) -> FeedParams {
+    let mut params = FeedParams::default();
+
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "limit" => {
+                if let Ok(l) = value.parse::<u32>() {
+                    params.limit = Some(l.clamp(1, 50));
+                }
+            }
+            "tags" => {
+                let tags = Tag::parse_many(&value);
+                if !tags.is_empty() {
+                    params.tags = Some(tags.iter().map(|t| t.to_string()).collect());
+                }
+            }
+            "full" => {
+                params.full = matches!(value.as_ref(), "true" | "1");
+            }
+            _ => {}
+        }
+    }
+
+    params
+}
+
+/// Parse a D1 timestamp (`YYYY-MM-DD HH:MM:SS`, RFC 3339 or a bare date) as UTC
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(naive) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+        return Some(naive.and_utc());
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|naive| naive.and_utc())
+}
+
+/// Escape text for use in XML element content and attribute values
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn render_rss(feed: &Feed) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" \
+                  xmlns:content=\"http://purl.org/rss/1.0/modules/content/\">\n<channel>\n");
+    xml.push_str(&format!("<title>{}</title>\n", xml_escape(FEED_TITLE)));
+    xml.push_str(&format!("<link>{}</link>\n", BLOG_BASE_URL));
+    xml.push_str(&format!("<description>{}</description>\n", xml_escape(FEED_DESCRIPTION)));
+    xml.push_str(&format!(
+        "<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        xml_escape(&feed.self_url)
+    ));
+    xml.push_str(&format!("<lastBuildDate>{}</lastBuildDate>\n", feed.updated.to_rfc2822()));
+
+    for entry in &feed.entries {
+        xml.push_str("<item>\n");
+        xml.push_str(&format!("<title>{}</title>\n", xml_escape(&entry.title)));
+        xml.push_str(&format!("<link>{}</link>\n", xml_escape(&entry.url)));
+        xml.push_str(&format!("<guid isPermaLink=\"true\">{}</guid>\n", xml_escape(&entry.url)));
+        xml.push_str(&format!("<pubDate>{}</pubDate>\n", entry.published.to_rfc2822()));
+        if !entry.summary.is_empty() {
+            xml.push_str(&format!("<description>{}</description>\n", xml_escape(&entry.summary)));
+        }
+        if let Some(content_html) = &entry.content_html {
+            xml.push_str(&format!("<content:encoded>{}</content:encoded>\n", xml_escape(content_html)));
+        }
+        for tag in &entry.tags {
+            xml.push_str(&format!("<category>{}</category>\n", xml_escape(tag)));
+        }
+        xml.push_str("</item>\n");
+    }
+
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+fn render_atom(feed: &Feed) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("<title>{}</title>\n", xml_escape(FEED_TITLE)));
+    xml.push_str(&format!("<subtitle>{}</subtitle>\n", xml_escape(FEED_DESCRIPTION)));
+    xml.push_str(&format!("<id>{}/</id>\n", BLOG_BASE_URL));
+    xml.push_str(&format!("<link href=\"{}\"/>\n", BLOG_BASE_URL));
+    xml.push_str(&format!("<link rel=\"self\" href=\"{}\"/>\n", xml_escape(&feed.self_url)));
+    xml.push_str(&format!("<updated>{}</updated>\n", feed.updated.to_rfc3339()));
+    xml.push_str(&format!("<author><name>{}</name></author>\n", xml_escape(FEED_AUTHOR)));
+
+    for entry in &feed.entries {
+        let published = entry.published.to_rfc3339();
+        xml.push_str("<entry>\n");
+        xml.push_str(&format!("<title>{}</title>\n", xml_escape(&entry.title)));
+        xml.push_str(&format!("<link href=\"{}\"/>\n", xml_escape(&entry.url)));
+        if let Some(external_url) = &entry.external_url {
+            xml.push_str(&format!("<link rel=\"related\" href=\"{}\"/>\n", xml_escape(external_url)));
+        }
+        xml.push_str(&format!("<id>{}</id>\n", xml_escape(&entry.url)));
+        xml.push_str(&format!("<published>{}</published>\n", published));
+        xml.push_str(&format!("<updated>{}</updated>\n", published));
+        if !entry.summary.is_empty() {
+            xml.push_str(&format!("<summary>{}</summary>\n", xml_escape(&entry.summary)));
+        }
+        if let Some(content_html) = &entry.content_html {
+            xml.push_str(&format!("<content type=\"html\">{}</content>\n", xml_escape(content_html)));
+        }
+        for tag in &entry.tags {
+            xml.push_str(&format!("<category term=\"{}\"/>\n", xml_escape(tag)));
+        }
+        xml.push_str("</entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+/// JSON Feed 1.1 document (https://www.jsonfeed.org/version/1.1/)
+#[derive(Serialize)]
+struct JsonFeed<'a> {
+    version: &'static str,
+    title: &'static str,
+    home_page_url: &'static str,
+    feed_url: &'a str,
+    description: &'static str,
+    authors: Vec<JsonFeedAuthor>,
+    items: Vec<JsonFeedItem<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAuthor {
+    name: &'static str,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem<'a> {
+    id: &'a str,
+    url: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_url: Option<&'a str>,
+    title: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    summary: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_html: Option<&'a str>,
+    /// JSON Feed requires a content field, so summary-only items repeat the summary
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_text: Option<&'a str>,
+    date_published: String,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    tags: &'a [String],
+}
+
+fn render_json_feed(feed: &Feed) -> Result<String> {
+    let json_feed = JsonFeed {
+        version: "https://jsonfeed.org/version/1.1",
+        title: FEED_TITLE,
+        home_page_url: BLOG_BASE_URL,
+        feed_url: &feed.self_url,
+        description: FEED_DESCRIPTION,
+        authors: vec![JsonFeedAuthor { name: FEED_AUTHOR }],
+        items: feed.entries.iter().map(|entry| JsonFeedItem {
+            id: &entry.url,
+            url: &entry.url,
+            external_url: entry.external_url.as_deref(),
+            title: &entry.title,
+            summary: &entry.summary,
+            content_html: entry.content_html.as_deref(),
+            content_text: entry.content_html.is_none().then_some(entry.summary.as_str()),
+            date_published: entry.published.to_rfc3339(),
+            tags: &entry.tags,
+        }).collect(),
+    };
+
+    serde_json::to_string(&json_feed).map_err(|e| Error::RustError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_feed(content_html: Option<&str>) -> Feed {
+        let published = parse_timestamp("2025-01-15 09:30:00").unwrap();
+        Feed {
+            self_url: "https://api.werdxz.info/v1/feed.xml?tags=rust&full=true".to_string(),
+            updated: published,
+            entries: vec![FeedEntry {
+                url: "https://blog.werdxz.info/posts/hello".to_string(),
+                title: "Rust & <Friends>".to_string(),
+                summary: "A \"short\" intro".to_string(),
+                published,
+                tags: vec!["rust".to_string(), "web-dev".to_string()],
+                external_url: None,
+                content_html: content_html.map(str::to_string),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_parse_feed_params() {
+        let url = Url::parse("https://api.werdxz.info/v1/feed.xml?tags=rust,bad.tag&full=true&limit=500").unwrap();
+        let params = parse_feed_params(&url);
+        assert_eq!(params.tags, Some(vec!["rust".to_string()]));
+        assert!(params.full);
+        assert_eq!(params.limit, Some(50));
+
+        let url = Url::parse("https://api.werdxz.info/v1/feed.xml?full=yes").unwrap();
+        let params = parse_feed_params(&url);
+        assert!(!params.full);
+        assert!(params.tags.is_none());
+        assert!(params.limit.is_none());
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let expected = "2025-01-15T09:30:00+00:00";
+        assert_eq!(parse_timestamp("2025-01-15 09:30:00").unwrap().to_rfc3339(), expected);
+        assert_eq!(parse_timestamp("2025-01-15T10:30:00+01:00").unwrap().to_rfc3339(), expected);
+        assert_eq!(parse_timestamp("2025-01-15").unwrap().to_rfc3339(), "2025-01-15T00:00:00+00:00");
+        assert!(parse_timestamp("yesterday").is_none());
+    }
+
+    #[test]
+    fn test_xml_escape() {
+        assert_eq!(xml_escape("a & b <c> \"d\" 'e'"), "a &amp; b &lt;c&gt; &quot;d&quot; &apos;e&apos;");
+        assert_eq!(xml_escape("plain"), "plain");
+    }
+
+    #[test]
+    fn test_render_rss() {
+        let xml = render_rss(&sample_feed(None));
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert!(xml.contains("<title>Rust &amp; &lt;Friends&gt;</title>"));
+        assert!(xml.contains("<guid isPermaLink=\"true\">https://blog.werdxz.info/posts/hello</guid>"));
+        assert!(xml.contains("<pubDate>Wed, 15 Jan 2025 09:30:00 +0000</pubDate>"));
+        assert!(xml.contains("<description>A &quot;short&quot; intro</description>"));
+        assert!(xml.contains("<category>web-dev</category>"));
+        assert!(xml.contains("href=\"https://api.werdxz.info/v1/feed.xml?tags=rust&amp;full=true\""));
+    }
+
+    #[test]
+    fn test_render_rss_full_content() {
+        let xml = render_rss(&sample_feed(Some("<h1 id=\"hello\">Hello</h1>")));
+        assert!(xml.contains("<description>A &quot;short&quot; intro</description>"));
+        assert!(xml.contains("<content:encoded>&lt;h1 id=&quot;hello&quot;&gt;Hello&lt;/h1&gt;</content:encoded>"));
+    }
+
+    #[test]
+    fn test_render_atom() {
+        let xml = render_atom(&sample_feed(Some("<p>Body</p>")));
+        assert!(xml.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+        assert!(xml.contains("<updated>2025-01-15T09:30:00+00:00</updated>"));
+        assert!(xml.contains("<id>https://blog.werdxz.info/posts/hello</id>"));
+        assert!(xml.contains("<summary>A &quot;short&quot; intro</summary>"));
+        assert!(xml.contains("<content type=\"html\">&lt;p&gt;Body&lt;/p&gt;</content>"));
+        assert!(xml.contains("<category term=\"rust\"/>"));
+        assert!(xml.contains("<author><name>WERDXZ</name></author>"));
+    }
+
+    #[test]
+    fn test_render_json_feed() {
+        let json = render_json_feed(&sample_feed(None)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], "https://jsonfeed.org/version/1.1");
+        let item = &value["items"][0];
+        assert_eq!(item["id"], "https://blog.werdxz.info/posts/hello");
+        assert_eq!(item["title"], "Rust & <Friends>");
+        assert_eq!(item["content_text"], "A \"short\" intro");
+        assert_eq!(item["date_published"], "2025-01-15T09:30:00+00:00");
+        assert_eq!(item["tags"][1], "web-dev");
+        assert!(item.get("external_url").is_none());
+        assert!(item.get("content_html").is_none());
+
+        let json = render_json_feed(&sample_feed(Some("<p>Body</p>"))).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["items"][0]["content_html"], "<p>Body</p>");
+        assert!(value["items"][0].get("content_text").is_none());
+    }
+}
@@ -2,6 +2,7 @@ use worker::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use utoipa::ToSchema;
+use crate::middleware::RequestContext;
 
 /// Simple health check result for D1
 #[derive(Deserialize)]
@@ -55,7 +56,7 @@ pub struct ApiEndpoints {
         (status = 503, description = "Service is unhealthy", body = HealthResponse)
     )
 )]
-pub async fn handle_health(_req: Request, ctx: RouteContext<()>) -> Result<Response> {
+pub async fn handle_health(_req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
     let mut services = HashMap::new();
     let mut all_healthy = true;
 
@@ -106,7 +107,7 @@ pub async fn handle_health(_req: Request, ctx: RouteContext<()>) -> Result<Respo
 }
 
 /// Check D1 database connectivity
-async fn check_d1(ctx: &RouteContext<()>) -> Result<()> {
+async fn check_d1(ctx: &RouteContext<RequestContext>) -> Result<()> {
     let db = ctx.env.d1("DB")?;
     // Simple query to check connectivity
     let _ = db.prepare("SELECT 1 as result").first::<HealthCheck>(None).await?;
@@ -114,14 +115,14 @@ async fn check_d1(ctx: &RouteContext<()>) -> Result<()> {
 }
 
 /// Check R2 bucket connectivity
-async fn check_r2(ctx: &RouteContext<()>) -> Result<()> {
+async fn check_r2(ctx: &RouteContext<RequestContext>) -> Result<()> {
     let _bucket = ctx.env.bucket("CONTENT_BUCKET")?;
     // Just checking if we can get the binding is enough
     Ok(())
 }
 
 /// Check KV namespace connectivity
-async fn check_kv(ctx: &RouteContext<()>) -> Result<()> {
+async fn check_kv(ctx: &RouteContext<RequestContext>) -> Result<()> {
     let _kv = ctx.env.kv("RESUME_KV")?;
     // Just checking if we can get the binding is enough
     Ok(())
@@ -136,7 +137,7 @@ async fn check_kv(ctx: &RouteContext<()>) -> Result<()> {
         (status = 200, description = "API service information", body = ApiInfoResponse)
     )
 )]
-pub async fn handle_root(_req: Request, _ctx: RouteContext<()>) -> Result<Response> {
+pub async fn handle_root(_req: Request, _ctx: RouteContext<RequestContext>) -> Result<Response> {
     let info = ApiInfoResponse {
         service: "werdxz-api".to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
@@ -152,7 +153,7 @@ pub async fn handle_root(_req: Request, _ctx: RouteContext<()>) -> Result<Respon
     Response::from_json(&info)
 }
 
-pub async fn handle_openapi_spec(_req: Request, _ctx: RouteContext<()>) -> Result<Response> {
+pub async fn handle_openapi_spec(_req: Request, _ctx: RouteContext<RequestContext>) -> Result<Response> {
     let spec = crate::openapi::get_openapi_spec();
     Response::from_html(spec)
         .map(|mut r| {
@@ -1,4 +1,33 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+use worker::*;
+
+pub mod feeds;
 pub mod meta;
 pub mod posts;
 pub mod projects;
 pub mod resume;
+pub mod tags;
+
+/// Body of a 301 response for a renamed resource
+#[derive(Serialize, ToSchema)]
+pub struct SlugRedirect {
+    /// Canonical path of the resource, including the original query string
+    pub redirect_to: String,
+}
+
+/// Permanent redirect from an old slug to `path`, keeping the request's query string
+///
+/// The target is sent both as a `Location` header and as `redirect_to` in the
+/// body, for clients that don't follow redirects.
+pub fn moved_permanently(url: &Url, path: &str) -> Result<Response> {
+    let redirect_to = match url.query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path.to_string(),
+    };
+
+    let mut response = Response::from_json(&SlugRedirect { redirect_to: redirect_to.clone() })?
+        .with_status(301);
+    response.headers_mut().set("Location", &redirect_to)?;
+    Ok(response)
+}
@@ -1,9 +1,13 @@
 use worker::*;
-use crate::storage::d1::ListPostsParams;
-use crate::storage::blog::{list_posts_with_pagination, get_full_post};
-use crate::storage::d1::get_all_tags as d1_get_all_tags;
+use crate::storage::d1::{ListPostsParams, SlugKind, Visibility, get_post_content_id, resolve_slug_alias};
+use crate::storage::blog::{ContentFormat, list_posts_with_pagination, get_full_post, create_post, update_post, delete_post};
 use crate::errors::ApiError;
-use crate::models::tag::Tag;
+use crate::logging::log_error;
+use crate::middleware::{RequestContext, authorize, json_with_etag, set_last_modified};
+use crate::routes::moved_permanently;
+use models::post::{CreatePostRequest, PostInput, PostStatus};
+use models::tag::Tag;
+use models::Slug;
 
 /// List blog posts with pagination and filtering
 #[utoipa::path(
@@ -14,15 +18,15 @@ use crate::models::tag::Tag;
         ("page" = Option<u32>, Query, description = "Page number (default: 1)"),
         ("limit" = Option<u32>, Query, description = "Items per page (default: 10, max: 50)"),
         ("tags" = Option<String>, Query, description = "Filter by tags (comma-separated)"),
-        ("search" = Option<String>, Query, description = "Search in title and summary"),
-        ("sort" = Option<String>, Query, description = "Sort field: published_at or title (default: published_at)"),
+        ("search" = Option<String>, Query, description = "Full-text search over title, summary and body; results are ranked by relevance and include a highlighted snippet"),
+        ("sort" = Option<String>, Query, description = "Sort field: published_at or title (default: published_at; tie-breaker when searching)"),
         ("order" = Option<String>, Query, description = "Sort order: asc or desc (default: desc)"),
     ),
     responses(
-        (status = 200, description = "List of blog posts", body = crate::models::post::PostsResponse)
+        (status = 200, description = "List of blog posts", body = models::post::PostsResponse)
     )
 )]
-pub async fn handle_list_posts(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+pub async fn handle_list_posts(req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
     // Get D1 database binding
     let db = ctx.env.d1("DB")?;
 
@@ -32,31 +36,34 @@ pub async fn handle_list_posts(req: Request, ctx: RouteContext<()>) -> Result<Re
 
     // Query posts
     match list_posts_with_pagination(&db, &params).await {
-        Ok(response) => Response::from_json(&response),
-        Err(_e) => {
-            // Log detailed error server-side (when error logging is fully implemented)
-            // console_error!("Failed to list posts: {:?}", e);
+        Ok(response) => json_with_etag(&response),
+        Err(e) => {
             let error = ApiError::internal_error("Unable to load posts");
+            error.log(&ctx.data.request_id, &format!("Failed to list posts: {:?}", e));
             error.to_response(500)
         }
     }
 }
 
 /// Get a single blog post by slug
+///
+/// Drafts and future-dated posts are only returned to requests carrying a valid API token.
 #[utoipa::path(
     get,
     path = "/v1/posts/{slug}",
     tag = "posts",
     params(
-        ("slug" = String, Path, description = "URL slug of the post")
+        ("slug" = String, Path, description = "URL slug of the post"),
+        ("format" = Option<String>, Query, description = "Body format: markdown (raw `content`) or html (sanitized `content_html` plus `toc`). Default: markdown"),
     ),
     responses(
-        (status = 200, description = "Full blog post with content", body = crate::models::post::Post),
-        (status = 400, description = "Invalid slug format"),
+        (status = 200, description = "Full blog post with content", body = models::post::Post),
+        (status = 301, description = "Post was renamed; `Location` points at its current slug", body = crate::routes::SlugRedirect),
+        (status = 400, description = "Invalid slug format or unknown format"),
         (status = 404, description = "Post not found")
     )
 )]
-pub async fn handle_get_post(_req: Request, ctx: RouteContext<()>) -> Result<Response> {
+pub async fn handle_get_post(req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
     let slug = ctx.param("slug").ok_or_else(|| Error::RustError("Missing slug".to_string()))?;
 
     // Validate slug format (security: prevent path traversal, SQL injection attempts)
@@ -65,33 +72,322 @@ pub async fn handle_get_post(_req: Request, ctx: RouteContext<()>) -> Result<Res
         return error.to_response(400);
     }
 
+    let url = req.url()?;
+    let format = match url.query_pairs().find(|(key, _)| key == "format") {
+        Some((_, value)) => match ContentFormat::from_str(&value) {
+            Some(format) => format,
+            None => {
+                let error = ApiError::bad_request("Invalid format (expected markdown or html)");
+                return error.to_response(400);
+            }
+        },
+        None => ContentFormat::Markdown,
+    };
+
     // Get D1 database and R2 bucket bindings
     let db = ctx.env.d1("DB")?;
     let bucket = ctx.env.bucket("CONTENT_BUCKET")?;
 
+    // Authenticated callers may preview drafts and scheduled posts
+    let has_token = req.headers().has("Authorization")?;
+    let visibility = if has_token && authorize(&req, &ctx.env).is_ok() {
+        Visibility::All
+    } else {
+        Visibility::Public
+    };
+
     // Get post with content
-    match get_full_post(&db, &bucket, slug).await {
+    match get_full_post(&db, &bucket, slug, visibility, format).await {
+        Ok(Some(post)) => {
+            // Hash the whole body: tag merges and slug renames change it without bumping updated_at
+            let mut response = json_with_etag(&post)?;
+            set_last_modified(&mut response, &post.updated_at)?;
+            Ok(response)
+        }
+        // The slug may belong to a renamed post
+        Ok(None) => match resolve_slug_alias(&db, SlugKind::Post, slug, visibility).await {
+            Ok(Some(canonical)) => moved_permanently(&url, &format!("/v1/posts/{}", canonical)),
+            Ok(None) => {
+                let error = ApiError::not_found("Post");
+                error.to_response(404)
+            }
+            Err(e) => {
+                let error = ApiError::internal_error("Unable to load post");
+                error.log(&ctx.data.request_id, &format!("Failed to resolve post alias '{}': {:?}", slug, e));
+                error.to_response(500)
+            }
+        },
+        Err(e) => {
+            let error = ApiError::internal_error("Unable to load post");
+            error.log(&ctx.data.request_id, &format!("Failed to get post '{}': {:?}", slug, e));
+            error.to_response(500)
+        }
+    }
+}
+
+/// Create a new blog post
+#[utoipa::path(
+    post,
+    path = "/v1/posts",
+    tag = "posts",
+    request_body = models::post::CreatePostRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, description = "Post created", body = models::post::Post),
+        (status = 400, description = "Invalid request body"),
+        (status = 401, description = "Missing or invalid API token"),
+        (status = 409, description = "A post with this slug already exists")
+    )
+)]
+pub async fn handle_create_post(mut req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
+    if let Err(error) = authorize(&req, &ctx.env) {
+        return error.to_response(401);
+    }
+
+    let mut body: CreatePostRequest = match req.json().await {
+        Ok(body) => body,
+        Err(_) => {
+            let error = ApiError::bad_request("Invalid JSON body");
+            return error.to_response(400);
+        }
+    };
+
+    if !is_valid_slug(&body.slug) {
+        let error = ApiError::bad_request("Invalid post slug format");
+        return error.to_response(400);
+    }
+    if let Err(error) = validate_post_input(&mut body.post) {
+        return error.to_response(400);
+    }
+
+    let db = ctx.env.d1("DB")?;
+    let bucket = ctx.env.bucket("CONTENT_BUCKET")?;
+
+    match get_post_content_id(&db, &body.slug).await {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            let error = ApiError::conflict(format!("Post '{}' already exists", body.slug));
+            return error.to_response(409);
+        }
+        Err(e) => {
+            let error = ApiError::internal_error("Unable to create post");
+            error.log(&ctx.data.request_id, &format!("Failed to check slug '{}': {:?}", body.slug, e));
+            return error.to_response(500);
+        }
+    }
+
+    if let Err(e) = create_post(&db, &bucket, &body.slug, &body.post).await {
+        let error = ApiError::internal_error("Unable to create post");
+        error.log(&ctx.data.request_id, &format!("Failed to create post '{}': {:?}", body.slug, e));
+        return error.to_response(500);
+    }
+
+    match get_full_post(&db, &bucket, &body.slug, Visibility::All, ContentFormat::Markdown).await {
+        Ok(Some(post)) => Ok(Response::from_json(&post)?.with_status(201)),
+        Ok(None) => Response::empty().map(|r| r.with_status(201)),
+        Err(e) => {
+            log_error(&ctx.data.request_id, &format!("Failed to load created post '{}': {:?}", body.slug, e), None);
+            Response::empty().map(|r| r.with_status(201))
+        }
+    }
+}
+
+/// Replace an existing blog post
+#[utoipa::path(
+    put,
+    path = "/v1/posts/{slug}",
+    tag = "posts",
+    params(
+        ("slug" = String, Path, description = "URL slug of the post")
+    ),
+    request_body = models::post::PostInput,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Post updated", body = models::post::Post),
+        (status = 400, description = "Invalid slug or request body"),
+        (status = 401, description = "Missing or invalid API token"),
+        (status = 404, description = "Post not found")
+    )
+)]
+pub async fn handle_update_post(mut req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
+    if let Err(error) = authorize(&req, &ctx.env) {
+        return error.to_response(401);
+    }
+
+    let slug = ctx.param("slug").ok_or_else(|| Error::RustError("Missing slug".to_string()))?;
+    if !is_valid_slug(slug) {
+        let error = ApiError::bad_request("Invalid post slug format");
+        return error.to_response(400);
+    }
+
+    let mut input: PostInput = match req.json().await {
+        Ok(body) => body,
+        Err(_) => {
+            let error = ApiError::bad_request("Invalid JSON body");
+            return error.to_response(400);
+        }
+    };
+    if let Err(error) = validate_post_input(&mut input) {
+        return error.to_response(400);
+    }
+
+    let db = ctx.env.d1("DB")?;
+    let bucket = ctx.env.bucket("CONTENT_BUCKET")?;
+
+    let content_id = match get_post_content_id(&db, slug).await {
+        Ok(Some(id)) => id,
+        Ok(None) => {
+            let error = ApiError::not_found("Post");
+            return error.to_response(404);
+        }
+        Err(e) => {
+            let error = ApiError::internal_error("Unable to update post");
+            error.log(&ctx.data.request_id, &format!("Failed to look up post '{}': {:?}", slug, e));
+            return error.to_response(500);
+        }
+    };
+
+    if let Err(e) = update_post(&db, &bucket, &content_id, &input).await {
+        let error = ApiError::internal_error("Unable to update post");
+        error.log(&ctx.data.request_id, &format!("Failed to update post '{}': {:?}", slug, e));
+        return error.to_response(500);
+    }
+
+    match get_full_post(&db, &bucket, slug, Visibility::All, ContentFormat::Markdown).await {
         Ok(Some(post)) => Response::from_json(&post),
+        Ok(None) => Response::empty(),
+        Err(e) => {
+            log_error(&ctx.data.request_id, &format!("Failed to load updated post '{}': {:?}", slug, e), None);
+            Response::empty()
+        }
+    }
+}
+
+/// Delete a blog post and its content
+#[utoipa::path(
+    delete,
+    path = "/v1/posts/{slug}",
+    tag = "posts",
+    params(
+        ("slug" = String, Path, description = "URL slug of the post")
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Post deleted"),
+        (status = 400, description = "Invalid slug format"),
+        (status = 401, description = "Missing or invalid API token"),
+        (status = 404, description = "Post not found")
+    )
+)]
+pub async fn handle_delete_post(req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
+    if let Err(error) = authorize(&req, &ctx.env) {
+        return error.to_response(401);
+    }
+
+    let slug = ctx.param("slug").ok_or_else(|| Error::RustError("Missing slug".to_string()))?;
+    if !is_valid_slug(slug) {
+        let error = ApiError::bad_request("Invalid post slug format");
+        return error.to_response(400);
+    }
+
+    let db = ctx.env.d1("DB")?;
+    let bucket = ctx.env.bucket("CONTENT_BUCKET")?;
+
+    let content_id = match get_post_content_id(&db, slug).await {
+        Ok(Some(id)) => id,
         Ok(None) => {
             let error = ApiError::not_found("Post");
-            error.to_response(404)
+            return error.to_response(404);
         }
         Err(e) => {
-            // Log detailed error server-side
-            console_error!("Failed to get post '{}': {:?}", slug, e);
-            let error = ApiError::internal_error("Unable to load post");
+            let error = ApiError::internal_error("Unable to delete post");
+            error.log(&ctx.data.request_id, &format!("Failed to look up post '{}': {:?}", slug, e));
+            return error.to_response(500);
+        }
+    };
+
+    match delete_post(&db, &bucket, &content_id).await {
+        Ok(()) => Response::empty().map(|r| r.with_status(204)),
+        Err(e) => {
+            let error = ApiError::internal_error("Unable to delete post");
+            error.log(&ctx.data.request_id, &format!("Failed to delete post '{}': {:?}", slug, e));
             error.to_response(500)
         }
     }
 }
 
-/// Validate slug format for security
-/// Allows: alphanumeric, hyphens, underscores
-/// Max length: 100 characters
+/// Validate and normalize a post write payload
+///
+/// Trims text fields, rejects invalid tags and URLs, deduplicates tags and
+/// converts `published_at` to the `YYYY-MM-DD HH:MM:SS` UTC format stored in D1.
+fn validate_post_input(input: &mut PostInput) -> std::result::Result<(), ApiError> {
+    input.title = input.title.trim().to_string();
+    if input.title.is_empty() || input.title.len() > 200 {
+        return Err(ApiError::bad_request("Title must be between 1 and 200 characters"));
+    }
+
+    input.summary = input.summary.trim().to_string();
+
+    if input.content.trim().is_empty() {
+        return Err(ApiError::bad_request("Content cannot be empty"));
+    }
+
+    let mut tags: Vec<String> = Vec::with_capacity(input.tags.len());
+    for raw in &input.tags {
+        let tag = Tag::normalize(raw)
+            .map_err(|_| ApiError::bad_request(format!("Invalid tag '{}'", raw)))?;
+        if !tags.iter().any(|t| t == &*tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    input.tags = tags;
+
+    if let Some(url) = input.external_url.as_deref().map(str::trim) {
+        if url.is_empty() {
+            input.external_url = None;
+        } else if url.starts_with("https://") || url.starts_with("http://") {
+            input.external_url = Some(url.to_string());
+        } else {
+            return Err(ApiError::bad_request("External URL must use http or https"));
+        }
+    }
+
+    if let Some(published_at) = input.published_at.as_deref() {
+        let normalized = normalize_timestamp(published_at)
+            .ok_or_else(|| ApiError::bad_request("published_at must be an RFC 3339 timestamp"))?;
+        input.published_at = Some(normalized);
+    }
+
+    // Timestamps share one fixed-width format, so string comparison orders them
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let is_future = input.published_at.as_deref().is_some_and(|at| at > now.as_str());
+    match input.status {
+        None if is_future => input.status = Some(PostStatus::Scheduled),
+        Some(PostStatus::Scheduled) if !is_future => {
+            return Err(ApiError::bad_request("Scheduled posts need a future published_at"));
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Convert an RFC 3339 (or already-normalized) timestamp to SQLite's datetime format in UTC
+fn normalize_timestamp(value: &str) -> Option<String> {
+    const SQLITE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value.trim()) {
+        return Some(dt.with_timezone(&chrono::Utc).format(SQLITE_FORMAT).to_string());
+    }
+
+    chrono::NaiveDateTime::parse_from_str(value.trim(), SQLITE_FORMAT)
+        .ok()
+        .map(|dt| dt.format(SQLITE_FORMAT).to_string())
+}
+
+/// Validate slug format for security (see `Slug`)
 fn is_valid_slug(slug: &str) -> bool {
-    !slug.is_empty()
-        && slug.len() <= 100
-        && slug.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    Slug::is_valid(slug)
 }
 
 /// Parse query parameters for list endpoint
@@ -118,10 +414,8 @@ fn parse_list_params(url: &Url) -> ListPostsParams {
                     params.tags = Some(tags.iter().map(|t| t.to_string()).collect());
                 }
             }
-            "search" => {
-                if !value.is_empty() {
-                    params.search = Some(value.to_string());
-                }
+            "search" if !value.trim().is_empty() => {
+                params.search = Some(value.trim().to_string());
             }
             "sort" => {
                 params.sort_by = crate::storage::d1::SortField::from_str(&value);
@@ -180,11 +474,16 @@ mod tests {
 
     #[test]
     fn test_parse_list_params_filters_invalid_tags() {
-        let url = Url::parse("http://example.com/posts?tags=rust,invalid tag,python,../../etc/passwd").unwrap();
+        let url = Url::parse("http://example.com/posts?tags=rust,invalid.tag,python,../../etc/passwd").unwrap();
         let params = parse_list_params(&url);
 
         // Only valid tags should be included
         assert_eq!(params.tags, Some(vec!["rust".to_string(), "python".to_string()]));
+
+        // Tags are normalized rather than dropped
+        let url = Url::parse("http://example.com/posts?tags=Web%20Dev,web_assembly").unwrap();
+        let params = parse_list_params(&url);
+        assert_eq!(params.tags, Some(vec!["web-dev".to_string(), "web-assembly".to_string()]));
     }
 
     #[test]
@@ -204,6 +503,94 @@ mod tests {
         assert_eq!(params.limit, 50); // Clamped to max
     }
 
+    fn post_input() -> PostInput {
+        PostInput {
+            title: "  Hello World  ".to_string(),
+            summary: " A summary ".to_string(),
+            content: "# Hello".to_string(),
+            tags: vec!["rust".to_string(), " webdev ".to_string(), "rust".to_string()],
+            external_url: None,
+            published_at: None,
+            status: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_post_input_normalizes() {
+        let mut input = post_input();
+        input.external_url = Some(" https://example.com/post ".to_string());
+        input.published_at = Some("2025-01-02T03:04:05+02:00".to_string());
+
+        assert!(validate_post_input(&mut input).is_ok());
+        assert_eq!(input.title, "Hello World");
+        assert_eq!(input.summary, "A summary");
+        assert_eq!(input.tags, vec!["rust".to_string(), "webdev".to_string()]);
+        assert_eq!(input.external_url.as_deref(), Some("https://example.com/post"));
+        assert_eq!(input.published_at.as_deref(), Some("2025-01-02 01:04:05"));
+    }
+
+    #[test]
+    fn test_validate_post_input_rejects_invalid() {
+        let mut input = post_input();
+        input.title = "   ".to_string();
+        assert!(validate_post_input(&mut input).is_err()); // Empty title
+
+        let mut input = post_input();
+        input.content = "\n".to_string();
+        assert!(validate_post_input(&mut input).is_err()); // Empty content
+
+        let mut input = post_input();
+        input.tags = vec!["invalid/tag".to_string()];
+        assert!(validate_post_input(&mut input).is_err()); // Tag with a slash
+
+        let mut input = post_input();
+        input.external_url = Some("javascript:alert(1)".to_string());
+        assert!(validate_post_input(&mut input).is_err()); // Non-http URL
+
+        let mut input = post_input();
+        input.published_at = Some("next tuesday".to_string());
+        assert!(validate_post_input(&mut input).is_err()); // Unparseable date
+    }
+
+    #[test]
+    fn test_validate_post_input_status() {
+        // Future publish date defaults to scheduled
+        let mut input = post_input();
+        input.published_at = Some("2999-01-01T00:00:00Z".to_string());
+        assert!(validate_post_input(&mut input).is_ok());
+        assert_eq!(input.status, Some(PostStatus::Scheduled));
+
+        // Explicit status is kept
+        let mut input = post_input();
+        input.published_at = Some("2999-01-01T00:00:00Z".to_string());
+        input.status = Some(PostStatus::Draft);
+        assert!(validate_post_input(&mut input).is_ok());
+        assert_eq!(input.status, Some(PostStatus::Draft));
+
+        // Past or missing publish date leaves status unset (published)
+        let mut input = post_input();
+        assert!(validate_post_input(&mut input).is_ok());
+        assert_eq!(input.status, None);
+
+        // Scheduled requires a future date
+        let mut input = post_input();
+        input.status = Some(PostStatus::Scheduled);
+        assert!(validate_post_input(&mut input).is_err());
+
+        let mut input = post_input();
+        input.published_at = Some("2020-01-01T00:00:00Z".to_string());
+        input.status = Some(PostStatus::Scheduled);
+        assert!(validate_post_input(&mut input).is_err());
+    }
+
+    #[test]
+    fn test_normalize_timestamp() {
+        assert_eq!(normalize_timestamp("2025-06-01T12:00:00Z").as_deref(), Some("2025-06-01 12:00:00"));
+        assert_eq!(normalize_timestamp("2025-06-01 12:00:00").as_deref(), Some("2025-06-01 12:00:00"));
+        assert_eq!(normalize_timestamp("2025-06-01"), None);
+        assert_eq!(normalize_timestamp("'; DROP TABLE posts; --"), None);
+    }
+
     #[test]
     fn test_parse_list_params_invalid_values() {
         let url = Url::parse("http://example.com/posts?page=invalid&limit=bad").unwrap();
@@ -214,28 +601,3 @@ mod tests {
         assert_eq!(params.limit, 10);
     }
 }
-
-/// Get all available tags with usage counts
-#[utoipa::path(
-    get,
-    path = "/v1/tags",
-    tag = "posts",
-    responses(
-        (status = 200, description = "List of all tags with usage counts", body = Vec<crate::models::tag::TagWithCount>)
-    )
-)]
-pub async fn handle_get_tags(_req: Request, ctx: RouteContext<()>) -> Result<Response> {
-    // Get D1 database binding
-    let db = ctx.env.d1("DB")?;
-
-    // Query all tags with counts
-    match d1_get_all_tags(&db).await {
-        Ok(tags) => Response::from_json(&tags),
-        Err(_e) => {
-            // Log detailed error server-side (when error logging is fully implemented)
-            // console_error!("Failed to list tags: {:?}", e);
-            let error = ApiError::internal_error("Unable to load tags");
-            error.to_response(500)
-        }
-    }
-}
@@ -1,50 +1,80 @@
 use worker::*;
-use crate::storage::d1::{get_all_projects, get_project_by_slug};
+use crate::storage::d1::{
+    get_project_by_slug, list_projects, resolve_slug_alias, update_project, ListProjectsParams, ProjectSortField,
+    SlugKind, SortOrder, Visibility,
+};
+use crate::storage::readme::get_readme;
 use crate::errors::ApiError;
-use crate::models::project::ProjectsResponse;
+use crate::logging::log_error;
+use crate::middleware::{RequestContext, authorize, json_with_etag, set_last_modified};
+use crate::routes::moved_permanently;
+use models::project::{ProjectPatch, ProjectStage, ProjectsResponse};
+use models::{Slug, Tag};
 
-/// List all projects
+/// List projects with pagination and filtering
 #[utoipa::path(
     get,
     path = "/v1/projects",
     tag = "projects",
+    params(
+        ("page" = Option<u32>, Query, description = "Page number (default: 1)"),
+        ("limit" = Option<u32>, Query, description = "Items per page (default: 50, max: 100)"),
+        ("stage" = Option<String>, Query, description = "Filter by stage (comma-separated: planned, wip, active, maintained, archived, shelved)"),
+        ("tags" = Option<String>, Query, description = "Filter by tags (comma-separated; projects with any of the tags match)"),
+        ("open_to_contributors" = Option<bool>, Query, description = "Only projects that are (true) or are not (false) open to contributors"),
+        ("search" = Option<String>, Query, description = "Case-insensitive search in name and description; every term must match"),
+        ("sort" = Option<String>, Query, description = "Sort field: name, updated_at or created_at (default: updated_at)"),
+        ("order" = Option<String>, Query, description = "Sort order: asc or desc (default: asc for name, desc otherwise)"),
+    ),
     responses(
-        (status = 200, description = "List of all projects", body = crate::models::project::ProjectsResponse)
+        (status = 200, description = "List of projects", body = models::project::ProjectsResponse),
+        (status = 400, description = "Invalid stage or open_to_contributors value")
     )
 )]
-pub async fn handle_list_projects(_req: Request, ctx: RouteContext<()>) -> Result<Response> {
+pub async fn handle_list_projects(req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
+    // Parse query parameters
+    let url = req.url()?;
+    let params = match parse_list_params(&url) {
+        Ok(params) => params,
+        Err(error) => return error.to_response(400),
+    };
+
     // Get D1 database binding
     let db = ctx.env.d1("DB")?;
 
-    // Query all projects
-    match get_all_projects(&db).await {
-        Ok(projects) => {
-            let response = ProjectsResponse { projects };
-            Response::from_json(&response)
+    match list_projects(&db, params).await {
+        Ok((projects, pagination)) => {
+            let response = ProjectsResponse { projects, pagination };
+            json_with_etag(&response)
         }
         Err(e) => {
-            console_error!("Failed to list projects: {:?}", e);
             let error = ApiError::internal_error("Unable to load projects");
+            error.log(&ctx.data.request_id, &format!("Failed to list projects: {:?}", e));
             error.to_response(500)
         }
     }
 }
 
 /// Get a single project by slug
+///
+/// With `?include=readme` the README is fetched through the Worker and cached
+/// in R2; if upstream is unreachable the cached copy is returned.
 #[utoipa::path(
     get,
     path = "/v1/projects/{slug}",
     tag = "projects",
     params(
-        ("slug" = String, Path, description = "URL slug of the project")
+        ("slug" = String, Path, description = "URL slug of the project"),
+        ("include" = Option<String>, Query, description = "Extra fields (comma-separated): readme adds the README markdown with relative links made absolute"),
     ),
     responses(
-        (status = 200, description = "Project details", body = crate::models::project::Project),
-        (status = 400, description = "Invalid slug format"),
+        (status = 200, description = "Project details", body = models::project::Project),
+        (status = 301, description = "Project was renamed; `Location` points at its current slug", body = crate::routes::SlugRedirect),
+        (status = 400, description = "Invalid slug format or unknown include"),
         (status = 404, description = "Project not found")
     )
 )]
-pub async fn handle_get_project(_req: Request, ctx: RouteContext<()>) -> Result<Response> {
+pub async fn handle_get_project(req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
     let slug = ctx.param("slug").ok_or_else(|| Error::RustError("Missing slug".to_string()))?;
 
     // Validate slug format (security: prevent path traversal, SQL injection attempts)
@@ -53,32 +83,216 @@ pub async fn handle_get_project(_req: Request, ctx: RouteContext<()>) -> Result<
         return error.to_response(400);
     }
 
+    let url = req.url()?;
+    let include_readme = match parse_include(&url) {
+        Ok(include_readme) => include_readme,
+        Err(error) => return error.to_response(400),
+    };
+
     // Get D1 database binding
     let db = ctx.env.d1("DB")?;
 
     // Get project
     match get_project_by_slug(&db, slug).await {
-        Ok(Some(project)) => Response::from_json(&project),
-        Ok(None) => {
-            let error = ApiError::not_found("Project");
-            error.to_response(404)
+        Ok(Some(mut project)) => {
+            if include_readme {
+                // A missing README leaves the field out rather than failing the request
+                let bucket = ctx.env.bucket("CONTENT_BUCKET")?;
+                match get_readme(&bucket, &project).await {
+                    Ok(readme) => project.readme = readme,
+                    Err(e) => log_error(&ctx.data.request_id, &format!("Failed to load README for '{}': {:?}", slug, e), None),
+                }
+            }
+            let mut response = json_with_etag(&project)?;
+            // The README changes upstream without touching `updated_at`
+            if !include_readme {
+                set_last_modified(&mut response, &project.updated_at)?;
+            }
+            Ok(response)
         }
+        // The slug may belong to a renamed project
+        Ok(None) => match resolve_slug_alias(&db, SlugKind::Project, slug, Visibility::All).await {
+            Ok(Some(canonical)) => moved_permanently(&url, &format!("/v1/projects/{}", canonical)),
+            Ok(None) => {
+                let error = ApiError::not_found("Project");
+                error.to_response(404)
+            }
+            Err(e) => {
+                let error = ApiError::internal_error("Unable to load project");
+                error.log(&ctx.data.request_id, &format!("Failed to resolve project alias '{}': {:?}", slug, e));
+                error.to_response(500)
+            }
+        },
         Err(e) => {
-            // Log detailed error server-side
-            console_error!("Failed to get project '{}': {:?}", slug, e);
             let error = ApiError::internal_error("Unable to load project");
+            error.log(&ctx.data.request_id, &format!("Failed to get project '{}': {:?}", slug, e));
             error.to_response(500)
         }
     }
 }
 
-/// Validate slug format for security
-/// Allows: alphanumeric, hyphens, underscores
-/// Max length: 100 characters
+/// Update a project's fields, stage, URLs and tags
+#[utoipa::path(
+    patch,
+    path = "/v1/projects/{slug}",
+    tag = "projects",
+    params(
+        ("slug" = String, Path, description = "URL slug of the project")
+    ),
+    request_body = models::project::ProjectPatch,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Project updated", body = models::project::Project),
+        (status = 400, description = "Invalid slug or request body"),
+        (status = 401, description = "Missing or invalid API token"),
+        (status = 404, description = "Project not found"),
+        (status = 409, description = "The project cannot move to the requested stage")
+    )
+)]
+pub async fn handle_patch_project(mut req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
+    if let Err(error) = authorize(&req, &ctx.env) {
+        return error.to_response(401);
+    }
+
+    let slug = ctx.param("slug").ok_or_else(|| Error::RustError("Missing slug".to_string()))?;
+    if !is_valid_slug(slug) {
+        let error = ApiError::bad_request("Invalid project slug format");
+        return error.to_response(400);
+    }
+
+    let mut patch: ProjectPatch = match req.json().await {
+        Ok(body) => body,
+        Err(_) => {
+            let error = ApiError::bad_request("Invalid JSON body");
+            return error.to_response(400);
+        }
+    };
+    if let Err(error) = validate_project_patch(&mut patch) {
+        return error.to_response(400);
+    }
+
+    let db = ctx.env.d1("DB")?;
+
+    let project = match get_project_by_slug(&db, slug).await {
+        Ok(Some(project)) => project,
+        Ok(None) => {
+            let error = ApiError::not_found("Project");
+            return error.to_response(404);
+        }
+        Err(e) => {
+            let error = ApiError::internal_error("Unable to update project");
+            error.log(&ctx.data.request_id, &format!("Failed to look up project '{}': {:?}", slug, e));
+            return error.to_response(500);
+        }
+    };
+
+    if let Some(stage) = patch.stage
+        && let Err(e) = project.stage.transition_to(stage)
+    {
+        let error = ApiError::conflict(e.to_string());
+        return error.to_response(409);
+    }
+
+    if let Err(e) = update_project(&db, &project.id, &patch).await {
+        let error = ApiError::internal_error("Unable to update project");
+        error.log(&ctx.data.request_id, &format!("Failed to update project '{}': {:?}", slug, e));
+        return error.to_response(500);
+    }
+
+    match get_project_by_slug(&db, slug).await {
+        Ok(Some(project)) => Response::from_json(&project),
+        Ok(None) => Response::empty(),
+        Err(e) => {
+            log_error(&ctx.data.request_id, &format!("Failed to load updated project '{}': {:?}", slug, e), None);
+            Response::empty()
+        }
+    }
+}
+
+/// Validate and normalize a project patch (see `ProjectPatch::validate`)
+fn validate_project_patch(patch: &mut ProjectPatch) -> std::result::Result<(), ApiError> {
+    if patch.is_empty() {
+        return Err(ApiError::bad_request("Patch does not change anything"));
+    }
+    patch.validate().map_err(|e| ApiError::bad_request(e.to_string()))
+}
+
+/// Parse `?include=`; returns whether the README was requested
+fn parse_include(url: &Url) -> std::result::Result<bool, ApiError> {
+    let mut readme = false;
+    for (_, value) in url.query_pairs().filter(|(key, _)| key == "include") {
+        for field in value.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            match field {
+                "readme" => readme = true,
+                _ => return Err(ApiError::bad_request(format!("Unknown include '{}' (expected readme)", field))),
+            }
+        }
+    }
+    Ok(readme)
+}
+
+/// Parse query parameters for the list endpoint
+///
+/// Unknown stages and booleans are rejected rather than ignored, since
+/// dropping a filter would silently widen the result.
+fn parse_list_params(url: &Url) -> std::result::Result<ListProjectsParams, ApiError> {
+    let mut params = ListProjectsParams::default();
+
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "page" => {
+                if let Ok(p) = value.parse::<u32>() {
+                    params.page = p.max(1);
+                }
+            }
+            "limit" => {
+                if let Ok(l) = value.parse::<u32>() {
+                    params.limit = l.clamp(1, 100);
+                }
+            }
+            "stage" => {
+                let stages = value.split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(|s| ProjectStage::parse(&s.to_lowercase()))
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|e| ApiError::bad_request(e.to_string()))?;
+                if !stages.is_empty() {
+                    params.stages = Some(stages);
+                }
+            }
+            "tags" => {
+                let tags = Tag::parse_many(&value);
+                if !tags.is_empty() {
+                    params.tags = Some(tags.iter().map(|t| t.to_string()).collect());
+                }
+            }
+            "open_to_contributors" => {
+                params.open_to_contributors = match value.as_ref() {
+                    "true" | "1" => Some(true),
+                    "false" | "0" => Some(false),
+                    _ => return Err(ApiError::bad_request("open_to_contributors must be true or false")),
+                };
+            }
+            "search" if !value.trim().is_empty() => {
+                params.search = Some(value.trim().to_string());
+            }
+            "sort" => {
+                params.sort_by = ProjectSortField::from_str(&value);
+            }
+            "order" => {
+                params.order = Some(SortOrder::from_str(&value));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(params)
+}
+
+/// Validate slug format for security (see `Slug`)
 fn is_valid_slug(slug: &str) -> bool {
-    !slug.is_empty()
-        && slug.len() <= 100
-        && slug.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    Slug::is_valid(slug)
 }
 
 #[cfg(test)]
@@ -102,4 +316,86 @@ mod tests {
         assert!(!is_valid_slug("'; DROP TABLE projects; --")); // SQL injection attempt
         assert!(!is_valid_slug(&"a".repeat(101))); // Too long (> 100 chars)
     }
+
+    #[test]
+    fn test_parse_list_params_defaults() {
+        let url = Url::parse("http://example.com/v1/projects").unwrap();
+        let params = parse_list_params(&url).unwrap();
+
+        assert_eq!(params.page, 1);
+        assert_eq!(params.limit, 50);
+        assert!(params.stages.is_none());
+        assert!(params.order.is_none());
+        assert_eq!(params.sort_by, ProjectSortField::UpdatedAt);
+    }
+
+    #[test]
+    fn test_parse_list_params_with_values() {
+        let url = Url::parse(
+            "http://example.com/v1/projects?page=2&limit=500&stage=active,Maintained&tags=rust,wasm\
+             &open_to_contributors=true&search=%20cli%20&sort=name",
+        )
+        .unwrap();
+        let params = parse_list_params(&url).unwrap();
+
+        assert_eq!(params.page, 2);
+        assert_eq!(params.limit, 100);
+        assert_eq!(params.stages, Some(vec![ProjectStage::Active, ProjectStage::Maintained]));
+        assert_eq!(params.tags, Some(vec!["rust".to_string(), "wasm".to_string()]));
+        assert_eq!(params.open_to_contributors, Some(true));
+        assert_eq!(params.search.as_deref(), Some("cli"));
+        assert_eq!(params.sort_by, ProjectSortField::Name);
+    }
+
+    #[test]
+    fn test_parse_list_params_rejects_invalid_filters() {
+        let url = Url::parse("http://example.com/v1/projects?stage=done").unwrap();
+        assert!(parse_list_params(&url).is_err());
+
+        let url = Url::parse("http://example.com/v1/projects?open_to_contributors=maybe").unwrap();
+        assert!(parse_list_params(&url).is_err());
+    }
+
+    #[test]
+    fn test_parse_include() {
+        let url = Url::parse("http://example.com/v1/projects/crate").unwrap();
+        assert!(!parse_include(&url).unwrap());
+
+        let url = Url::parse("http://example.com/v1/projects/crate?include=readme").unwrap();
+        assert!(parse_include(&url).unwrap());
+
+        let url = Url::parse("http://example.com/v1/projects/crate?include=readme,stars").unwrap();
+        assert!(parse_include(&url).is_err());
+    }
+
+    #[test]
+    fn test_validate_project_patch() {
+        let mut patch = ProjectPatch {
+            name: Some("  Crate ".to_string()),
+            add_tags: vec!["rust".to_string(), " rust".to_string(), "web".to_string()],
+            add_urls: vec![models::project::ProjectUrl {
+                label: " Docs ".to_string(),
+                url: "https://docs.rs".to_string(),
+            }],
+            ..Default::default()
+        };
+        assert!(validate_project_patch(&mut patch).is_ok());
+        assert_eq!(patch.name.as_deref(), Some("Crate"));
+        assert_eq!(patch.add_tags, vec!["rust", "web"]);
+        assert_eq!(patch.add_urls[0].label, "Docs");
+    }
+
+    #[test]
+    fn test_validate_project_patch_rejects_invalid_input() {
+        assert!(validate_project_patch(&mut ProjectPatch::default()).is_err());
+
+        let mut patch = ProjectPatch { name: Some("   ".to_string()), ..Default::default() };
+        assert!(validate_project_patch(&mut patch).is_err());
+
+        let mut patch = ProjectPatch { readme_url: Some("javascript:alert(1)".to_string()), ..Default::default() };
+        assert!(validate_project_patch(&mut patch).is_err());
+
+        let mut patch = ProjectPatch { remove_tags: vec!["bad/tag".to_string()], ..Default::default() };
+        assert!(validate_project_patch(&mut patch).is_err());
+    }
 }
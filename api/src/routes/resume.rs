@@ -1,7 +1,8 @@
 use worker::*;
-use crate::models::resume::Resume;
+use models::resume::Resume;
 use crate::errors::ApiError;
-use crate::models::tag::Tag;
+use crate::middleware::{RequestContext, etag};
+use models::tag::Tag;
 
 /// Get resume data with optional filtering
 #[utoipa::path(
@@ -15,11 +16,11 @@ use crate::models::tag::Tag;
         ("limit" = Option<usize>, Query, description = "Limit items per section"),
     ),
     responses(
-        (status = 200, description = "Resume data", body = crate::models::resume::Resume),
+        (status = 200, description = "Resume data", body = models::resume::Resume),
         (status = 404, description = "Resume not found")
     )
 )]
-pub async fn handle_get_resume(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+pub async fn handle_get_resume(req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
     // Get KV namespace binding
     let kv = ctx.env.kv("RESUME_KV")?;
 
@@ -35,10 +36,9 @@ pub async fn handle_get_resume(req: Request, ctx: RouteContext<()>) -> Result<Re
     // Parse resume JSON
     let mut resume: Resume = match serde_json::from_str(&resume_data) {
         Ok(r) => r,
-        Err(_e) => {
-            // Log detailed error server-side (when error logging is fully implemented)
-            // console_error!("Failed to parse resume from KV: {:?}", e);
+        Err(e) => {
             let error = ApiError::internal_error("Unable to load resume data");
+            error.log(&ctx.data.request_id, &format!("Failed to parse resume from KV: {:?}", e));
             return error.to_response(500);
         }
     };
@@ -47,11 +47,9 @@ pub async fn handle_get_resume(req: Request, ctx: RouteContext<()>) -> Result<Re
     let url = req.url()?;
     apply_filters(&mut resume, &url);
 
-    // Add cache headers
+    // Cache headers are set by the caching middleware; the ETag follows the KV value
     let mut response = Response::from_json(&resume)?;
-    let headers = response.headers_mut();
-    headers.set("Cache-Control", "public, max-age=3600")?; // Cache for 1 hour
-
+    response.headers_mut().set("ETag", &etag(&[resume_data.as_bytes()]))?;
     Ok(response)
 }
 
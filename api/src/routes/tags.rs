@@ -0,0 +1,93 @@
+use worker::*;
+use crate::storage::d1::{get_all_tags, get_posts_by_tag, get_projects_by_tag, get_tag, resolve_tag_alias};
+use crate::errors::ApiError;
+use crate::middleware::{RequestContext, json_with_etag};
+use crate::routes::moved_permanently;
+use models::tag::{Tag, TagDetail};
+
+/// Get all tags used by posts or projects
+#[utoipa::path(
+    get,
+    path = "/v1/tags",
+    tag = "tags",
+    responses(
+        (status = 200, description = "Tags with their metadata and separate post and project counts", body = Vec<models::tag::TagWithCount>)
+    )
+)]
+pub async fn handle_list_tags(_req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
+    // Get D1 database binding
+    let db = ctx.env.d1("DB")?;
+
+    // Query all tags with counts
+    match get_all_tags(&db).await {
+        Ok(tags) => json_with_etag(&tags),
+        Err(e) => {
+            let error = ApiError::internal_error("Unable to load tags");
+            error.log(&ctx.data.request_id, &format!("Failed to list tags: {:?}", e));
+            error.to_response(500)
+        }
+    }
+}
+
+/// Get a tag with the posts and projects carrying it
+///
+/// Names are normalized ("Web Dev" finds `web-dev`), and aliases of a merged
+/// or renamed tag redirect to the tag they point at.
+#[utoipa::path(
+    get,
+    path = "/v1/tags/{name}",
+    tag = "tags",
+    params(
+        ("name" = String, Path, description = "Tag name")
+    ),
+    responses(
+        (status = 200, description = "Tag with its listed posts and projects", body = models::tag::TagDetail),
+        (status = 301, description = "Name is an alias; `Location` points at the tag", body = crate::routes::SlugRedirect),
+        (status = 400, description = "Invalid tag name"),
+        (status = 404, description = "Tag not found")
+    )
+)]
+pub async fn handle_get_tag(req: Request, ctx: RouteContext<RequestContext>) -> Result<Response> {
+    let name = ctx.param("name").ok_or_else(|| Error::RustError("Missing tag name".to_string()))?;
+
+    let Ok(tag) = Tag::normalize(name) else {
+        let error = ApiError::bad_request("Invalid tag name");
+        return error.to_response(400);
+    };
+
+    // Get D1 database binding
+    let db = ctx.env.d1("DB")?;
+
+    let tag = match get_tag(&db, &tag).await {
+        Ok(Some(tag)) => tag,
+        // The name may be an alias of a merged or renamed tag
+        Ok(None) => match resolve_tag_alias(&db, &tag).await {
+            Ok(Some(canonical)) => return moved_permanently(&req.url()?, &format!("/v1/tags/{}", canonical)),
+            Ok(None) => {
+                let error = ApiError::not_found("Tag");
+                return error.to_response(404);
+            }
+            Err(e) => {
+                let error = ApiError::internal_error("Unable to load tag");
+                error.log(&ctx.data.request_id, &format!("Failed to resolve tag alias '{}': {:?}", name, e));
+                return error.to_response(500);
+            }
+        },
+        Err(e) => {
+            let error = ApiError::internal_error("Unable to load tag");
+            error.log(&ctx.data.request_id, &format!("Failed to get tag '{}': {:?}", name, e));
+            return error.to_response(500);
+        }
+    };
+
+    let posts = get_posts_by_tag(&db, &tag.tag).await;
+    let projects = get_projects_by_tag(&db, &tag.tag).await;
+    match (posts, projects) {
+        (Ok(posts), Ok(projects)) => json_with_etag(&TagDetail { tag, posts, projects }),
+        (Err(e), _) | (_, Err(e)) => {
+            let error = ApiError::internal_error("Unable to load tag");
+            error.log(&ctx.data.request_id, &format!("Failed to load items tagged '{}': {:?}", name, e));
+            error.to_response(500)
+        }
+    }
+}
@@ -1,8 +1,13 @@
 // Blog storage abstraction combining D1 + R2
 use worker::*;
-use crate::models::post::{Post, PostsResponse};
-use crate::storage::d1::{ListPostsParams, list_posts as d1_list_posts, get_post_by_slug as d1_get_post_by_slug};
-use crate::storage::r2::get_content;
+use models::post::{Post, PostInput, PostsResponse};
+use crate::storage::d1::{
+    ListPostsParams, Visibility, list_posts as d1_list_posts, get_post_by_slug as d1_get_post_by_slug,
+    insert_post as d1_insert_post, update_post as d1_update_post, delete_post as d1_delete_post,
+    next_post_version,
+};
+use crate::storage::r2::{get_content, put_content, delete_content, put_version_content, delete_version_contents};
+use crate::storage::markdown::render_markdown;
 
 /// CDN base URL for blog assets
 const CDN_BASE_URL: &str = "https://cloud.werdxz.info";
@@ -40,37 +45,138 @@ fn rewrite_image_urls(content: &str, slug: &str) -> String {
     }).to_string()
 }
 
+/// Representation of a post body in API responses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContentFormat {
+    /// Raw markdown in `content`
+    #[default]
+    Markdown,
+    /// Sanitized HTML in `content_html`, plus a table of contents
+    Html,
+}
+
+impl ContentFormat {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "markdown" => Some(Self::Markdown),
+            "html" => Some(Self::Html),
+            _ => None,
+        }
+    }
+}
+
 /// Get full post with content from R2
-pub async fn get_full_post(db: &D1Database, bucket: &Bucket, slug: &str) -> Result<Option<Post>> {
+pub async fn get_full_post(
+    db: &D1Database,
+    bucket: &Bucket,
+    slug: &str,
+    visibility: Visibility,
+    format: ContentFormat,
+) -> Result<Option<Post>> {
     // Get metadata from D1
-    let mut post = match d1_get_post_by_slug(db, slug).await? {
+    let mut post = match d1_get_post_by_slug(db, slug, visibility).await? {
         Some(p) => p,
         None => return Ok(None),
     };
 
-    // Get content from R2
-    let content = get_content(bucket, &post.content_id).await?;
+    let content = get_post_content(bucket, &post.content_id, slug).await?;
 
-    // Rewrite relative image URLs to CDN URLs
-    post.content = content.map(|c| rewrite_image_urls(&c, slug));
+    match format {
+        ContentFormat::Markdown => post.content = content,
+        ContentFormat::Html => {
+            if let Some(markdown) = content {
+                let rendered = render_markdown(&markdown);
+                post.content_html = Some(rendered.html);
+                post.toc = Some(rendered.toc);
+            }
+        }
+    }
 
     Ok(Some(post))
 }
 
+/// Get a post's markdown from R2 with image URLs pointing at the CDN
+pub async fn get_post_content(bucket: &Bucket, content_id: &str, slug: &str) -> Result<Option<String>> {
+    let content = get_content(bucket, content_id).await?;
+
+    // Rewrite relative image URLs to CDN URLs
+    Ok(content.map(|c| rewrite_image_urls(&c, slug)))
+}
+
 /// List posts with pagination
 pub async fn list_posts_with_pagination(
     db: &D1Database,
     params: &ListPostsParams,
 ) -> Result<PostsResponse> {
     // Tag filtering now handled at SQL level in d1_list_posts
+    // Reading stats are stored at write time, so no R2 reads are needed here
     let (posts, pagination) = d1_list_posts(db, params).await?;
 
-    // TODO: Add read_time_minutes column to D1 and calculate during publishing
-    // For now, read time is None in listings (would need to fetch full content)
-
     Ok(PostsResponse { posts, pagination })
 }
 
+/// Create a post: markdown goes to R2, metadata and tags to D1
+///
+/// Content is written first so a post row never points at missing content.
+/// If the D1 batch fails, the freshly written object is removed again.
+pub async fn create_post(db: &D1Database, bucket: &Bucket, slug: &str, input: &PostInput) -> Result<String> {
+    let content_id = uuid::Uuid::new_v4().to_string();
+
+    put_content(bucket, &content_id, &input.content).await?;
+
+    if let Err(e) = d1_insert_post(db, &content_id, slug, input).await {
+        if let Err(cleanup) = delete_content(bucket, &content_id).await {
+            console_error!("Failed to remove orphaned content '{}': {:?}", content_id, cleanup);
+        }
+        return Err(e);
+    }
+
+    Ok(content_id)
+}
+
+/// Replace a post's content, metadata and tags, keeping its content_id
+///
+/// The current markdown and metadata are saved as the next version first,
+/// so `cargo xtask post rollback` can restore them.
+pub async fn update_post(db: &D1Database, bucket: &Bucket, content_id: &str, input: &PostInput) -> Result<()> {
+    let version = next_post_version(db, content_id).await?;
+
+    let previous = get_content(bucket, content_id).await?;
+    if let Some(previous) = &previous {
+        put_version_content(bucket, content_id, version, previous).await?;
+    }
+
+    put_content(bucket, content_id, &input.content).await?;
+
+    // Keep the old markdown live alongside the old metadata if D1 rejects the update
+    if let Err(e) = d1_update_post(db, content_id, version, input).await {
+        if let Some(previous) = &previous
+            && let Err(restore) = put_content(bucket, content_id, previous).await
+        {
+            console_error!("Failed to restore content '{}': {:?}", content_id, restore);
+        }
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+/// Delete a post's metadata, content and version history
+///
+/// The D1 row goes first so the post disappears from the API even if R2 cleanup fails.
+pub async fn delete_post(db: &D1Database, bucket: &Bucket, content_id: &str) -> Result<()> {
+    d1_delete_post(db, content_id).await?;
+
+    if let Err(e) = delete_content(bucket, content_id).await {
+        console_error!("Failed to delete content '{}' from R2: {:?}", content_id, e);
+    }
+    if let Err(e) = delete_version_contents(bucket, content_id).await {
+        console_error!("Failed to delete version history of '{}' from R2: {:?}", content_id, e);
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
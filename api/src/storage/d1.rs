@@ -1,8 +1,8 @@
 use worker::*;
 use serde::Deserialize;
-use crate::models::post::{Post, PostListItem, Pagination};
-use crate::models::project::Project;
-use crate::models::tag::TagWithCount;
+use models::post::{reading_stats, Post, PostInput, PostListItem, PostStatus, Pagination};
+use models::project::{Project, ProjectPatch, ProjectStage};
+use models::tag::TagWithCount;
 
 /// Count query result
 #[derive(Deserialize)]
@@ -103,34 +103,37 @@ impl QueryType {
         match self {
             Self::NoFilters => {
                 "SELECT COUNT(*) as count FROM posts p \
-                 WHERE p.published_at <= datetime('now')"
+                 WHERE p.status IN ('published', 'scheduled') AND p.published_at <= datetime('now')"
             }
             Self::TagsOnly => {
                 "SELECT COUNT(DISTINCT p.content_id) as count FROM posts p \
                  INNER JOIN post_tags pt ON p.content_id = pt.post_id \
                  INNER JOIN tags t ON pt.tag_id = t.id \
-                 WHERE p.published_at <= datetime('now') \
+                 WHERE p.status IN ('published', 'scheduled') AND p.published_at <= datetime('now') \
                  AND t.name IN (SELECT json_each.value FROM json_each(?))"
             }
             Self::SearchOnly => {
-                "SELECT COUNT(*) as count FROM posts p \
-                 WHERE p.published_at <= datetime('now') \
-                 AND (p.title LIKE '%' || ? || '%' OR p.summary LIKE '%' || ? || '%')"
+                "SELECT COUNT(*) as count FROM posts_fts \
+                 INNER JOIN posts p ON p.content_id = posts_fts.content_id \
+                 WHERE posts_fts MATCH ? \
+                 AND p.status IN ('published', 'scheduled') AND p.published_at <= datetime('now')"
             }
             Self::TagsAndSearch => {
-                "SELECT COUNT(DISTINCT p.content_id) as count FROM posts p \
-                 INNER JOIN post_tags pt ON p.content_id = pt.post_id \
-                 INNER JOIN tags t ON pt.tag_id = t.id \
-                 WHERE p.published_at <= datetime('now') \
-                 AND t.name IN (SELECT json_each.value FROM json_each(?)) \
-                 AND (p.title LIKE '%' || ? || '%' OR p.summary LIKE '%' || ? || '%')"
+                "SELECT COUNT(*) as count FROM posts_fts \
+                 INNER JOIN posts p ON p.content_id = posts_fts.content_id \
+                 WHERE posts_fts MATCH ? \
+                 AND p.status IN ('published', 'scheduled') AND p.published_at <= datetime('now') \
+                 AND p.content_id IN (SELECT pt.post_id FROM post_tags pt \
+                     INNER JOIN tags t ON pt.tag_id = t.id \
+                     WHERE t.name IN (SELECT json_each.value FROM json_each(?)))"
             }
         }
     }
 
     /// Get the select query for this filter combination
     fn select_query(&self, sort_field: SortField, sort_order: SortOrder) -> String {
-        let base = "SELECT p.slug, p.title, p.summary, p.published_at, p.external_url, \
+        let base = "SELECT p.content_id, p.slug, p.title, p.summary, p.published_at, p.external_url, \
+                    p.word_count, p.read_time_minutes, \
                     (SELECT json_group_array(t.name) FROM post_tags pt \
                      INNER JOIN tags t ON pt.tag_id = t.id \
                      WHERE pt.post_id = p.content_id) as tags \
@@ -138,15 +141,34 @@ impl QueryType {
 
         let order = format!(" ORDER BY p.{} {}", sort_field.to_sql(), sort_order.to_sql());
 
+        // Search queries read from the FTS index and rank by bm25
+        // (weights: content_id, title, summary, body), with the sort field as tie-breaker
+        let search_base = format!(
+            "SELECT p.content_id, p.slug, p.title, p.summary, p.published_at, p.external_url, \
+             p.word_count, p.read_time_minutes, \
+             (SELECT json_group_array(t.name) FROM post_tags pt \
+              INNER JOIN tags t ON pt.tag_id = t.id \
+              WHERE pt.post_id = p.content_id) as tags, \
+             snippet(posts_fts, -1, '{}', '{}', '…', 24) as snippet \
+             FROM posts_fts \
+             INNER JOIN posts p ON p.content_id = posts_fts.content_id",
+            SNIPPET_START, SNIPPET_END
+        );
+        let search_order = format!(
+            " ORDER BY bm25(posts_fts, 0.0, 10.0, 5.0, 1.0), p.{} {}",
+            sort_field.to_sql(),
+            sort_order.to_sql()
+        );
+
         match self {
             Self::NoFilters => {
-                format!("{} WHERE p.published_at <= datetime('now'){} LIMIT ? OFFSET ?", base, order)
+                format!("{} WHERE p.status IN ('published', 'scheduled') AND p.published_at <= datetime('now'){} LIMIT ? OFFSET ?", base, order)
             }
             Self::TagsOnly => {
                 format!(
                     "{} INNER JOIN post_tags pt ON p.content_id = pt.post_id \
                      INNER JOIN tags t ON pt.tag_id = t.id \
-                     WHERE p.published_at <= datetime('now') \
+                     WHERE p.status IN ('published', 'scheduled') AND p.published_at <= datetime('now') \
                      AND t.name IN (SELECT json_each.value FROM json_each(?))\
                      {} LIMIT ? OFFSET ?",
                     base, order
@@ -154,32 +176,85 @@ impl QueryType {
             }
             Self::SearchOnly => {
                 format!(
-                    "{} WHERE p.published_at <= datetime('now') \
-                     AND (p.title LIKE '%' || ? || '%' OR p.summary LIKE '%' || ? || '%')\
+                    "{} WHERE posts_fts MATCH ? \
+                     AND p.status IN ('published', 'scheduled') AND p.published_at <= datetime('now')\
                      {} LIMIT ? OFFSET ?",
-                    base, order
+                    search_base, search_order
                 )
             }
             Self::TagsAndSearch => {
                 format!(
-                    "{} INNER JOIN post_tags pt ON p.content_id = pt.post_id \
-                     INNER JOIN tags t ON pt.tag_id = t.id \
-                     WHERE p.published_at <= datetime('now') \
-                     AND t.name IN (SELECT json_each.value FROM json_each(?)) \
-                     AND (p.title LIKE '%' || ? || '%' OR p.summary LIKE '%' || ? || '%')\
+                    "{} WHERE posts_fts MATCH ? \
+                     AND p.status IN ('published', 'scheduled') AND p.published_at <= datetime('now') \
+                     AND p.content_id IN (SELECT pt.post_id FROM post_tags pt \
+                         INNER JOIN tags t ON pt.tag_id = t.id \
+                         WHERE t.name IN (SELECT json_each.value FROM json_each(?)))\
                      {} LIMIT ? OFFSET ?",
-                    base, order
+                    search_base, search_order
                 )
             }
         }
     }
 }
 
+/// Markers wrapped around matched terms by `snippet()`; control characters
+/// never appear in post text, so they survive HTML escaping unambiguously
+const SNIPPET_START: &str = "\u{2}";
+const SNIPPET_END: &str = "\u{3}";
+
+/// Maximum number of search terms passed to FTS5
+const MAX_SEARCH_TERMS: usize = 10;
+
+/// Build an FTS5 MATCH expression from free-form user input
+///
+/// Every term is quoted so FTS5 operators (AND, NEAR, column filters, ...)
+/// in user input are matched literally. The last term is a prefix match so
+/// results update while the user is still typing.
+pub fn fts_query(search: &str) -> String {
+    let terms: Vec<String> = search
+        .split_whitespace()
+        .map(|term| term.replace('"', ""))
+        .filter(|term| !term.is_empty())
+        .take(MAX_SEARCH_TERMS)
+        .map(|term| format!("\"{}\"", term))
+        .collect();
+
+    match terms.split_last() {
+        Some((last, [])) => format!("{}*", last),
+        Some((last, rest)) => format!("{} {}*", rest.join(" "), last),
+        None => "\"\"".to_string(),
+    }
+}
+
+/// HTML-escape a snippet and replace the FTS markers with `<mark>` tags
+fn highlight_snippet(snippet: &str) -> String {
+    let mut out = String::with_capacity(snippet.len() + 16);
+    for c in snippet.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            '\u{2}' => out.push_str("<mark>"),
+            '\u{3}' => out.push_str("</mark>"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
 /// List posts with pagination and filtering
 pub async fn list_posts(db: &D1Database, params: &ListPostsParams) -> Result<(Vec<PostListItem>, Pagination)> {
     let offset = (params.page - 1) * params.limit;
     let query_type = QueryType::from_params(params);
 
+    // Aliases are resolved up front, so the filter queries only match tag names
+    let tags_json = match &params.tags {
+        Some(tags) => json_list(&resolve_tag_aliases(db, tags).await?)?,
+        None => String::new(),
+    };
+
     // Execute count query based on filter combination
     let total = match query_type {
         QueryType::NoFilters => {
@@ -188,26 +263,22 @@ pub async fn list_posts(db: &D1Database, params: &ListPostsParams) -> Result<(Ve
             result.map(|c| c.count).unwrap_or(0)
         }
         QueryType::TagsOnly => {
-            let tags_json = serde_json::to_string(params.tags.as_ref().unwrap())
-                .map_err(|_| Error::RustError("Failed to serialize tags".to_string()))?;
             let stmt = db.prepare(query_type.count_query())
-                .bind(&[tags_json.into()])?;
+                .bind(&[tags_json.as_str().into()])?;
             let result = stmt.first::<CountResult>(None).await?;
             result.map(|c| c.count).unwrap_or(0)
         }
         QueryType::SearchOnly => {
-            let search = params.search.as_ref().unwrap();
+            let search = fts_query(params.search.as_ref().unwrap());
             let stmt = db.prepare(query_type.count_query())
-                .bind(&[search.clone().into(), search.clone().into()])?;
+                .bind(&[search.into()])?;
             let result = stmt.first::<CountResult>(None).await?;
             result.map(|c| c.count).unwrap_or(0)
         }
         QueryType::TagsAndSearch => {
-            let tags_json = serde_json::to_string(params.tags.as_ref().unwrap())
-                .map_err(|_| Error::RustError("Failed to serialize tags".to_string()))?;
-            let search = params.search.as_ref().unwrap();
+            let search = fts_query(params.search.as_ref().unwrap());
             let stmt = db.prepare(query_type.count_query())
-                .bind(&[tags_json.into(), search.clone().into(), search.clone().into()])?;
+                .bind(&[search.into(), tags_json.as_str().into()])?;
             let result = stmt.first::<CountResult>(None).await?;
             result.map(|c| c.count).unwrap_or(0)
         }
@@ -222,31 +293,36 @@ pub async fn list_posts(db: &D1Database, params: &ListPostsParams) -> Result<(Ve
             results.results()?
         }
         QueryType::TagsOnly => {
-            let tags_json = serde_json::to_string(params.tags.as_ref().unwrap())
-                .map_err(|_| Error::RustError("Failed to serialize tags".to_string()))?;
             let stmt = db.prepare(query_type.select_query(params.sort_by, params.order))
-                .bind(&[tags_json.into(), params.limit.into(), offset.into()])?;
+                .bind(&[tags_json.as_str().into(), params.limit.into(), offset.into()])?;
             let results = stmt.all().await?;
             results.results()?
         }
         QueryType::SearchOnly => {
-            let search = params.search.as_ref().unwrap();
+            let search = fts_query(params.search.as_ref().unwrap());
             let stmt = db.prepare(query_type.select_query(params.sort_by, params.order))
-                .bind(&[search.clone().into(), search.clone().into(), params.limit.into(), offset.into()])?;
+                .bind(&[search.into(), params.limit.into(), offset.into()])?;
             let results = stmt.all().await?;
             results.results()?
         }
         QueryType::TagsAndSearch => {
-            let tags_json = serde_json::to_string(params.tags.as_ref().unwrap())
-                .map_err(|_| Error::RustError("Failed to serialize tags".to_string()))?;
-            let search = params.search.as_ref().unwrap();
+            let search = fts_query(params.search.as_ref().unwrap());
             let stmt = db.prepare(query_type.select_query(params.sort_by, params.order))
-                .bind(&[tags_json.into(), search.clone().into(), search.clone().into(), params.limit.into(), offset.into()])?;
+                .bind(&[search.into(), tags_json.as_str().into(), params.limit.into(), offset.into()])?;
             let results = stmt.all().await?;
             results.results()?
         }
     };
 
+    // Escape snippets and turn the FTS markers into <mark> tags
+    let posts = posts
+        .into_iter()
+        .map(|mut post| {
+            post.snippet = post.snippet.as_deref().map(highlight_snippet);
+            post
+        })
+        .collect();
+
     // Calculate pagination
     let has_next = (params.page * params.limit) < total;
     let pagination = Pagination {
@@ -259,9 +335,32 @@ pub async fn list_posts(db: &D1Database, params: &ListPostsParams) -> Result<(Ve
     Ok((posts, pagination))
 }
 
-/// SQL query for fetching a single post by slug
+/// Which posts a lookup by slug may return
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// Published and scheduled posts once due, plus unlisted posts
+    Public,
+    /// Every post, including drafts and future-dated posts (authenticated callers only)
+    All,
+}
+
+/// SQL query for fetching a single publicly visible post by slug
 const GET_POST_BY_SLUG_QUERY: &str =
-    "SELECT p.content_id, p.slug, p.title, p.summary, p.published_at, p.updated_at, p.external_url, p.created_at, \
+    "SELECT p.content_id, p.slug, p.title, p.summary, p.status, p.published_at, p.updated_at, p.external_url, p.created_at, \
+     p.word_count, p.read_time_minutes, \
+     (SELECT json_group_array(t.name) FROM post_tags pt \
+      INNER JOIN tags t ON pt.tag_id = t.id \
+      WHERE pt.post_id = p.content_id) as tags \
+     FROM posts p \
+     WHERE p.slug = ? \
+     AND p.status IN ('published', 'scheduled', 'unlisted') \
+     AND p.published_at <= datetime('now') \
+     LIMIT 1";
+
+/// SQL query for fetching any post by slug, regardless of status or publish date
+const GET_ANY_POST_BY_SLUG_QUERY: &str =
+    "SELECT p.content_id, p.slug, p.title, p.summary, p.status, p.published_at, p.updated_at, p.external_url, p.created_at, \
+     p.word_count, p.read_time_minutes, \
      (SELECT json_group_array(t.name) FROM post_tags pt \
       INNER JOIN tags t ON pt.tag_id = t.id \
       WHERE pt.post_id = p.content_id) as tags \
@@ -269,54 +368,502 @@ const GET_POST_BY_SLUG_QUERY: &str =
      WHERE p.slug = ? LIMIT 1";
 
 /// Get a single post by slug
-pub async fn get_post_by_slug(db: &D1Database, slug: &str) -> Result<Option<Post>> {
-    let stmt = db.prepare(GET_POST_BY_SLUG_QUERY)
+pub async fn get_post_by_slug(db: &D1Database, slug: &str, visibility: Visibility) -> Result<Option<Post>> {
+    let query = match visibility {
+        Visibility::Public => GET_POST_BY_SLUG_QUERY,
+        Visibility::All => GET_ANY_POST_BY_SLUG_QUERY,
+    };
+    let stmt = db.prepare(query)
         .bind(&[slug.into()])?;
 
     let result = stmt.first::<Post>(None).await?;
     Ok(result)
 }
 
-/// SQL query for fetching all tags with usage counts
-const GET_ALL_TAGS_QUERY: &str =
-    "SELECT t.name as tag, COUNT(pt.post_id) as count \
-     FROM tags t \
-     INNER JOIN post_tags pt ON t.id = pt.tag_id \
-     INNER JOIN posts p ON pt.post_id = p.content_id \
-     WHERE p.published_at <= datetime('now') \
-     GROUP BY t.id, t.name \
-     ORDER BY t.name ASC";
+/// Columns of a tag: its metadata plus post (listed only) and project counts
+const TAG_COLUMNS: &str =
+    "t.name as tag, t.display_name, t.description, t.color, \
+     (SELECT COUNT(*) FROM post_tags pt \
+      INNER JOIN posts p ON pt.post_id = p.content_id \
+      WHERE pt.tag_id = t.id \
+      AND p.status IN ('published', 'scheduled') AND p.published_at <= datetime('now')) as post_count, \
+     (SELECT COUNT(*) FROM project_tags pt WHERE pt.tag_id = t.id) as project_count";
 
-/// Get all tags with their usage counts (only includes tags from published posts)
+/// Get all tags used by a listed post or a project, with their counts
 pub async fn get_all_tags(db: &D1Database) -> Result<Vec<TagWithCount>> {
-    let stmt = db.prepare(GET_ALL_TAGS_QUERY);
-    let results = stmt.all().await?;
+    let query = format!(
+        "SELECT * FROM (SELECT {} FROM tags t) \
+         WHERE post_count > 0 OR project_count > 0 \
+         ORDER BY tag ASC",
+        TAG_COLUMNS
+    );
+    let results = db.prepare(&query).all().await?;
     let tags: Vec<TagWithCount> = results.results()?;
     Ok(tags)
 }
 
+/// Get a single tag by name, whether or not anything uses it
+pub async fn get_tag(db: &D1Database, name: &str) -> Result<Option<TagWithCount>> {
+    let query = format!("SELECT {} FROM tags t WHERE t.name = ? LIMIT 1", TAG_COLUMNS);
+    let stmt = db.prepare(&query)
+        .bind(&[name.into()])?;
+
+    let result = stmt.first::<TagWithCount>(None).await?;
+    Ok(result)
+}
+
+/// Row holding a tag name
+#[derive(Deserialize)]
+struct TagNameRow {
+    name: String,
+}
+
+/// Replace tag aliases with the names of the tags they point at
+///
+/// Names that are not aliases are kept as they are; duplicates are dropped.
+pub async fn resolve_tag_aliases(db: &D1Database, tags: &[String]) -> Result<Vec<String>> {
+    let stmt = db.prepare(
+        "SELECT COALESCE((SELECT t.name FROM tag_aliases a INNER JOIN tags t ON a.tag_id = t.id \
+          WHERE a.alias = j.value), j.value) as name \
+         FROM json_each(?) j ORDER BY j.key",
+    )
+    .bind(&[json_list(tags)?.into()])?;
+
+    let rows: Vec<TagNameRow> = stmt.all().await?.results()?;
+    let mut names: Vec<String> = Vec::with_capacity(rows.len());
+    for row in rows {
+        if !names.contains(&row.name) {
+            names.push(row.name);
+        }
+    }
+    Ok(names)
+}
+
+/// Name of the tag an alias points at, or `None` if `alias` is not an alias
+pub async fn resolve_tag_alias(db: &D1Database, alias: &str) -> Result<Option<String>> {
+    let stmt = db.prepare(
+        "SELECT t.name FROM tag_aliases a INNER JOIN tags t ON a.tag_id = t.id WHERE a.alias = ? LIMIT 1",
+    )
+    .bind(&[alias.into()])?;
+
+    let result = stmt.first::<TagNameRow>(None).await?;
+    Ok(result.map(|row| row.name))
+}
+
+/// SQL query for fetching the listed posts carrying a tag
+const GET_POSTS_BY_TAG_QUERY: &str =
+    "SELECT p.content_id, p.slug, p.title, p.summary, p.published_at, p.external_url, \
+     p.word_count, p.read_time_minutes, \
+     (SELECT json_group_array(t.name) FROM post_tags pt \
+      INNER JOIN tags t ON pt.tag_id = t.id \
+      WHERE pt.post_id = p.content_id) as tags \
+     FROM posts p \
+     WHERE p.status IN ('published', 'scheduled') AND p.published_at <= datetime('now') \
+     AND p.content_id IN (SELECT pt.post_id FROM post_tags pt \
+         INNER JOIN tags t ON pt.tag_id = t.id WHERE t.name = ?) \
+     ORDER BY p.published_at DESC";
+
+/// Get every listed post carrying a tag, newest first
+pub async fn get_posts_by_tag(db: &D1Database, name: &str) -> Result<Vec<PostListItem>> {
+    let stmt = db.prepare(GET_POSTS_BY_TAG_QUERY)
+        .bind(&[name.into()])?;
+    let results = stmt.all().await?;
+    let posts: Vec<PostListItem> = results.results()?;
+    Ok(posts)
+}
+
+/// Resource a slug alias points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlugKind {
+    Post,
+    Project,
+}
+
+/// SQL query resolving an old post slug to the current slug of a publicly visible post
+const RESOLVE_POST_ALIAS_QUERY: &str =
+    "SELECT p.slug FROM slug_aliases a \
+     INNER JOIN posts p ON p.content_id = a.target_id \
+     WHERE a.kind = 'post' AND a.old_slug = ? \
+     AND p.status IN ('published', 'scheduled', 'unlisted') \
+     AND p.published_at <= datetime('now') \
+     LIMIT 1";
+
+/// SQL query resolving an old post slug to the current slug of any post
+const RESOLVE_ANY_POST_ALIAS_QUERY: &str =
+    "SELECT p.slug FROM slug_aliases a \
+     INNER JOIN posts p ON p.content_id = a.target_id \
+     WHERE a.kind = 'post' AND a.old_slug = ? \
+     LIMIT 1";
+
+/// SQL query resolving an old project slug to the project's current slug
+const RESOLVE_PROJECT_ALIAS_QUERY: &str =
+    "SELECT p.slug FROM slug_aliases a \
+     INNER JOIN projects p ON p.id = a.target_id \
+     WHERE a.kind = 'project' AND a.old_slug = ? \
+     LIMIT 1";
+
+/// Row holding a resolved slug
+#[derive(Deserialize)]
+struct SlugRow {
+    slug: String,
+}
+
+/// Resolve a slug that was renamed away to the resource's current slug
+///
+/// Post aliases only resolve to posts the caller could fetch directly, so a
+/// redirect never reveals the slug of a draft.
+pub async fn resolve_slug_alias(db: &D1Database, kind: SlugKind, slug: &str, visibility: Visibility) -> Result<Option<String>> {
+    let query = match (kind, visibility) {
+        (SlugKind::Post, Visibility::Public) => RESOLVE_POST_ALIAS_QUERY,
+        (SlugKind::Post, Visibility::All) => RESOLVE_ANY_POST_ALIAS_QUERY,
+        (SlugKind::Project, _) => RESOLVE_PROJECT_ALIAS_QUERY,
+    };
+    let stmt = db.prepare(query)
+        .bind(&[slug.into()])?;
+
+    let result = stmt.first::<SlugRow>(None).await?;
+    Ok(result.map(|row| row.slug))
+}
+
+// ============================================================================
+// Post Mutations
+// ============================================================================
+
+/// Row holding a post's content_id
+#[derive(Deserialize)]
+struct ContentIdRow {
+    content_id: String,
+}
+
+/// Look up the content_id for a slug, regardless of publish date
+pub async fn get_post_content_id(db: &D1Database, slug: &str) -> Result<Option<String>> {
+    let stmt = db.prepare("SELECT content_id FROM posts WHERE slug = ? LIMIT 1")
+        .bind(&[slug.into()])?;
+
+    let result = stmt.first::<ContentIdRow>(None).await?;
+    Ok(result.map(|row| row.content_id))
+}
+
+/// Id of the tag named `j.value` (from `json_each(?) j`), following aliases
+const TAG_ID_OF_JSON_VALUE: &str =
+    "COALESCE((SELECT a.tag_id FROM tag_aliases a WHERE a.alias = j.value), \
+     (SELECT t.id FROM tags t WHERE t.name = j.value))";
+
+/// Statement creating the tags in a JSON array that are neither tags nor aliases yet
+fn create_tags_statement(db: &D1Database, tags_json: &str) -> Result<D1PreparedStatement> {
+    db.prepare(
+        "INSERT OR IGNORE INTO tags (name) SELECT value FROM json_each(?) \
+         WHERE value NOT IN (SELECT alias FROM tag_aliases)",
+    )
+    .bind(&[tags_json.into()])
+}
+
+/// Statements that replace every tag link of a post with `tags`
+///
+/// Tags are passed as a JSON array and expanded with `json_each`, so the number
+/// of statements does not grow with the number of tags. Aliases link the tag
+/// they point at.
+fn replace_post_tags_statements(db: &D1Database, content_id: &str, tags: &[String]) -> Result<Vec<D1PreparedStatement>> {
+    let tags_json = json_list(tags)?;
+
+    Ok(vec![
+        db.prepare("DELETE FROM post_tags WHERE post_id = ?")
+            .bind(&[content_id.into()])?,
+        create_tags_statement(db, &tags_json)?,
+        db.prepare(format!(
+            "INSERT OR IGNORE INTO post_tags (post_id, tag_id) SELECT ?, {} FROM json_each(?) j",
+            TAG_ID_OF_JSON_VALUE
+        ))
+        .bind(&[content_id.into(), tags_json.into()])?,
+    ])
+}
+
+/// Insert a post and its tags in a single batch (D1 batches run as one transaction)
+pub async fn insert_post(db: &D1Database, content_id: &str, slug: &str, input: &PostInput) -> Result<()> {
+    let stats = reading_stats(&input.content);
+    let mut statements = vec![
+        db.prepare(
+            "INSERT INTO posts (content_id, slug, title, summary, status, published_at, external_url, \
+             word_count, read_time_minutes) \
+             VALUES (?, ?, ?, ?, ?, COALESCE(?, datetime('now')), ?, ?, ?)",
+        )
+        .bind(&[
+            content_id.into(),
+            slug.into(),
+            input.title.as_str().into(),
+            input.summary.as_str().into(),
+            input.status.unwrap_or_default().as_str().into(),
+            optional_text(input.published_at.as_deref()),
+            optional_text(input.external_url.as_deref()),
+            stats.word_count.into(),
+            stats.read_time_minutes.into(),
+        ])?,
+    ];
+    statements.extend(replace_post_tags_statements(db, content_id, &input.tags)?);
+    statements.push(index_body_statement(db, content_id, &input.content)?);
+
+    db.batch(statements).await?;
+    Ok(())
+}
+
+/// Row holding the highest recorded version of a post
+#[derive(Deserialize)]
+struct VersionRow {
+    version: Option<u32>,
+}
+
+/// Next free version number in a post's history (1 for a post never updated)
+pub async fn next_post_version(db: &D1Database, content_id: &str) -> Result<u32> {
+    let stmt = db.prepare("SELECT MAX(version) as version FROM post_versions WHERE post_id = ?")
+        .bind(&[content_id.into()])?;
+
+    let row = stmt.first::<VersionRow>(None).await?;
+    Ok(row.and_then(|r| r.version).unwrap_or(0) + 1)
+}
+
+/// Statement copying a post's current metadata and tags into `post_versions`
+fn snapshot_post_statement(db: &D1Database, content_id: &str, version: u32) -> Result<D1PreparedStatement> {
+    db.prepare(
+        "INSERT INTO post_versions (post_id, version, title, summary, status, published_at, external_url, tags) \
+         SELECT p.content_id, ?, p.title, p.summary, p.status, p.published_at, p.external_url, \
+         (SELECT json_group_array(t.name) FROM post_tags pt \
+          INNER JOIN tags t ON pt.tag_id = t.id \
+          WHERE pt.post_id = p.content_id) \
+         FROM posts p WHERE p.content_id = ?",
+    )
+    .bind(&[version.into(), content_id.into()])
+}
+
+/// Replace a post's metadata and tags in a single batch, bumping `updated_at`
+///
+/// The previous metadata is recorded as `version` in `post_versions` first.
+pub async fn update_post(db: &D1Database, content_id: &str, version: u32, input: &PostInput) -> Result<()> {
+    let stats = reading_stats(&input.content);
+    let mut statements = vec![
+        snapshot_post_statement(db, content_id, version)?,
+        db.prepare(
+            "UPDATE posts SET title = ?, summary = ?, status = COALESCE(?, status), \
+             published_at = COALESCE(?, published_at), external_url = ?, \
+             word_count = ?, read_time_minutes = ?, updated_at = datetime('now') \
+             WHERE content_id = ?",
+        )
+        .bind(&[
+            input.title.as_str().into(),
+            input.summary.as_str().into(),
+            optional_text(input.status.map(PostStatus::as_str)),
+            optional_text(input.published_at.as_deref()),
+            optional_text(input.external_url.as_deref()),
+            stats.word_count.into(),
+            stats.read_time_minutes.into(),
+            content_id.into(),
+        ])?,
+    ];
+    statements.extend(replace_post_tags_statements(db, content_id, &input.tags)?);
+    statements.push(index_body_statement(db, content_id, &input.content)?);
+
+    db.batch(statements).await?;
+    Ok(())
+}
+
+/// Statement storing a post's markdown body in the full-text index
+///
+/// The index row itself is created by the `posts_fts_insert` trigger.
+fn index_body_statement(db: &D1Database, content_id: &str, body: &str) -> Result<D1PreparedStatement> {
+    db.prepare("UPDATE posts_fts SET body = ? WHERE content_id = ?")
+        .bind(&[body.into(), content_id.into()])
+}
+
+/// Delete a post (cascades to post_tags; a trigger removes it from posts_fts)
+pub async fn delete_post(db: &D1Database, content_id: &str) -> Result<()> {
+    db.prepare("DELETE FROM posts WHERE content_id = ?")
+        .bind(&[content_id.into()])?
+        .run()
+        .await?;
+    Ok(())
+}
+
+/// Bind an optional string as TEXT or NULL
+fn optional_text(value: Option<&str>) -> wasm_bindgen::JsValue {
+    value.map(Into::into).unwrap_or(wasm_bindgen::JsValue::NULL)
+}
+
+/// Serialize a list for binding as a `json_each` parameter
+fn json_list<T: serde::Serialize>(items: &[T]) -> Result<String> {
+    serde_json::to_string(items)
+        .map_err(|_| Error::RustError("Failed to serialize list".to_string()))
+}
+
 // ============================================================================
 // Project Queries
 // ============================================================================
 
-/// SQL query for fetching all projects
-const GET_ALL_PROJECTS_QUERY: &str =
-    "SELECT p.id, p.slug, p.name, p.description, p.stage, p.open_to_contributors, \
+/// Sort field for projects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectSortField {
+    Name,
+    UpdatedAt,
+    CreatedAt,
+}
+
+impl ProjectSortField {
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "name" => Self::Name,
+            "created_at" => Self::CreatedAt,
+            _ => Self::UpdatedAt, // Default to updated_at
+        }
+    }
+
+    pub fn to_sql(self) -> &'static str {
+        match self {
+            Self::Name => "p.name COLLATE NOCASE",
+            Self::UpdatedAt => "p.updated_at",
+            Self::CreatedAt => "p.created_at",
+        }
+    }
+
+    /// Order used when the request does not give one: A-Z for names, newest first for dates
+    pub fn default_order(self) -> SortOrder {
+        match self {
+            Self::Name => SortOrder::Asc,
+            Self::UpdatedAt | Self::CreatedAt => SortOrder::Desc,
+        }
+    }
+}
+
+/// Query parameters for listing projects
+pub struct ListProjectsParams {
+    pub page: u32,
+    pub limit: u32,
+    pub stages: Option<Vec<ProjectStage>>,
+    pub tags: Option<Vec<String>>,
+    pub open_to_contributors: Option<bool>,
+    pub search: Option<String>,
+    pub sort_by: ProjectSortField,
+    /// Defaults to the sort field's `default_order`
+    pub order: Option<SortOrder>,
+}
+
+impl Default for ListProjectsParams {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            limit: 50,
+            stages: None,
+            tags: None,
+            open_to_contributors: None,
+            search: None,
+            sort_by: ProjectSortField::UpdatedAt,
+            order: None,
+        }
+    }
+}
+
+/// Columns of a project, with its tags and URLs aggregated as JSON
+const PROJECT_COLUMNS: &str =
+    "p.id, p.slug, p.name, p.description, p.stage, p.open_to_contributors, \
      p.readme_url, p.created_at, p.updated_at, \
      (SELECT json_group_array(t.name) FROM project_tags pt \
       INNER JOIN tags t ON pt.tag_id = t.id \
       WHERE pt.project_id = p.id) as tags, \
      (SELECT json_group_array(json_object('label', pu.label, 'url', pu.url)) \
-      FROM project_urls pu WHERE pu.project_id = p.id) as urls \
-     FROM projects p \
-     ORDER BY p.updated_at DESC";
+      FROM project_urls pu WHERE pu.project_id = p.id) as urls";
 
-/// Get all projects
-pub async fn get_all_projects(db: &D1Database) -> Result<Vec<Project>> {
-    let stmt = db.prepare(GET_ALL_PROJECTS_QUERY);
-    let results = stmt.all().await?;
+/// Build the `WHERE` clause for the project filters and the values bound to it
+///
+/// Every user-supplied value is bound as a parameter; only the boolean filter
+/// is written into the SQL.
+fn project_filters(params: &ListProjectsParams) -> Result<(String, Vec<String>)> {
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<String> = Vec::new();
+
+    if let Some(stages) = &params.stages {
+        clauses.push("p.stage IN (SELECT value FROM json_each(?))".to_string());
+        values.push(json_list(&stages.iter().map(|stage| stage.as_str()).collect::<Vec<_>>())?);
+    }
+    if let Some(tags) = &params.tags {
+        clauses.push(
+            "EXISTS (SELECT 1 FROM project_tags pt INNER JOIN tags t ON pt.tag_id = t.id \
+             WHERE pt.project_id = p.id AND t.name IN (SELECT value FROM json_each(?)))"
+                .to_string(),
+        );
+        values.push(json_list(tags)?);
+    }
+    if let Some(open) = params.open_to_contributors {
+        clauses.push(format!("p.open_to_contributors = {}", i32::from(open)));
+    }
+    if let Some(search) = &params.search {
+        // Every term must appear in the name or the description
+        for term in search.split_whitespace().take(MAX_SEARCH_TERMS) {
+            clauses.push("(p.name LIKE ? ESCAPE '\\' OR p.description LIKE ? ESCAPE '\\')".to_string());
+            let pattern = like_pattern(term);
+            values.push(pattern.clone());
+            values.push(pattern);
+        }
+    }
+
+    if clauses.is_empty() {
+        return Ok((String::new(), values));
+    }
+    Ok((format!(" WHERE {}", clauses.join(" AND ")), values))
+}
+
+/// `LIKE` pattern matching `term` anywhere, with `%`, `_` and `\` escaped
+fn like_pattern(term: &str) -> String {
+    let mut pattern = String::with_capacity(term.len() + 2);
+    pattern.push('%');
+    for c in term.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// List projects with pagination and filtering
+pub async fn list_projects(db: &D1Database, mut params: ListProjectsParams) -> Result<(Vec<Project>, Pagination)> {
+    if let Some(tags) = params.tags.take() {
+        params.tags = Some(resolve_tag_aliases(db, &tags).await?);
+    }
+
+    let offset = (params.page - 1) * params.limit;
+    let (filters, values) = project_filters(&params)?;
+    let bindings: Vec<wasm_bindgen::JsValue> = values.iter().map(|value| value.as_str().into()).collect();
+
+    let count_query = format!("SELECT COUNT(*) as count FROM projects p{}", filters);
+    let result = db.prepare(&count_query)
+        .bind(&bindings)?
+        .first::<CountResult>(None)
+        .await?;
+    let total = result.map(|c| c.count).unwrap_or(0);
+
+    let order = params.order.unwrap_or(params.sort_by.default_order());
+    let select_query = format!(
+        "SELECT {} FROM projects p{} ORDER BY {} {}, p.slug LIMIT ? OFFSET ?",
+        PROJECT_COLUMNS,
+        filters,
+        params.sort_by.to_sql(),
+        order.to_sql()
+    );
+    let mut select_bindings = bindings;
+    select_bindings.push(params.limit.into());
+    select_bindings.push(offset.into());
+    let results = db.prepare(&select_query)
+        .bind(&select_bindings)?
+        .all()
+        .await?;
     let projects: Vec<Project> = results.results()?;
-    Ok(projects)
+
+    let pagination = Pagination {
+        page: params.page,
+        limit: params.limit,
+        total,
+        has_next: (params.page * params.limit) < total,
+    };
+
+    Ok((projects, pagination))
 }
 
 /// SQL query for fetching a single project by slug
@@ -339,3 +886,159 @@ pub async fn get_project_by_slug(db: &D1Database, slug: &str) -> Result<Option<P
     let result = stmt.first::<Project>(None).await?;
     Ok(result)
 }
+
+/// Get every project carrying a tag, most recently updated first
+pub async fn get_projects_by_tag(db: &D1Database, name: &str) -> Result<Vec<Project>> {
+    let query = format!(
+        "SELECT {} FROM projects p \
+         WHERE p.id IN (SELECT pt.project_id FROM project_tags pt \
+             INNER JOIN tags t ON pt.tag_id = t.id WHERE t.name = ?) \
+         ORDER BY p.updated_at DESC",
+        PROJECT_COLUMNS
+    );
+    let stmt = db.prepare(&query)
+        .bind(&[name.into()])?;
+    let results = stmt.all().await?;
+    let projects: Vec<Project> = results.results()?;
+    Ok(projects)
+}
+
+/// Apply a project patch in a single batch, bumping `updated_at`
+///
+/// Fields missing from the patch keep their value; URLs with a removed or
+/// re-added label are deleted before the new URLs are inserted.
+pub async fn update_project(db: &D1Database, project_id: &str, patch: &ProjectPatch) -> Result<()> {
+    let mut statements = vec![
+        db.prepare(
+            "UPDATE projects SET name = COALESCE(?, name), description = COALESCE(?, description), \
+             stage = COALESCE(?, stage), readme_url = COALESCE(?, readme_url), \
+             open_to_contributors = COALESCE(?, open_to_contributors), updated_at = datetime('now') \
+             WHERE id = ?",
+        )
+        .bind(&[
+            optional_text(patch.name.as_deref()),
+            optional_text(patch.description.as_deref()),
+            optional_text(patch.stage.map(|stage| stage.as_str())),
+            optional_text(patch.readme_url.as_deref()),
+            patch.open_to_contributors.map(|open| i32::from(open).into()).unwrap_or(wasm_bindgen::JsValue::NULL),
+            project_id.into(),
+        ])?,
+    ];
+
+    if !patch.remove_tags.is_empty() {
+        statements.push(
+            db.prepare(format!(
+                "DELETE FROM project_tags WHERE project_id = ? \
+                 AND tag_id IN (SELECT {} FROM json_each(?) j)",
+                TAG_ID_OF_JSON_VALUE
+            ))
+            .bind(&[project_id.into(), json_list(&patch.remove_tags)?.into()])?,
+        );
+    }
+    if !patch.add_tags.is_empty() {
+        let tags_json = json_list(&patch.add_tags)?;
+        statements.push(create_tags_statement(db, &tags_json)?);
+        statements.push(
+            db.prepare(format!(
+                "INSERT OR IGNORE INTO project_tags (project_id, tag_id) SELECT ?, {} FROM json_each(?) j",
+                TAG_ID_OF_JSON_VALUE
+            ))
+            .bind(&[project_id.into(), tags_json.into()])?,
+        );
+    }
+
+    let labels: Vec<&str> = patch.replaced_url_labels().collect();
+    if !labels.is_empty() {
+        statements.push(
+            db.prepare("DELETE FROM project_urls WHERE project_id = ? AND label IN (SELECT value FROM json_each(?))")
+                .bind(&[project_id.into(), json_list(&labels)?.into()])?,
+        );
+    }
+    for url in &patch.add_urls {
+        statements.push(
+            db.prepare("INSERT INTO project_urls (project_id, label, url) VALUES (?, ?, ?)")
+                .bind(&[project_id.into(), url.label.as_str().into(), url.url.as_str().into()])?,
+        );
+    }
+
+    db.batch(statements).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query_quotes_terms() {
+        assert_eq!(fts_query("rust"), "\"rust\"*");
+        assert_eq!(fts_query("rust async"), "\"rust\" \"async\"*");
+        assert_eq!(fts_query("  rust   async  "), "\"rust\" \"async\"*");
+    }
+
+    #[test]
+    fn test_fts_query_neutralizes_operators() {
+        // FTS5 syntax is treated as literal terms
+        assert_eq!(fts_query("title:rust OR NEAR"), "\"title:rust\" \"OR\" \"NEAR\"*");
+        assert_eq!(fts_query("\"quoted\" phrase"), "\"quoted\" \"phrase\"*");
+        assert_eq!(fts_query("\"\""), "\"\"");
+    }
+
+    #[test]
+    fn test_fts_query_limits_terms() {
+        let query = fts_query("a b c d e f g h i j k l");
+        assert_eq!(query.matches('"').count(), MAX_SEARCH_TERMS * 2);
+    }
+
+    #[test]
+    fn test_highlight_snippet() {
+        assert_eq!(
+            highlight_snippet("learn \u{2}rust\u{3} today"),
+            "learn <mark>rust</mark> today"
+        );
+        assert_eq!(
+            highlight_snippet("<script>\u{2}alert\u{3}</script>"),
+            "&lt;script&gt;<mark>alert</mark>&lt;/script&gt;"
+        );
+    }
+
+    #[test]
+    fn test_project_filters_empty() {
+        let (sql, values) = project_filters(&ListProjectsParams::default()).unwrap();
+        assert_eq!(sql, "");
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn test_project_filters_bind_values_in_order() {
+        let params = ListProjectsParams {
+            stages: Some(vec![ProjectStage::Active, ProjectStage::Maintained]),
+            tags: Some(vec!["rust".to_string()]),
+            open_to_contributors: Some(true),
+            search: Some("cli tool".to_string()),
+            ..Default::default()
+        };
+        let (sql, values) = project_filters(&params).unwrap();
+
+        assert!(sql.starts_with(" WHERE p.stage IN (SELECT value FROM json_each(?)) AND EXISTS"));
+        assert!(sql.contains("p.open_to_contributors = 1"));
+        assert_eq!(sql.matches('?').count(), values.len());
+        assert_eq!(
+            values,
+            vec![r#"["active","maintained"]"#, r#"["rust"]"#, "%cli%", "%cli%", "%tool%", "%tool%"]
+        );
+    }
+
+    #[test]
+    fn test_like_pattern_escapes_wildcards() {
+        assert_eq!(like_pattern("rust"), "%rust%");
+        assert_eq!(like_pattern("100%_\\"), "%100\\%\\_\\\\%");
+    }
+
+    #[test]
+    fn test_project_sort_default_order() {
+        assert!(matches!(ProjectSortField::from_str("name").default_order(), SortOrder::Asc));
+        assert!(matches!(ProjectSortField::from_str("bogus"), ProjectSortField::UpdatedAt));
+        assert!(matches!(ProjectSortField::UpdatedAt.default_order(), SortOrder::Desc));
+    }
+}
@@ -0,0 +1,196 @@
+// Markdown rendering: CommonMark + GFM to sanitized HTML with heading anchors
+use std::collections::HashSet;
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag, TagEnd};
+use models::post::TocEntry;
+
+/// Rendered HTML plus the table of contents collected from its headings
+pub struct RenderedMarkdown {
+    pub html: String,
+    pub toc: Vec<TocEntry>,
+}
+
+/// Render markdown to sanitized HTML
+///
+/// Every heading gets a unique `id` derived from its text and a trailing
+/// `<a class="anchor">` self-link; the same ids are returned in the table of contents.
+/// Raw HTML in the markdown goes through the same sanitizer as the generated output.
+pub fn render_markdown(markdown: &str) -> RenderedMarkdown {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_GFM;
+
+    let mut events: Vec<Event> = Vec::new();
+    let mut toc = Vec::new();
+    let mut ids = HeadingIds::default();
+
+    // Index of the open heading's Start event, plus the text seen inside it
+    let mut open_heading: Option<usize> = None;
+    let mut heading_text = String::new();
+
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::Heading { .. }) => {
+                open_heading = Some(events.len());
+                heading_text.clear();
+                events.push(event);
+            }
+            Event::End(TagEnd::Heading(level)) => {
+                if let Some(start) = open_heading.take() {
+                    let title = heading_text.trim().to_string();
+                    let id = ids.unique(&slugify(&title));
+
+                    if let Event::Start(Tag::Heading { id: heading_id, .. }) = &mut events[start] {
+                        *heading_id = Some(CowStr::from(id.clone()));
+                    }
+                    events.push(Event::InlineHtml(CowStr::from(format!(
+                        "<a class=\"anchor\" href=\"#{}\">#</a>",
+                        id
+                    ))));
+
+                    toc.push(TocEntry { level: level as u8, id, title });
+                }
+                events.push(event);
+            }
+            Event::Text(ref text) | Event::Code(ref text) if open_heading.is_some() => {
+                heading_text.push_str(text);
+                events.push(event);
+            }
+            _ => events.push(event),
+        }
+    }
+
+    let mut unsafe_html = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, events.into_iter());
+
+    RenderedMarkdown {
+        html: sanitizer().clean(&unsafe_html).to_string(),
+        toc,
+    }
+}
+
+/// Ammonia's defaults plus the attributes the renderer itself emits
+fn sanitizer() -> ammonia::Builder<'static> {
+    let mut builder = ammonia::Builder::default();
+    builder
+        .add_tags(["input"])
+        .add_tag_attributes("h1", ["id"])
+        .add_tag_attributes("h2", ["id"])
+        .add_tag_attributes("h3", ["id"])
+        .add_tag_attributes("h4", ["id"])
+        .add_tag_attributes("h5", ["id"])
+        .add_tag_attributes("h6", ["id"])
+        // Heading anchors
+        .add_tag_attributes("a", ["class"])
+        // Fenced code language (`language-rust`)
+        .add_tag_attributes("code", ["class"])
+        // GFM alerts (`markdown-alert-note`)
+        .add_tag_attributes("blockquote", ["class"])
+        // Footnotes
+        .add_tag_attributes("sup", ["class"])
+        .add_tag_attributes("div", ["class", "id"])
+        // Table column alignment
+        .add_tag_attributes("th", ["style"])
+        .add_tag_attributes("td", ["style"])
+        .filter_style_properties(["text-align"].into_iter().collect())
+        // Task list checkboxes
+        .add_tag_attributes("input", ["checked", "disabled"])
+        .add_tag_attribute_values("input", "type", ["checkbox"]);
+    builder
+}
+
+/// Turn heading text into a URL fragment: lowercase alphanumerics joined by hyphens
+fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Deduplicates heading ids the way GitHub does (`intro`, `intro-1`, `intro-2`, ...)
+#[derive(Default)]
+struct HeadingIds {
+    seen: HashSet<String>,
+}
+
+impl HeadingIds {
+    fn unique(&mut self, base: &str) -> String {
+        let mut id = base.to_string();
+        let mut suffix = 0;
+        while !self.seen.insert(id.clone()) {
+            suffix += 1;
+            id = format!("{}-{}", base, suffix);
+        }
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Getting Started"), "getting-started");
+        assert_eq!(slugify("What's new in Rust 2024?"), "whats-new-in-rust-2024");
+        assert_eq!(slugify("  snake_case  and--dashes "), "snake-case-and-dashes");
+        assert_eq!(slugify("Über Café"), "über-café");
+        assert_eq!(slugify("???"), "section");
+    }
+
+    #[test]
+    fn test_heading_ids_are_unique() {
+        let mut ids = HeadingIds::default();
+        assert_eq!(ids.unique("intro"), "intro");
+        assert_eq!(ids.unique("intro"), "intro-1");
+        assert_eq!(ids.unique("intro-1"), "intro-1-1");
+        assert_eq!(ids.unique("intro"), "intro-2");
+    }
+
+    #[test]
+    fn test_render_headings_and_toc() {
+        let rendered = render_markdown("# Title\n\n## Setup `cargo`\n\ntext\n\n## Setup `cargo`\n");
+        assert!(rendered.html.contains("<h1 id=\"title\">Title<a class=\"anchor\" href=\"#title\" rel=\"noopener noreferrer\">#</a></h1>"));
+        assert!(rendered.html.contains("<h2 id=\"setup-cargo\">"));
+        assert!(rendered.html.contains("<h2 id=\"setup-cargo-1\">"));
+
+        let toc: Vec<_> = rendered.toc.iter().map(|e| (e.level, e.id.as_str(), e.title.as_str())).collect();
+        assert_eq!(toc, vec![
+            (1, "title", "Title"),
+            (2, "setup-cargo", "Setup cargo"),
+            (2, "setup-cargo-1", "Setup cargo"),
+        ]);
+    }
+
+    #[test]
+    fn test_render_gfm() {
+        let markdown = "| a | b |\n|:-:|---|\n| 1 | 2 |\n\n~~old~~\n\n- [x] done\n\n```rust\nfn main() {}\n```\n";
+        let html = render_markdown(markdown).html;
+        assert!(html.contains("<th style=\"text-align:center\">a</th>"));
+        assert!(html.contains("<del>old</del>"));
+        assert!(html.contains("<input disabled=\"\" type=\"checkbox\" checked=\"\">"));
+        assert!(html.contains("<code class=\"language-rust\">"));
+    }
+
+    #[test]
+    fn test_render_sanitizes_raw_html() {
+        let markdown = "<script>alert(1)</script>\n\n<img src=x onerror=alert(1)>\n\n[link](javascript:alert(1))\n\n<input type=\"text\" value=\"x\">";
+        let html = render_markdown(markdown).html;
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("type=\"text\""));
+    }
+}
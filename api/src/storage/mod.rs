@@ -1,3 +1,6 @@
 pub mod d1;
 pub mod r2;
 pub mod blog;
+pub mod markdown;
+pub mod readme;
+pub mod rate_limiter;
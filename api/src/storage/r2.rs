@@ -1,15 +1,27 @@
 use worker::*;
 
+/// R2 key for a post's markdown body
+fn content_key(content_id: &str) -> String {
+    format!("posts/{}.md", content_id)
+}
+
+/// R2 key for a snapshot of a post's markdown
+fn version_key(content_id: &str, version: u32) -> String {
+    format!("{}{}.md", version_prefix(content_id), version)
+}
+
+/// R2 prefix holding every snapshot of a post
+fn version_prefix(content_id: &str) -> String {
+    format!("post-versions/{}/", content_id)
+}
+
 /// Retrieve markdown content from R2
 ///
-/// Note: Admin operations (PUT, DELETE, LIST) should be done via wrangler CLI:
-/// - wrangler r2 object put cloud posts/uuid.md --file post.md
-/// - wrangler r2 object delete cloud posts/uuid.md
+/// Note: Image uploads and bucket listing are still done via wrangler CLI:
+/// - wrangler r2 object put cloud posts/{slug}/image.png --file image.png
 /// - wrangler r2 object list cloud --prefix posts/
 pub async fn get_content(bucket: &Bucket, content_id: &str) -> Result<Option<String>> {
-    let key = format!("posts/{}.md", content_id);
-
-    match bucket.get(&key).execute().await? {
+    match bucket.get(content_key(content_id)).execute().await? {
         Some(object) => {
             let content = object.body()
                 .ok_or_else(|| Error::RustError("Failed to get object body".to_string()))?
@@ -20,3 +32,38 @@ pub async fn get_content(bucket: &Bucket, content_id: &str) -> Result<Option<Str
         None => Ok(None),
     }
 }
+
+/// Store markdown content in R2, overwriting any existing object
+pub async fn put_content(bucket: &Bucket, content_id: &str, content: &str) -> Result<()> {
+    put_markdown(bucket, content_key(content_id), content).await
+}
+
+/// Store a snapshot of a post's markdown under its version number
+pub async fn put_version_content(bucket: &Bucket, content_id: &str, version: u32, content: &str) -> Result<()> {
+    put_markdown(bucket, version_key(content_id, version), content).await
+}
+
+/// Delete every stored snapshot of a post
+pub async fn delete_version_contents(bucket: &Bucket, content_id: &str) -> Result<()> {
+    let listing = bucket.list().prefix(version_prefix(content_id)).execute().await?;
+    for object in listing.objects() {
+        bucket.delete(object.key()).await?;
+    }
+    Ok(())
+}
+
+async fn put_markdown(bucket: &Bucket, key: String, content: &str) -> Result<()> {
+    bucket.put(key, content.to_string())
+        .http_metadata(HttpMetadata {
+            content_type: Some("text/markdown; charset=utf-8".to_string()),
+            ..Default::default()
+        })
+        .execute()
+        .await?;
+    Ok(())
+}
+
+/// Delete markdown content from R2 (no-op if the object does not exist)
+pub async fn delete_content(bucket: &Bucket, content_id: &str) -> Result<()> {
+    bucket.delete(content_key(content_id)).await
+}
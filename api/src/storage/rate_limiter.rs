@@ -0,0 +1,36 @@
+// Durable Object holding the rate limit buckets of one client IP
+//
+// Buckets are kept in memory only: if the object is evicted, the client simply
+// starts again with full buckets.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use worker::*;
+
+use crate::middleware::{TakeRequest, TokenBucket};
+
+#[durable_object(fetch)]
+pub struct RateLimiter {
+    buckets: RefCell<HashMap<String, TokenBucket>>,
+}
+
+impl DurableObject for RateLimiter {
+    fn new(_state: State, _env: Env) -> Self {
+        Self { buckets: RefCell::new(HashMap::new()) }
+    }
+
+    async fn fetch(&self, mut req: Request) -> Result<Response> {
+        let take: TakeRequest = match req.json().await {
+            Ok(take) => take,
+            Err(_) => return Response::error("Invalid rate limit request", 400),
+        };
+
+        let now = Date::now().as_millis();
+        let decision = self.buckets.borrow_mut()
+            .entry(take.bucket)
+            .or_insert_with(|| TokenBucket::full(&take.budget, now))
+            .take(&take.budget, now);
+
+        Response::from_json(&decision)
+    }
+}
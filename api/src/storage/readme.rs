@@ -0,0 +1,363 @@
+// Project READMEs fetched from upstream and cached in R2
+use std::collections::HashMap;
+use regex::Regex;
+use worker::*;
+use models::project::Project;
+
+/// How long a cached README is served without revalidating upstream
+const FRESH_FOR_MS: u64 = 10 * 60 * 1000;
+
+/// R2 key for a project's cached README
+fn readme_key(project_id: &str) -> String {
+    format!("readmes/{}.md", project_id)
+}
+
+/// README body as stored in R2, with the metadata needed to revalidate it
+struct CachedReadme {
+    body: String,
+    /// Upstream `ETag`, sent back as `If-None-Match`
+    etag: Option<String>,
+    /// `readme_url` the body was fetched from; a changed URL invalidates the cache
+    source_url: String,
+    /// Milliseconds since the epoch
+    fetched_at: u64,
+}
+
+async fn get_cached(bucket: &Bucket, project_id: &str) -> Result<Option<CachedReadme>> {
+    let Some(object) = bucket.get(readme_key(project_id)).execute().await? else {
+        return Ok(None);
+    };
+
+    let metadata = object.custom_metadata()?;
+    let body = object.body()
+        .ok_or_else(|| Error::RustError("Failed to get object body".to_string()))?
+        .text()
+        .await?;
+
+    Ok(Some(CachedReadme {
+        body,
+        etag: metadata.get("etag").cloned(),
+        source_url: metadata.get("source_url").cloned().unwrap_or_default(),
+        fetched_at: metadata.get("fetched_at").and_then(|at| at.parse().ok()).unwrap_or(0),
+    }))
+}
+
+async fn put_cached(bucket: &Bucket, project_id: &str, readme: &CachedReadme) -> Result<()> {
+    let mut metadata = HashMap::from([
+        ("source_url".to_string(), readme.source_url.clone()),
+        ("fetched_at".to_string(), readme.fetched_at.to_string()),
+    ]);
+    if let Some(etag) = &readme.etag {
+        metadata.insert("etag".to_string(), etag.clone());
+    }
+
+    bucket.put(readme_key(project_id), readme.body.clone())
+        .http_metadata(HttpMetadata {
+            content_type: Some("text/markdown; charset=utf-8".to_string()),
+            ..Default::default()
+        })
+        .custom_metadata(metadata)
+        .execute()
+        .await?;
+    Ok(())
+}
+
+/// Get a project's README, with relative links made absolute
+///
+/// A cached copy younger than `FRESH_FOR_MS` is returned as is; an older one
+/// is revalidated with `If-None-Match`. When upstream fails, the cached copy
+/// is served regardless of its age. Returns `None` if the project has no
+/// README or upstream reports it missing.
+pub async fn get_readme(bucket: &Bucket, project: &Project) -> Result<Option<String>> {
+    let Some(base) = ReadmeBase::from_url(&project.readme_url) else {
+        return Ok(None);
+    };
+    let now = Date::now().as_millis();
+
+    let cached = match get_cached(bucket, &project.id).await {
+        Ok(cached) => cached.filter(|c| c.source_url == project.readme_url),
+        Err(e) => {
+            console_error!("Failed to read cached README for '{}': {:?}", project.slug, e);
+            None
+        }
+    };
+    if let Some(cached) = &cached
+        && now.saturating_sub(cached.fetched_at) < FRESH_FOR_MS
+    {
+        return Ok(Some(cached.body.clone()));
+    }
+
+    let mut response = match fetch_upstream(&base.fetch_url, cached.as_ref().and_then(|c| c.etag.as_deref())).await {
+        Ok(response) => response,
+        Err(e) => {
+            console_error!("Failed to fetch README for '{}': {:?}", project.slug, e);
+            return fallback(cached, e);
+        }
+    };
+
+    let readme = match (response.status_code(), cached) {
+        (304, Some(mut cached)) => {
+            cached.fetched_at = now;
+            cached
+        }
+        (200, _) => CachedReadme {
+            body: rewrite_relative_urls(&response.text().await?, &base),
+            etag: response.headers().get("ETag")?,
+            source_url: project.readme_url.clone(),
+            fetched_at: now,
+        },
+        (404, _) => return Ok(None),
+        (status, cached) => {
+            console_error!("README for '{}' returned HTTP {}", project.slug, status);
+            return fallback(cached, Error::RustError(format!("Upstream returned HTTP {}", status)));
+        }
+    };
+
+    // A failed cache write only costs a refetch next time
+    if let Err(e) = put_cached(bucket, &project.id, &readme).await {
+        console_error!("Failed to cache README for '{}': {:?}", project.slug, e);
+    }
+    Ok(Some(readme.body))
+}
+
+async fn fetch_upstream(url: &str, etag: Option<&str>) -> Result<Response> {
+    let headers = Headers::new();
+    headers.set("User-Agent", "werdxz-api")?;
+    if let Some(etag) = etag {
+        headers.set("If-None-Match", etag)?;
+    }
+
+    let mut init = RequestInit::new();
+    init.with_method(Method::Get).with_headers(headers);
+    Fetch::Request(Request::new_with_init(url, &init)?).send().await
+}
+
+/// Serve the cached copy when upstream is unavailable, or report `error`
+fn fallback(cached: Option<CachedReadme>, error: Error) -> Result<Option<String>> {
+    match cached {
+        Some(cached) => Ok(Some(cached.body)),
+        None => Err(error),
+    }
+}
+
+/// Where a README is fetched from and what its relative URLs resolve against
+#[derive(Debug, PartialEq, Eq)]
+struct ReadmeBase {
+    fetch_url: String,
+    /// Prefix for links, up to the repository (or host) root
+    link_root: String,
+    /// Prefix for images, up to the repository (or host) root
+    image_root: String,
+    /// Directory of the README below the root, empty or ending in `/`
+    dir: String,
+}
+
+impl ReadmeBase {
+    /// Derive the bases from a `readme_url`
+    ///
+    /// GitHub URLs (raw, blob or plain repository) resolve links to the
+    /// repository's blob pages and images to raw files; any other URL resolves
+    /// both against its own host.
+    fn from_url(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let (scheme, rest) = url.split_once("://")?;
+        if scheme != "https" && scheme != "http" {
+            return None;
+        }
+        let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        let github = match (host, segments.as_slice()) {
+            // raw.githubusercontent.com/{owner}/{repo}/{ref}/{path}
+            ("raw.githubusercontent.com", [owner, repo, git_ref, file @ ..]) if !file.is_empty() => {
+                Some((*owner, *repo, *git_ref, file.join("/")))
+            }
+            // github.com/{owner}/{repo}/blob/{ref}/{path}
+            ("github.com", [owner, repo, "blob", git_ref, file @ ..]) if !file.is_empty() => {
+                Some((*owner, *repo, *git_ref, file.join("/")))
+            }
+            // github.com/{owner}/{repo}
+            ("github.com", [owner, repo]) => Some((*owner, *repo, "HEAD", "README.md".to_string())),
+            _ => None,
+        };
+
+        Some(match github {
+            Some((owner, repo, git_ref, file)) => {
+                let image_root = format!("https://raw.githubusercontent.com/{}/{}/{}/", owner, repo, git_ref);
+                Self {
+                    fetch_url: format!("{}{}", image_root, file),
+                    link_root: format!("https://github.com/{}/{}/blob/{}/", owner, repo, git_ref),
+                    image_root,
+                    dir: parent_dir(&file),
+                }
+            }
+            None => {
+                let root = format!("{}://{}/", scheme, host);
+                Self {
+                    fetch_url: url.to_string(),
+                    link_root: root.clone(),
+                    image_root: root,
+                    dir: parent_dir(path),
+                }
+            }
+        })
+    }
+
+    /// Resolve a relative URL found in the README, or `None` if it is already absolute
+    fn resolve(&self, url: &str, image: bool) -> Option<String> {
+        if !is_relative(url) {
+            return None;
+        }
+        let root = if image { &self.image_root } else { &self.link_root };
+        let path = match url.strip_prefix('/') {
+            Some(from_root) => from_root.to_string(),
+            None => format!("{}{}", self.dir, url),
+        };
+        Some(format!("{}{}", root, normalize_path(&path)))
+    }
+}
+
+/// Directory part of a path, ending in `/` (or empty)
+fn parent_dir(path: &str) -> String {
+    match path.rfind('/') {
+        Some(i) => path[..=i].trim_start_matches('/').to_string(),
+        None => String::new(),
+    }
+}
+
+/// Whether a URL is relative to the document (no scheme, not protocol-relative, not a fragment)
+fn is_relative(url: &str) -> bool {
+    if url.is_empty() || url.starts_with('#') || url.starts_with("//") {
+        return false;
+    }
+    // `mailto:`, `data:`, `https:` ...; a colon after the first `/` belongs to the path
+    match url.find(':') {
+        Some(i) => url[..i].contains('/'),
+        None => true,
+    }
+}
+
+/// Collapse `.` and `..` segments (never climbing above the root)
+fn normalize_path(path: &str) -> String {
+    let (path, suffix) = match path.find(['?', '#']) {
+        Some(i) => path.split_at(i),
+        None => (path, ""),
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    // Segments that were dropped must not leave a leading slash behind
+    while segments.first() == Some(&"") && segments.len() > 1 {
+        segments.remove(0);
+    }
+    format!("{}{}", segments.join("/"), suffix)
+}
+
+/// Make relative URLs in markdown links/images and HTML `src`/`href` attributes absolute
+fn rewrite_relative_urls(markdown: &str, base: &ReadmeBase) -> String {
+    // ![alt](url "title") and [text](url "title"); 1=`!` 2=text 3=url 4=title
+    let markdown_re = Regex::new(r#"(!?)\[([^\]]*)\]\(\s*([^)\s]+)((?:\s+"[^"]*")?)\s*\)"#).unwrap();
+    // <img src="..."> and <a href="...">; 1=tag 2=everything up to the value 3=url
+    let html_re = Regex::new(r#"(?i)<(img|source|a)\b([^>]*?\s(?:src|href)\s*=\s*["'])([^"']+)"#).unwrap();
+
+    let rewritten = markdown_re.replace_all(markdown, |caps: &regex::Captures| {
+        match base.resolve(&caps[3], !caps[1].is_empty()) {
+            Some(url) => format!("{}[{}]({}{})", &caps[1], &caps[2], url, &caps[4]),
+            None => caps[0].to_string(),
+        }
+    });
+
+    html_re.replace_all(&rewritten, |caps: &regex::Captures| {
+        let image = !caps[1].eq_ignore_ascii_case("a");
+        match base.resolve(&caps[3], image) {
+            Some(url) => format!("<{}{}{}", &caps[1], &caps[2], url),
+            None => caps[0].to_string(),
+        }
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn github_base() -> ReadmeBase {
+        ReadmeBase::from_url("https://raw.githubusercontent.com/WERDXZ/crate/main/docs/README.md").unwrap()
+    }
+
+    #[test]
+    fn test_base_from_raw_github_url() {
+        assert_eq!(
+            github_base(),
+            ReadmeBase {
+                fetch_url: "https://raw.githubusercontent.com/WERDXZ/crate/main/docs/README.md".to_string(),
+                link_root: "https://github.com/WERDXZ/crate/blob/main/".to_string(),
+                image_root: "https://raw.githubusercontent.com/WERDXZ/crate/main/".to_string(),
+                dir: "docs/".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_base_from_github_blob_and_repo_urls() {
+        let base = ReadmeBase::from_url("https://github.com/WERDXZ/crate/blob/v1/README.md").unwrap();
+        assert_eq!(base.fetch_url, "https://raw.githubusercontent.com/WERDXZ/crate/v1/README.md");
+        assert_eq!(base.dir, "");
+
+        let base = ReadmeBase::from_url("https://github.com/WERDXZ/crate").unwrap();
+        assert_eq!(base.fetch_url, "https://raw.githubusercontent.com/WERDXZ/crate/HEAD/README.md");
+        assert_eq!(base.link_root, "https://github.com/WERDXZ/crate/blob/HEAD/");
+    }
+
+    #[test]
+    fn test_base_from_other_url() {
+        let base = ReadmeBase::from_url("https://example.com/project/README.md").unwrap();
+        assert_eq!(base.fetch_url, "https://example.com/project/README.md");
+        assert_eq!(base.image_root, "https://example.com/");
+        assert_eq!(base.dir, "project/");
+
+        assert!(ReadmeBase::from_url("").is_none());
+        assert!(ReadmeBase::from_url("ftp://example.com/README.md").is_none());
+    }
+
+    #[test]
+    fn test_rewrite_markdown_links_and_images() {
+        let markdown = "![logo](./logo.png \"Logo\") see [guide](../GUIDE.md#setup) and [home](/README.md)";
+        assert_eq!(
+            rewrite_relative_urls(markdown, &github_base()),
+            "![logo](https://raw.githubusercontent.com/WERDXZ/crate/main/docs/logo.png \"Logo\") \
+             see [guide](https://github.com/WERDXZ/crate/blob/main/GUIDE.md#setup) \
+             and [home](https://github.com/WERDXZ/crate/blob/main/README.md)"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_html_attributes() {
+        let markdown = r#"<img src="img/demo.gif" width="400"> <a href='CONTRIBUTING.md'>contribute</a>"#;
+        assert_eq!(
+            rewrite_relative_urls(markdown, &github_base()),
+            r#"<img src="https://raw.githubusercontent.com/WERDXZ/crate/main/docs/img/demo.gif" width="400"> <a href='https://github.com/WERDXZ/crate/blob/main/docs/CONTRIBUTING.md'>contribute</a>"#
+        );
+    }
+
+    #[test]
+    fn test_absolute_urls_unchanged() {
+        let markdown = "[a](https://example.com) [b](#usage) [c](mailto:me@example.com) ![d](//cdn.example.com/x.png)";
+        assert_eq!(rewrite_relative_urls(markdown, &github_base()), markdown);
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("docs/./a/../b.md"), "docs/b.md");
+        assert_eq!(normalize_path("../../x.md?raw=1"), "x.md?raw=1");
+        assert_eq!(normalize_path("a/b.md#x/../y"), "a/b.md#x/../y");
+    }
+}
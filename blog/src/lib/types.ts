@@ -20,5 +20,9 @@ export interface PostsResponse {
 
 export interface TagWithCount {
   tag: string;
-  count: number;
+  display_name?: string;
+  description?: string;
+  color?: string;
+  post_count: number;
+  project_count: number;
 }
@@ -201,9 +201,9 @@ export default component$(() => {
             }}
           >
             <option value="">All tags</option>
-            {availableTags.value.map((tagData) => (
+            {availableTags.value.filter((tagData) => tagData.post_count > 0).map((tagData) => (
               <option key={tagData.tag} value={tagData.tag}>
-                {`${tagData.tag} (${tagData.count})`}
+                {`${tagData.display_name ?? tagData.tag} (${tagData.post_count})`}
               </option>
             ))}
           </select>
@@ -0,0 +1,70 @@
+// Deserializers for values D1 returns in a different shape than the JSON API
+//
+// Both accept the API shape too, so the same structs read D1 rows and API responses.
+
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// List stored as a JSON string (e.g. from `json_group_array`), a JSON array or null
+pub fn json_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(Vec::new()),
+        Value::String(s) if s.is_empty() || s == "null" => Ok(Vec::new()),
+        Value::String(s) => serde_json::from_str(&s).map_err(D::Error::custom),
+        value => serde_json::from_value(value).map_err(D::Error::custom),
+    }
+}
+
+/// SQLite INTEGER (0 or 1) or a JSON boolean
+pub fn bool_from_int<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Bool(b) => Ok(b),
+        Value::Number(n) => Ok(n.as_i64() != Some(0)),
+        other => Err(D::Error::custom(format!("expected 0, 1 or a boolean, got {}", other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Row {
+        #[serde(default, deserialize_with = "json_list")]
+        tags: Vec<String>,
+        #[serde(deserialize_with = "bool_from_int")]
+        flag: bool,
+    }
+
+    #[test]
+    fn test_d1_shape() {
+        let row: Row = serde_json::from_str(r#"{"tags": "[\"rust\",\"wasm\"]", "flag": 1}"#).unwrap();
+        assert_eq!(row.tags, vec!["rust", "wasm"]);
+        assert!(row.flag);
+    }
+
+    #[test]
+    fn test_api_shape() {
+        let row: Row = serde_json::from_str(r#"{"tags": ["rust"], "flag": false}"#).unwrap();
+        assert_eq!(row.tags, vec!["rust"]);
+        assert!(!row.flag);
+    }
+
+    #[test]
+    fn test_empty_lists() {
+        for tags in [r#""""#, r#""null""#, "null"] {
+            let row: Row = serde_json::from_str(&format!(r#"{{"tags": {}, "flag": 0}}"#, tags)).unwrap();
+            assert!(row.tags.is_empty());
+        }
+        let row: Row = serde_json::from_str(r#"{"flag": 0}"#).unwrap();
+        assert!(row.tags.is_empty());
+    }
+}
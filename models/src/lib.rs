@@ -0,0 +1,51 @@
+//! Types shared by the api, the portfolio and xtask
+//!
+//! Validation lives with the types, so every crate accepts the same slugs,
+//! tags, stages and statuses. Serde and utoipa derives are behind the `serde`
+//! and `utoipa` features.
+
+use std::fmt;
+
+#[cfg(feature = "serde")]
+mod de;
+pub mod portfolio;
+pub mod post;
+pub mod project;
+pub mod resume;
+pub mod slug;
+pub mod tag;
+
+pub use slug::Slug;
+pub use tag::Tag;
+
+/// Why a value was rejected by one of the validated types
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    Empty { what: &'static str },
+    TooLong { what: &'static str, max: usize, len: usize },
+    InvalidCharacters { what: &'static str },
+    Unknown { what: &'static str, expected: String },
+    Transition { what: &'static str, from: String, to: String, allowed: String },
+    NotHttpUrl { what: &'static str, url: String },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty { what } => write!(f, "{} cannot be empty", what),
+            Self::TooLong { what, max, len } => {
+                write!(f, "{} must be {} characters or less (got {})", what, max, len)
+            }
+            Self::InvalidCharacters { what } => {
+                write!(f, "{} can only contain alphanumeric characters, hyphens, and underscores", what)
+            }
+            Self::Unknown { what, expected } => write!(f, "Invalid {}. Must be one of: {}", what, expected),
+            Self::Transition { what, from, to, allowed } => {
+                write!(f, "Cannot change {} from {} to {} (allowed: {})", what, from, to, allowed)
+            }
+            Self::NotHttpUrl { what, url } => write!(f, "{} '{}' must use http or https", what, url),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
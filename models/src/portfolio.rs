@@ -0,0 +1,33 @@
+// Featured content shown on the portfolio, stored as JSON in KV
+// (see scripts/portfolio/README.md)
+
+use crate::project::ProjectUrl;
+
+/// Featured project card
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Project {
+    pub title: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub image_url: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub redirect_url: Option<String>,
+    pub links: Vec<ProjectUrl>,
+}
+
+/// Featured work experience entry
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Experience {
+    pub company: String,
+    pub role: String,
+    pub period: String,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub location: Option<String>,
+    pub description: String,
+    pub tags: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub redirect_url: Option<String>,
+}
@@ -0,0 +1,253 @@
+use crate::ValidationError;
+
+/// Publication state of a post
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum PostStatus {
+    /// Never visible through the public API
+    Draft,
+    /// Listed once `published_at` has passed
+    Scheduled,
+    /// Listed once `published_at` has passed
+    #[default]
+    Published,
+    /// Reachable by slug but never listed
+    Unlisted,
+}
+
+impl PostStatus {
+    pub const ALL: [Self; 4] = [Self::Draft, Self::Scheduled, Self::Published, Self::Unlisted];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Scheduled => "scheduled",
+            Self::Published => "published",
+            Self::Unlisted => "unlisted",
+        }
+    }
+
+    /// Parse a status name as stored in D1
+    pub fn parse(s: &str) -> Result<Self, ValidationError> {
+        Self::ALL.into_iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| ValidationError::Unknown {
+                what: "status",
+                expected: Self::ALL.map(Self::as_str).join(", "),
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Post {
+    pub content_id: String,
+    pub slug: String,
+    pub title: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub summary: String,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub content: Option<String>,  // Fetched from R2, not in DB
+    /// Sanitized HTML rendering of the content (`?format=html` only)
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub content_html: Option<String>,
+    /// Table of contents built from the rendered headings (`?format=html` only)
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub toc: Option<Vec<TocEntry>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub status: PostStatus,
+    pub published_at: String,
+    pub updated_at: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "crate::de::json_list"))]
+    pub tags: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub external_url: Option<String>,
+    pub created_at: String,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub word_count: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub read_time_minutes: Option<u32>,
+}
+
+/// Heading in a rendered post, linkable via `#id`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct TocEntry {
+    /// Heading level (1-6)
+    pub level: u8,
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct PostListItem {
+    /// R2 content key, used internally (e.g. for full-content feeds)
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing))]
+    pub content_id: String,
+    pub slug: String,
+    pub title: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub summary: String,
+    pub published_at: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "crate::de::json_list"))]
+    pub tags: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub external_url: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub word_count: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub read_time_minutes: Option<u32>,
+    /// Excerpt around the matched terms (search results only), HTML-escaped with `<mark>` highlights
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub snippet: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct PostsResponse {
+    pub posts: Vec<PostListItem>,
+    pub pagination: Pagination,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Pagination {
+    pub page: u32,
+    pub limit: u32,
+    pub total: u32,
+    pub has_next: bool,
+}
+
+/// Writable post fields shared by the create and update endpoints
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct PostInput {
+    pub title: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub summary: String,
+    /// Markdown body, stored in R2
+    pub content: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tags: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub external_url: Option<String>,
+    /// RFC 3339 timestamp; defaults to the time of the request
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub published_at: Option<String>,
+    /// Defaults to `scheduled` for future `published_at`, otherwise `published`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub status: Option<PostStatus>,
+}
+
+/// Request body for `POST /v1/posts`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct CreatePostRequest {
+    pub slug: String,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub post: PostInput,
+}
+
+/// Average adult reading speed used for `read_time_minutes`
+const WORDS_PER_MINUTE: u32 = 200;
+
+/// Word count and estimated reading time of a markdown body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadingStats {
+    pub word_count: u32,
+    pub read_time_minutes: u32,
+}
+
+/// Count the prose words in a markdown body
+///
+/// Front matter and fenced code blocks are skipped; a word is any
+/// whitespace-separated token containing a letter or digit, so list markers,
+/// heading hashes and horizontal rules don't count. Read time is rounded up
+/// and is at least one minute.
+pub fn reading_stats(markdown: &str) -> ReadingStats {
+    let mut word_count = 0u32;
+    let mut fence: Option<&str> = None;
+
+    for line in strip_front_matter(markdown).lines() {
+        let trimmed = line.trim_start();
+
+        match fence {
+            Some(marker) => {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+            }
+            None if trimmed.starts_with("```") => fence = Some("```"),
+            None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+            None => {
+                word_count += trimmed
+                    .split_whitespace()
+                    .filter(|word| word.chars().any(char::is_alphanumeric))
+                    .count() as u32;
+            }
+        }
+    }
+
+    ReadingStats {
+        word_count,
+        read_time_minutes: word_count.div_ceil(WORDS_PER_MINUTE).max(1),
+    }
+}
+
+/// Drop a leading YAML (`---`) or TOML (`+++`) front matter block
+fn strip_front_matter(markdown: &str) -> &str {
+    for delimiter in ["---", "+++"] {
+        let Some(rest) = markdown.strip_prefix(delimiter) else { continue };
+        let Some(rest) = rest.strip_prefix('\n').or_else(|| rest.strip_prefix("\r\n")) else { continue };
+
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            offset += line.len();
+            if line.trim_end() == delimiter {
+                return &rest[offset..];
+            }
+        }
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reading_stats_counts_prose_only() {
+        let markdown = "---\ntitle: Ignored front matter\n---\n# Hello world\n\n- one two\n\n```rust\nlet skipped = 1;\n```\n\n---\n\nthree `four` five.\n";
+        let stats = reading_stats(markdown);
+        assert_eq!(stats.word_count, 7);
+        assert_eq!(stats.read_time_minutes, 1);
+    }
+
+    #[test]
+    fn test_reading_stats_read_time() {
+        assert_eq!(reading_stats("").read_time_minutes, 1);
+        assert_eq!(reading_stats(&"word ".repeat(200)).read_time_minutes, 1);
+        assert_eq!(reading_stats(&"word ".repeat(201)).read_time_minutes, 2);
+        assert_eq!(reading_stats(&"word ".repeat(1000)).word_count, 1000);
+    }
+
+    #[test]
+    fn test_strip_front_matter() {
+        assert_eq!(strip_front_matter("+++\ntitle = \"x\"\n+++\nbody"), "body");
+        assert_eq!(strip_front_matter("---\r\ntitle: x\r\n---\r\nbody"), "body");
+        // A horizontal rule later in the document is not front matter
+        assert_eq!(strip_front_matter("intro\n---\nbody"), "intro\n---\nbody");
+        // Unterminated blocks are left alone
+        assert_eq!(strip_front_matter("---\ntitle: x\nbody"), "---\ntitle: x\nbody");
+    }
+}
@@ -0,0 +1,333 @@
+use std::fmt;
+
+use crate::post::Pagination;
+use crate::{Tag, ValidationError};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ProjectUrl {
+    pub label: String,
+    pub url: String,
+}
+
+/// Lifecycle stage of a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ProjectStage {
+    Planned,
+    Wip,
+    Active,
+    Maintained,
+    Archived,
+    Shelved,
+}
+
+impl ProjectStage {
+    pub const ALL: [Self; 6] = [
+        Self::Planned,
+        Self::Wip,
+        Self::Active,
+        Self::Maintained,
+        Self::Archived,
+        Self::Shelved,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Planned => "planned",
+            Self::Wip => "wip",
+            Self::Active => "active",
+            Self::Maintained => "maintained",
+            Self::Archived => "archived",
+            Self::Shelved => "shelved",
+        }
+    }
+
+    /// Parse a stage name as stored in D1
+    pub fn parse(s: &str) -> Result<Self, ValidationError> {
+        Self::ALL.into_iter()
+            .find(|stage| stage.as_str() == s)
+            .ok_or_else(|| ValidationError::Unknown {
+                what: "stage",
+                expected: Self::ALL.map(Self::as_str).join(", "),
+            })
+    }
+
+    /// Stages a project in this stage may move to
+    pub fn next_stages(self) -> &'static [Self] {
+        match self {
+            Self::Planned => &[Self::Wip, Self::Active, Self::Shelved],
+            Self::Wip => &[Self::Planned, Self::Active, Self::Shelved],
+            Self::Active => &[Self::Wip, Self::Maintained, Self::Archived, Self::Shelved],
+            Self::Maintained => &[Self::Active, Self::Archived],
+            Self::Archived => &[Self::Active, Self::Maintained],
+            Self::Shelved => &[Self::Planned, Self::Wip],
+        }
+    }
+
+    /// Check that a project may move from this stage to `next`
+    ///
+    /// Staying in the same stage is always allowed.
+    pub fn transition_to(self, next: Self) -> Result<Self, ValidationError> {
+        if self == next || self.next_stages().contains(&next) {
+            return Ok(next);
+        }
+        Err(ValidationError::Transition {
+            what: "stage",
+            from: self.to_string(),
+            to: next.to_string(),
+            allowed: self.next_stages().iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", "),
+        })
+    }
+}
+
+impl fmt::Display for ProjectStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Project {
+    pub id: String,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub stage: ProjectStage,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::bool_from_int"))]
+    pub open_to_contributors: bool,
+    pub readme_url: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "crate::de::json_list"))]
+    pub tags: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "crate::de::json_list"))]
+    pub urls: Vec<ProjectUrl>,
+    pub created_at: String,
+    pub updated_at: String,
+    /// README markdown with absolute links, only present with `?include=readme`
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub readme: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ProjectsResponse {
+    pub projects: Vec<Project>,
+    pub pagination: Pagination,
+}
+
+/// Request body for `PATCH /v1/projects/{slug}`
+///
+/// Unset fields are left unchanged. URLs are matched by label: adding a URL
+/// replaces any existing URL with the same label.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ProjectPatch {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub description: Option<String>,
+    /// Must be reachable from the current stage (see `ProjectStage::next_stages`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stage: Option<ProjectStage>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub readme_url: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub open_to_contributors: Option<bool>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub add_urls: Vec<ProjectUrl>,
+    /// Labels of the URLs to remove
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub remove_urls: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub add_tags: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub remove_tags: Vec<String>,
+}
+
+impl ProjectPatch {
+    /// Longest project name accepted
+    pub const MAX_NAME_LENGTH: usize = 100;
+
+    /// Validate and normalize the patch in place
+    ///
+    /// Trims text fields, rejects empty names and descriptions, non-HTTP URLs and
+    /// invalid tags, and normalizes and deduplicates tags. Shared by the PATCH
+    /// endpoint and `xtask projects update`, so both accept the same values.
+    pub fn validate(&mut self) -> Result<(), ValidationError> {
+        if let Some(name) = self.name.as_mut() {
+            *name = name.trim().to_string();
+            if name.is_empty() {
+                return Err(ValidationError::Empty { what: "Name" });
+            }
+            if name.len() > Self::MAX_NAME_LENGTH {
+                return Err(ValidationError::TooLong { what: "Name", max: Self::MAX_NAME_LENGTH, len: name.len() });
+            }
+        }
+
+        if let Some(description) = self.description.as_mut() {
+            *description = description.trim().to_string();
+            if description.is_empty() {
+                return Err(ValidationError::Empty { what: "Description" });
+            }
+        }
+
+        if let Some(readme_url) = self.readme_url.as_mut() {
+            *readme_url = readme_url.trim().to_string();
+            if !is_http_url(readme_url) {
+                return Err(ValidationError::NotHttpUrl { what: "README URL", url: readme_url.clone() });
+            }
+        }
+
+        for url in &mut self.add_urls {
+            url.label = url.label.trim().to_string();
+            url.url = url.url.trim().to_string();
+            if url.label.is_empty() {
+                return Err(ValidationError::Empty { what: "URL label" });
+            }
+            if !is_http_url(&url.url) {
+                return Err(ValidationError::NotHttpUrl { what: "URL", url: url.url.clone() });
+            }
+        }
+        for label in &mut self.remove_urls {
+            *label = label.trim().to_string();
+        }
+
+        self.add_tags = normalize_tags(&self.add_tags)?;
+        self.remove_tags = normalize_tags(&self.remove_tags)?;
+
+        Ok(())
+    }
+
+    /// Whether the patch changes nothing
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.description.is_none()
+            && self.stage.is_none()
+            && self.readme_url.is_none()
+            && self.open_to_contributors.is_none()
+            && self.add_urls.is_empty()
+            && self.remove_urls.is_empty()
+            && self.add_tags.is_empty()
+            && self.remove_tags.is_empty()
+    }
+
+    /// Labels whose existing URLs are deleted: removed ones and replaced ones
+    pub fn replaced_url_labels(&self) -> impl Iterator<Item = &str> {
+        self.remove_urls.iter()
+            .map(String::as_str)
+            .chain(self.add_urls.iter().map(|url| url.label.as_str()))
+    }
+}
+
+/// Normalize and deduplicate tag names
+fn normalize_tags(raw_tags: &[String]) -> Result<Vec<String>, ValidationError> {
+    let mut tags: Vec<String> = Vec::with_capacity(raw_tags.len());
+    for raw in raw_tags {
+        let tag = Tag::normalize(raw)?;
+        if !tags.iter().any(|t| t == tag.as_str()) {
+            tags.push(tag.as_str().to_string());
+        }
+    }
+    Ok(tags)
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_parse() {
+        assert_eq!(ProjectStage::parse("wip"), Ok(ProjectStage::Wip));
+        assert!(ProjectStage::parse("WIP").is_err());
+        assert!(ProjectStage::parse("done").is_err());
+    }
+
+    #[test]
+    fn test_stage_transitions() {
+        assert_eq!(ProjectStage::Wip.transition_to(ProjectStage::Active), Ok(ProjectStage::Active));
+        assert_eq!(ProjectStage::Active.transition_to(ProjectStage::Active), Ok(ProjectStage::Active));
+        assert_eq!(ProjectStage::Archived.transition_to(ProjectStage::Maintained), Ok(ProjectStage::Maintained));
+
+        let error = ProjectStage::Archived.transition_to(ProjectStage::Planned).unwrap_err();
+        assert_eq!(error.to_string(), "Cannot change stage from archived to planned (allowed: active, maintained)");
+        assert!(ProjectStage::Planned.transition_to(ProjectStage::Maintained).is_err());
+    }
+
+    #[test]
+    fn test_every_stage_can_be_left() {
+        for stage in ProjectStage::ALL {
+            assert!(!stage.next_stages().is_empty());
+            assert!(!stage.next_stages().contains(&stage));
+        }
+    }
+
+    #[test]
+    fn test_patch_is_empty() {
+        assert!(ProjectPatch::default().is_empty());
+        let patch = ProjectPatch { remove_tags: vec!["rust".to_string()], ..Default::default() };
+        assert!(!patch.is_empty());
+    }
+
+    #[test]
+    fn test_patch_validate_normalizes() {
+        let mut patch = ProjectPatch {
+            name: Some("  Crate ".to_string()),
+            description: Some(" A crate\n".to_string()),
+            readme_url: Some(" https://example.com/README.md".to_string()),
+            add_tags: vec!["Rust".to_string(), " rust".to_string(), "web dev".to_string()],
+            remove_urls: vec![" Docs ".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(patch.validate(), Ok(()));
+        assert_eq!(patch.name.as_deref(), Some("Crate"));
+        assert_eq!(patch.description.as_deref(), Some("A crate"));
+        assert_eq!(patch.readme_url.as_deref(), Some("https://example.com/README.md"));
+        assert_eq!(patch.add_tags, vec!["rust", "web-dev"]);
+        assert_eq!(patch.remove_urls, vec!["Docs"]);
+    }
+
+    #[test]
+    fn test_patch_validate_rejects_invalid_fields() {
+        let invalid = |patch: ProjectPatch| {
+            let mut patch = patch;
+            patch.validate().unwrap_err()
+        };
+
+        assert_eq!(
+            invalid(ProjectPatch { name: Some("  ".to_string()), ..Default::default() }),
+            ValidationError::Empty { what: "Name" }
+        );
+        assert_eq!(
+            invalid(ProjectPatch { name: Some("x".repeat(101)), ..Default::default() }),
+            ValidationError::TooLong { what: "Name", max: 100, len: 101 }
+        );
+        assert_eq!(
+            invalid(ProjectPatch { description: Some("".to_string()), ..Default::default() }),
+            ValidationError::Empty { what: "Description" }
+        );
+        assert_eq!(
+            invalid(ProjectPatch { readme_url: Some("ftp://example.com".to_string()), ..Default::default() }).to_string(),
+            "README URL 'ftp://example.com' must use http or https"
+        );
+        let url = ProjectUrl { label: " ".to_string(), url: "https://example.com".to_string() };
+        assert_eq!(
+            invalid(ProjectPatch { add_urls: vec![url], ..Default::default() }),
+            ValidationError::Empty { what: "URL label" }
+        );
+        assert!(matches!(
+            invalid(ProjectPatch { remove_tags: vec!["bad/tag".to_string()], ..Default::default() }),
+            ValidationError::InvalidCharacters { what: "Tag" }
+        ));
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+use std::ops::Deref;
+
+use crate::ValidationError;
+
+/// Validated slug of a post or project
+///
+/// Slugs must be:
+/// - Non-empty
+/// - Max 100 characters
+/// - Alphanumeric, hyphens, and underscores only
+///
+/// This keeps them safe in URLs, R2 keys and SQL.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Slug(String);
+
+impl Slug {
+    /// Maximum length for a slug
+    pub const MAX_LENGTH: usize = 100;
+
+    /// Create a new validated slug
+    pub fn parse(s: impl Into<String>) -> Result<Self, ValidationError> {
+        let s = s.into();
+
+        if s.is_empty() {
+            return Err(ValidationError::Empty { what: "Slug" });
+        }
+        if s.len() > Self::MAX_LENGTH {
+            return Err(ValidationError::TooLong { what: "Slug", max: Self::MAX_LENGTH, len: s.len() });
+        }
+        if !s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(ValidationError::InvalidCharacters { what: "Slug" });
+        }
+
+        Ok(Self(s))
+    }
+
+    /// Check a slug without allocating
+    pub fn is_valid(s: &str) -> bool {
+        !s.is_empty()
+            && s.len() <= Self::MAX_LENGTH
+            && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Slug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Deref for Slug {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_slugs() {
+        assert!(Slug::parse("hello-world").is_ok());
+        assert!(Slug::parse("post_2024").is_ok());
+        assert!(Slug::parse("a".repeat(100)).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_slugs() {
+        assert_eq!(Slug::parse("").unwrap_err(), ValidationError::Empty { what: "Slug" });
+        assert_eq!(
+            Slug::parse("a".repeat(101)).unwrap_err(),
+            ValidationError::TooLong { what: "Slug", max: 100, len: 101 }
+        );
+        assert!(Slug::parse("../etc/passwd").is_err());
+        assert!(Slug::parse("it's").is_err());
+        assert!(Slug::parse("hello world").is_err());
+        assert!(!Slug::is_valid("a/b"));
+    }
+}
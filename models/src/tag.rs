@@ -0,0 +1,239 @@
+use std::fmt;
+use std::ops::Deref;
+
+use crate::post::PostListItem;
+use crate::project::Project;
+use crate::ValidationError;
+
+/// Validated tag wrapper
+///
+/// Tags must be:
+/// - Non-empty
+/// - Max 50 characters
+/// - Alphanumeric, hyphens, and underscores only
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag(String);
+
+/// Tag with its metadata and usage counts for API responses
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct TagWithCount {
+    pub tag: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub display_name: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub description: Option<String>,
+    /// Hex color such as `#dea584`
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub color: Option<String>,
+    /// Number of listed posts carrying the tag
+    pub post_count: u32,
+    /// Number of projects carrying the tag
+    pub project_count: u32,
+}
+
+/// A tag with the posts and projects carrying it
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct TagDetail {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub tag: TagWithCount,
+    pub posts: Vec<PostListItem>,
+    pub projects: Vec<Project>,
+}
+
+impl Tag {
+    /// Maximum length for a tag
+    pub const MAX_LENGTH: usize = 50;
+
+    /// Maximum number of tags allowed in a query
+    pub const MAX_COUNT: usize = 10;
+
+    /// Create a new validated tag
+    pub fn new(s: impl Into<String>) -> Option<Self> {
+        Self::parse(s).ok()
+    }
+
+    /// Create a new validated tag, explaining why an invalid one was rejected
+    pub fn parse(s: impl Into<String>) -> Result<Self, ValidationError> {
+        let s = s.into();
+
+        if s.is_empty() {
+            return Err(ValidationError::Empty { what: "Tag" });
+        }
+        if s.len() > Self::MAX_LENGTH {
+            return Err(ValidationError::TooLong { what: "Tag", max: Self::MAX_LENGTH, len: s.len() });
+        }
+        if !s.chars().all(Self::is_valid_char) {
+            return Err(ValidationError::InvalidCharacters { what: "Tag" });
+        }
+
+        Ok(Tag(s))
+    }
+
+    /// Create a tag from free-form input, in its canonical form
+    ///
+    /// Trims and lowercases the input and turns runs of spaces, underscores
+    /// and hyphens into a single hyphen, so "Web Dev", "web_dev" and
+    /// "web-dev" are the same tag. The result is validated like `parse`.
+    pub fn normalize(s: &str) -> Result<Self, ValidationError> {
+        let mut normalized = String::with_capacity(s.len());
+        let mut pending_hyphen = false;
+        for c in s.trim().chars() {
+            if c.is_whitespace() || c == '_' || c == '-' {
+                pending_hyphen = true;
+                continue;
+            }
+            if pending_hyphen && !normalized.is_empty() {
+                normalized.push('-');
+            }
+            pending_hyphen = false;
+            normalized.extend(c.to_lowercase());
+        }
+
+        Self::parse(normalized)
+    }
+
+    /// Whether a tag is already in the form `normalize` produces
+    pub fn is_canonical(s: &str) -> bool {
+        Self::normalize(s).is_ok_and(|tag| tag.as_str() == s)
+    }
+
+    fn is_valid_char(c: char) -> bool {
+        c.is_ascii_alphanumeric() || c == '-' || c == '_'
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Parse and normalize a comma-separated list of tags from query parameter
+    /// Returns only valid tags, up to MAX_COUNT
+    pub fn parse_many(value: &str) -> Vec<Tag> {
+        value
+            .split(',')
+            .take(Self::MAX_COUNT)
+            .filter_map(|s| Tag::normalize(s).ok())
+            .collect()
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Deref for Tag {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_tags() {
+        assert!(Tag::new("rust").is_some());
+        assert!(Tag::new("web-dev").is_some());
+        assert!(Tag::new("rust_lang").is_some());
+        assert!(Tag::new("rust-2024").is_some());
+    }
+
+    #[test]
+    fn test_invalid_tags() {
+        assert!(Tag::new("").is_none()); // empty
+        assert!(Tag::new("a".repeat(51)).is_none()); // too long
+        assert!(Tag::new("rust/lang").is_none()); // invalid char
+        assert!(Tag::new("rust lang").is_none()); // space
+        assert!(Tag::new("../etc/passwd").is_none()); // path traversal attempt
+        assert!(Tag::new("'; DROP TABLE").is_none()); // SQL injection attempt
+    }
+
+    #[test]
+    fn test_parse_many() {
+        let tags = Tag::parse_many("rust,python,javascript");
+        assert_eq!(tags.len(), 3);
+        assert_eq!(&*tags[0], "rust");
+        assert_eq!(&*tags[1], "python");
+        assert_eq!(&*tags[2], "javascript");
+
+        // Test trimming
+        let tags = Tag::parse_many(" rust , python , javascript ");
+        assert_eq!(tags.len(), 3);
+
+        // Test filtering invalid
+        let tags = Tag::parse_many("rust,invalid/tag,python,../etc/passwd");
+        assert_eq!(tags.len(), 2);
+        assert_eq!(&*tags[0], "rust");
+        assert_eq!(&*tags[1], "python");
+
+        // Test normalization
+        let tags = Tag::parse_many("Rust,web dev");
+        assert_eq!(&*tags[0], "rust");
+        assert_eq!(&*tags[1], "web-dev");
+
+        // Test max count limit
+        let tags = Tag::parse_many("t1,t2,t3,t4,t5,t6,t7,t8,t9,t10,t11,t12");
+        assert_eq!(tags.len(), Tag::MAX_COUNT);
+
+        // Test empty result
+        let tags = Tag::parse_many("invalid/tag,another.bad");
+        assert!(tags.is_empty());
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(Tag::normalize("Web Dev").unwrap().as_str(), "web-dev");
+        assert_eq!(Tag::normalize("  web_dev ").unwrap().as_str(), "web-dev");
+        assert_eq!(Tag::normalize("web -- dev").unwrap().as_str(), "web-dev");
+        assert_eq!(Tag::normalize("-rust-").unwrap().as_str(), "rust");
+        assert_eq!(Tag::normalize("WebDev").unwrap().as_str(), "webdev");
+
+        assert!(Tag::normalize("   ").is_err());
+        assert!(Tag::normalize("c++").is_err());
+    }
+
+    #[test]
+    fn test_is_canonical() {
+        assert!(Tag::is_canonical("web-dev"));
+        assert!(!Tag::is_canonical("Web-Dev"));
+        assert!(!Tag::is_canonical("web_dev"));
+        assert!(!Tag::is_canonical("web dev"));
+    }
+
+    #[test]
+    fn test_deref() {
+        let tag = Tag::new("rust").unwrap();
+        // Can use as &str
+        assert_eq!(&*tag, "rust");
+        // Works with string methods
+        assert!(tag.starts_with("ru"));
+        assert_eq!(tag.len(), 4);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_tag_detail_flattens_tag() {
+        let tag: TagWithCount = serde_json::from_value(serde_json::json!({
+            "tag": "rust", "display_name": "Rust", "description": null, "color": null,
+            "post_count": 3, "project_count": 1
+        }))
+        .unwrap();
+        let detail = TagDetail { tag, posts: Vec::new(), projects: Vec::new() };
+
+        assert_eq!(
+            serde_json::to_value(&detail).unwrap(),
+            serde_json::json!({
+                "tag": "rust", "display_name": "Rust", "post_count": 3, "project_count": 1,
+                "posts": [], "projects": []
+            })
+        );
+    }
+}
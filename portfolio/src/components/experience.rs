@@ -1,9 +1,10 @@
 use leptos::prelude::*;
-use crate::{get_featured_experience, types::{Experience, Mode}};
+use crate::{get_featured_experience, types::Mode};
+use models::portfolio::Experience;
 
 #[component]
 pub fn ExperienceSection(mode: Signal<Mode>) -> impl IntoView {
-    let experiences = Resource::new(move || mode.get(), |mode| get_featured_experience(mode));
+    let experiences = Resource::new(move || mode.get(), get_featured_experience);
 
     view! {
         <section id="experience" class="experience-section">
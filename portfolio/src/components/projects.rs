@@ -1,9 +1,11 @@
 use leptos::prelude::*;
-use crate::{get_featured_projects, types::{Mode, Project, ProjectLink}};
+use crate::{get_featured_projects, types::Mode};
+use models::portfolio::Project;
+use models::project::ProjectUrl;
 
 #[component]
 pub fn ProjectsSection(mode: Signal<Mode>) -> impl IntoView {
-    let projects = Resource::new(move || mode.get(), |mode| get_featured_projects(mode));
+    let projects = Resource::new(move || mode.get(), get_featured_projects);
 
     view! {
         <section id="projects" class="projects-section">
@@ -102,8 +104,8 @@ fn ProjectCard(project: Project) -> impl IntoView {
 }
 
 #[component]
-fn ProjectLinkButton(link: ProjectLink) -> impl IntoView {
-    let ProjectLink { label, url } = link;
+fn ProjectLinkButton(link: ProjectUrl) -> impl IntoView {
+    let ProjectUrl { label, url } = link;
 
     view! {
         <a
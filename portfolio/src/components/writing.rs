@@ -1,9 +1,10 @@
-use crate::{get_featured_posts, types::{BlogPost, Mode}};
+use crate::{get_featured_posts, types::Mode};
 use leptos::prelude::*;
+use models::post::PostListItem;
 
 #[component]
 pub fn WritingSection(mode: Signal<Mode>) -> impl IntoView {
-    let posts = Resource::new(move || mode.get(), |mode| get_featured_posts(mode));
+    let posts = Resource::new(move || mode.get(), get_featured_posts);
 
     view! {
         <Suspense fallback=|| view! { <></> }>
@@ -22,7 +23,7 @@ pub fn WritingSection(mode: Signal<Mode>) -> impl IntoView {
                                 </section>
                             }.into_any()
                         }
-                        _ => view! { <></> }.into_any()
+                        _ => ().into_any()
                     }
                 })
             }}
@@ -31,13 +32,14 @@ pub fn WritingSection(mode: Signal<Mode>) -> impl IntoView {
 }
 
 #[component]
-fn BlogPostCard(post: BlogPost) -> impl IntoView {
-    let BlogPost {
+fn BlogPostCard(post: PostListItem) -> impl IntoView {
+    let PostListItem {
         slug,
         title,
         summary,
         published_at,
         tags,
+        ..
     } = post;
 
     let post_url = format!("{}/posts/{}", crate::constants::BLOG_BASE_URL, slug);
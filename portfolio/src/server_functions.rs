@@ -6,7 +6,9 @@ use std::sync::Arc;
 #[cfg(feature = "ssr")]
 use worker::Env;
 
-use crate::types::{AboutContent, BlogPost, Experience, HeroContent, Mode, Project};
+use crate::types::{AboutContent, HeroContent, Mode};
+use models::portfolio::{Experience, Project};
+use models::post::PostListItem;
 
 /// Get featured projects from KV
 #[server(GetFeaturedProjects)]
@@ -116,7 +118,7 @@ pub async fn get_featured_experience(mode: Mode) -> Result<Vec<Experience>, Serv
 
 /// Get featured blog posts from API
 #[server(GetFeaturedPosts)]
-pub async fn get_featured_posts(mode: Mode) -> Result<Vec<BlogPost>, ServerFnError> {
+pub async fn get_featured_posts(mode: Mode) -> Result<Vec<PostListItem>, ServerFnError> {
     #[cfg(feature = "ssr")]
     use crate::worker_helpers;
     #[cfg(feature = "ssr")]
@@ -159,8 +161,8 @@ pub async fn get_featured_posts(mode: Mode) -> Result<Vec<BlogPost>, ServerFnErr
             let url = format!("{}/v1/posts/{}", api_base_url, slug);
             let result = async {
                 let response = client.get(&url).send().await?;
-                let post = response.json::<BlogPost>().await?;
-                Ok::<BlogPost, reqwest::Error>(post)
+                let post = response.json::<PostListItem>().await?;
+                Ok::<PostListItem, reqwest::Error>(post)
             }.await;
 
             match result {
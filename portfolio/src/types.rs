@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 /// Portfolio mode/persona
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum Mode {
+    #[default]
     Industry,
     Academia,
 }
@@ -43,56 +44,6 @@ impl Mode {
     }
 }
 
-impl Default for Mode {
-    fn default() -> Self {
-        Mode::Industry
-    }
-}
-
-/// Link for projects (e.g., "Website", "GitHub", "API")
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct ProjectLink {
-    pub label: String,
-    pub url: String,
-}
-
-/// Featured project for portfolio display
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct Project {
-    pub title: String,
-    pub description: String,
-    pub tags: Vec<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub image_url: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub redirect_url: Option<String>,
-    pub links: Vec<ProjectLink>,
-}
-
-/// Work experience for portfolio display
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct Experience {
-    pub company: String,
-    pub role: String,
-    pub period: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub location: Option<String>,
-    pub description: String,
-    pub tags: Vec<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub redirect_url: Option<String>,
-}
-
-/// Blog post from API (featured posts)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct BlogPost {
-    pub slug: String,
-    pub title: String,
-    pub summary: String,
-    pub published_at: String,
-    pub tags: Vec<String>,
-}
-
 /// Hero content from KV (mode-specific)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HeroContent {
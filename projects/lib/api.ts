@@ -16,10 +16,20 @@ export interface Project {
   urls: ProjectUrl[];
   created_at: string;
   updated_at: string;
+  /** README markdown, present when requested with `?include=readme` */
+  readme?: string;
+}
+
+export interface Pagination {
+  page: number;
+  limit: number;
+  total: number;
+  has_next: boolean;
 }
 
 export interface ProjectsResponse {
   projects: Project[];
+  pagination: Pagination;
 }
 
 const API_BASE_URL = "https://api.werdxz.info/v1";
@@ -42,20 +52,32 @@ async function fetchWithTimeout(url: string, timeout = FETCH_TIMEOUT): Promise<R
   }
 }
 
+/** Largest page size `/v1/projects` accepts */
+const PROJECTS_PAGE_SIZE = 100;
+
+/** Fetch every project, following `pagination.has_next` across pages */
 export async function getProjects(): Promise<Project[]> {
-  const url = `${API_BASE_URL}/projects`;
-  const response = await fetchWithTimeout(url);
+  const projects: Project[] = [];
 
-  if (!response.ok) {
-    throw new Error(`Failed to fetch projects: ${response.statusText}`);
-  }
+  for (let page = 1; ; page++) {
+    const url = `${API_BASE_URL}/projects?page=${page}&limit=${PROJECTS_PAGE_SIZE}`;
+    const response = await fetchWithTimeout(url);
 
-  const data: ProjectsResponse = await response.json();
-  return data.projects;
+    if (!response.ok) {
+      throw new Error(`Failed to fetch projects: ${response.statusText}`);
+    }
+
+    const data: ProjectsResponse = await response.json();
+    projects.push(...data.projects);
+
+    if (!data.pagination.has_next || data.projects.length === 0) {
+      return projects;
+    }
+  }
 }
 
 export async function getProjectBySlug(slug: string): Promise<Project | undefined> {
-  const url = `${API_BASE_URL}/projects/${slug}`;
+  const url = `${API_BASE_URL}/projects/${slug}?include=readme`;
 
   try {
     const response = await fetchWithTimeout(url);
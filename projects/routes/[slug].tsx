@@ -14,7 +14,7 @@ export default define.page(async function ProjectDetail(ctx) {
       throw new HttpError(404, `Project "${slug}" not found`);
     }
 
-    const readme = await fetchReadme(project.readme_url);
+    const readme = project.readme ?? await fetchReadme(project.readme_url);
     const headings = extractHeadings(readme);
 
     return (
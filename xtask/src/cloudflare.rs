@@ -0,0 +1,574 @@
+// Typed access to D1, R2 and KV
+//
+// Remote resources are reached through the Cloudflare REST API. Local
+// development keeps going through `wrangler --local` (see `wrangler.rs`), which
+// drives the same miniflare storage `wrangler dev` uses.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Read;
+use std::path::Path;
+
+use crate::wrangler::WranglerBackend;
+use crate::{content_bucket_name, WranglerConfig};
+
+/// Default Cloudflare REST API endpoint
+pub const DEFAULT_API_BASE_URL: &str = "https://api.cloudflare.com/client/v4";
+
+/// A SQL statement with positional (`?`) parameters
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Statement {
+    pub sql: String,
+    pub params: Vec<Value>,
+}
+
+impl Statement {
+    pub fn new(sql: impl Into<String>) -> Self {
+        Self { sql: sql.into(), params: Vec::new() }
+    }
+
+    /// Bind the next `?` placeholder
+    pub fn bind(mut self, value: impl Into<Value>) -> Self {
+        self.params.push(value.into());
+        self
+    }
+}
+
+/// Names and ids of the bindings in `api/wrangler.toml`
+#[derive(Debug, Clone)]
+pub struct Resources {
+    pub database_id: String,
+    pub database_name: String,
+    pub bucket_name: String,
+}
+
+impl Resources {
+    pub fn from_config(config: &WranglerConfig) -> Result<Self> {
+        let database = config.d1_databases
+            .as_ref()
+            .and_then(|dbs| dbs.iter().find(|db| db.binding == "DB"))
+            .context("DB database not found in wrangler.toml")?;
+
+        Ok(Self {
+            database_id: database.database_id.clone(),
+            database_name: database.database_name.clone(),
+            bucket_name: content_bucket_name(config)?,
+        })
+    }
+}
+
+/// Storage operations used by xtask commands
+///
+/// Statements run against the `DB` database, objects live in `CONTENT_BUCKET`.
+pub trait Backend {
+    /// Run a read query and return its rows as JSON objects
+    fn query_rows(&self, statement: &Statement) -> Result<Vec<Value>>;
+
+    /// Run statements in order as a single transaction
+    fn batch(&self, statements: &[Statement]) -> Result<()>;
+
+    /// Download an object, or `None` if it does not exist
+    fn r2_get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Upload an object, overwriting it
+    fn r2_put(&self, key: &str, body: &[u8], content_type: &str) -> Result<()>;
+
+    /// Delete an object (deleting a missing object succeeds)
+    fn r2_delete(&self, key: &str) -> Result<()>;
+
+    /// List every key under `prefix`, or `None` if this backend cannot list
+    fn r2_list(&self, prefix: &str) -> Result<Option<Vec<String>>>;
+
+    /// Write a value to a KV namespace
+    fn kv_put(&self, namespace_id: &str, key: &str, value: &[u8]) -> Result<()>;
+
+    /// Purge edge-cached responses by cache tag, or `false` if this backend cannot purge
+    fn purge_cache(&self, tags: &[&str]) -> Result<bool>;
+
+    /// Human-readable target, for status output
+    fn describe(&self) -> String;
+}
+
+impl dyn Backend + '_ {
+    /// Run a read query and deserialize its rows
+    pub fn query<T: DeserializeOwned>(&self, statement: &Statement) -> Result<Vec<T>> {
+        self.query_rows(statement)?
+            .into_iter()
+            .map(|row| serde_json::from_value(row).context("Unexpected row shape in D1 result"))
+            .collect()
+    }
+
+    /// Run a single statement
+    pub fn execute(&self, statement: Statement) -> Result<()> {
+        self.batch(&[statement])
+    }
+
+    /// Download an object as UTF-8 text, failing if it does not exist
+    pub fn r2_get_text(&self, key: &str) -> Result<String> {
+        let bytes = self.r2_get(key)?
+            .with_context(|| format!("R2 object {} not found", key))?;
+        String::from_utf8(bytes).context("R2 object is not valid UTF-8")
+    }
+
+    /// Copy an object to another key, byte for byte
+    pub fn r2_copy(&self, from: &str, to: &str) -> Result<()> {
+        let bytes = self.r2_get(from)?
+            .with_context(|| format!("R2 object {} not found", from))?;
+        self.r2_put(to, &bytes, content_type_for(to))
+    }
+}
+
+/// Pick a backend for local or remote resources
+///
+/// - `--remote` with `CLOUDFLARE_API_TOKEN` and `CLOUDFLARE_ACCOUNT_ID` set uses the REST API
+/// - `CLOUDFLARE_API_BASE_URL` points the REST client at a stand-in server (also locally)
+/// - `CLOUDFLARE_ZONE_ID` lets the REST client purge the API's edge cache
+/// - otherwise commands fall back to wrangler, using its own login for `--remote`
+pub fn connect(workspace_root: &Path, config: &WranglerConfig, remote: bool) -> Result<Box<dyn Backend>> {
+    let resources = Resources::from_config(config)?;
+    let base_url = std::env::var("CLOUDFLARE_API_BASE_URL").ok();
+
+    if remote || base_url.is_some() {
+        match (std::env::var("CLOUDFLARE_ACCOUNT_ID"), std::env::var("CLOUDFLARE_API_TOKEN")) {
+            (Ok(account_id), Ok(token)) => {
+                let base_url = base_url.unwrap_or_else(|| DEFAULT_API_BASE_URL.to_string());
+                let client = CloudflareClient::new(base_url, account_id, token, resources)
+                    .with_zone(std::env::var("CLOUDFLARE_ZONE_ID").ok());
+                return Ok(Box::new(client));
+            }
+            _ if base_url.is_some() => {
+                anyhow::bail!("CLOUDFLARE_API_BASE_URL requires CLOUDFLARE_ACCOUNT_ID and CLOUDFLARE_API_TOKEN");
+            }
+            _ => {}
+        }
+    }
+
+    Ok(Box::new(WranglerBackend::new(workspace_root, resources, remote)))
+}
+
+/// Content type for an object key, by extension
+pub fn content_type_for(key: &str) -> &'static str {
+    let extension = key.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("md") => "text/markdown; charset=utf-8",
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Client for the Cloudflare REST API
+pub struct CloudflareClient {
+    agent: ureq::Agent,
+    base_url: String,
+    account_id: String,
+    token: String,
+    resources: Resources,
+    /// Zone serving the API, for cache purges
+    zone_id: Option<String>,
+}
+
+/// Envelope of every Cloudflare API JSON response
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    success: bool,
+    #[serde(default)]
+    errors: Vec<ApiMessage>,
+    result: Option<T>,
+    #[serde(default)]
+    result_info: Option<ResultInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiMessage {
+    #[serde(default)]
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResultInfo {
+    cursor: Option<String>,
+    #[serde(default)]
+    is_truncated: bool,
+}
+
+/// Result of one statement in a D1 query response
+#[derive(Debug, Deserialize)]
+struct D1StatementResult {
+    #[serde(default)]
+    results: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct R2Object {
+    key: String,
+}
+
+impl CloudflareClient {
+    pub fn new(base_url: impl Into<String>, account_id: impl Into<String>, token: impl Into<String>, resources: Resources) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().build(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            account_id: account_id.into(),
+            token: token.into(),
+            resources,
+            zone_id: None,
+        }
+    }
+
+    pub fn with_zone(mut self, zone_id: Option<String>) -> Self {
+        self.zone_id = zone_id;
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/accounts/{}/{}", self.base_url, self.account_id, path)
+    }
+
+    fn object_url(&self, key: &str) -> String {
+        self.url(&format!("r2/buckets/{}/objects/{}", self.resources.bucket_name, encode_path(key)))
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        self.agent.request(method, url)
+            .set("Authorization", &format!("Bearer {}", self.token))
+    }
+
+    /// Send a request and unwrap the JSON envelope
+    fn call<T: DeserializeOwned>(&self, request: ureq::Request, body: Option<Value>) -> Result<ApiResponse<T>> {
+        let description = format!("{} {}", request.method(), request.url());
+        let response = match body {
+            Some(body) => request.send_json(body),
+            None => request.call(),
+        };
+
+        let response = match response {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, response)) => {
+                let text = response.into_string().unwrap_or_default();
+                let message = serde_json::from_str::<ApiResponse<Value>>(&text)
+                    .map(|envelope| format_errors(&envelope.errors))
+                    .unwrap_or(text);
+                anyhow::bail!("{} failed with {}: {}", description, code, message);
+            }
+            Err(e) => return Err(e).with_context(|| format!("{} failed", description)),
+        };
+
+        let envelope: ApiResponse<T> = response.into_json()
+            .with_context(|| format!("Failed to parse response of {}", description))?;
+        if !envelope.success {
+            anyhow::bail!("{} failed: {}", description, format_errors(&envelope.errors));
+        }
+        Ok(envelope)
+    }
+
+    fn d1_query(&self, body: Value) -> Result<Vec<D1StatementResult>> {
+        let url = self.url(&format!("d1/database/{}/query", self.resources.database_id));
+        let envelope = self.call::<Vec<D1StatementResult>>(self.request("POST", &url), Some(body))?;
+        Ok(envelope.result.unwrap_or_default())
+    }
+}
+
+impl Backend for CloudflareClient {
+    fn query_rows(&self, statement: &Statement) -> Result<Vec<Value>> {
+        let body = serde_json::to_value(statement)?;
+        Ok(self.d1_query(body)?.into_iter().flat_map(|result| result.results).collect())
+    }
+
+    fn batch(&self, statements: &[Statement]) -> Result<()> {
+        if statements.is_empty() {
+            return Ok(());
+        }
+        self.d1_query(serde_json::json!({ "batch": statements }))?;
+        Ok(())
+    }
+
+    fn r2_get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match self.request("GET", &self.object_url(key)).call() {
+            Ok(response) => {
+                let mut bytes = Vec::new();
+                response.into_reader().read_to_end(&mut bytes)
+                    .with_context(|| format!("Failed to download {}", key))?;
+                Ok(Some(bytes))
+            }
+            Err(ureq::Error::Status(404, _)) => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to download {}", key)),
+        }
+    }
+
+    fn r2_put(&self, key: &str, body: &[u8], content_type: &str) -> Result<()> {
+        let request = self.request("PUT", &self.object_url(key))
+            .set("Content-Type", content_type);
+        request.send_bytes(body)
+            .with_context(|| format!("Failed to upload {}", key))?;
+        Ok(())
+    }
+
+    fn r2_delete(&self, key: &str) -> Result<()> {
+        match self.request("DELETE", &self.object_url(key)).call() {
+            Ok(_) | Err(ureq::Error::Status(404, _)) => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to delete {}", key)),
+        }
+    }
+
+    fn r2_list(&self, prefix: &str) -> Result<Option<Vec<String>>> {
+        let url = self.url(&format!("r2/buckets/{}/objects", self.resources.bucket_name));
+        let mut keys = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut request = self.request("GET", &url)
+                .query("prefix", prefix)
+                .query("per_page", "1000");
+            if let Some(cursor) = &cursor {
+                request = request.query("cursor", cursor);
+            }
+
+            let page = self.call::<Vec<R2Object>>(request, None)?;
+            keys.extend(page.result.unwrap_or_default().into_iter().map(|object| object.key));
+
+            match page.result_info {
+                Some(ResultInfo { cursor: Some(next), is_truncated: true }) => cursor = Some(next),
+                _ => break,
+            }
+        }
+
+        Ok(Some(keys))
+    }
+
+    fn kv_put(&self, namespace_id: &str, key: &str, value: &[u8]) -> Result<()> {
+        let url = self.url(&format!("storage/kv/namespaces/{}/values/{}", namespace_id, encode_path(key)));
+        let request = self.request("PUT", &url)
+            .set("Content-Type", "application/octet-stream");
+        request.send_bytes(value)
+            .with_context(|| format!("Failed to write KV key {}", key))?;
+        Ok(())
+    }
+
+    fn purge_cache(&self, tags: &[&str]) -> Result<bool> {
+        let Some(zone_id) = &self.zone_id else {
+            return Ok(false);
+        };
+        let url = format!("{}/zones/{}/purge_cache", self.base_url, zone_id);
+        self.call::<Value>(self.request("POST", &url), Some(serde_json::json!({ "tags": tags })))?;
+        Ok(true)
+    }
+
+    fn describe(&self) -> String {
+        format!("Cloudflare API ({})", self.base_url)
+    }
+}
+
+fn format_errors(errors: &[ApiMessage]) -> String {
+    if errors.is_empty() {
+        return "unknown error".to_string();
+    }
+    errors.iter()
+        .map(|e| format!("{} (code {})", e.message, e.code))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Percent-encode an object key for a URL path, keeping `/` separators
+fn encode_path(key: &str) -> String {
+    let mut encoded = String::with_capacity(key.len());
+    for byte in key.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::thread;
+
+    /// Request captured by the mock server
+    struct Recorded {
+        method: String,
+        url: String,
+        authorization: Option<String>,
+        body: Vec<u8>,
+    }
+
+    /// Serve one canned response per request on a local port, recording each request
+    fn mock_server(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<Recorded>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let mut request = server.recv().unwrap();
+                let mut request_body = Vec::new();
+                request.as_reader().read_to_end(&mut request_body).unwrap();
+                sender.send(Recorded {
+                    method: request.method().to_string(),
+                    url: request.url().to_string(),
+                    authorization: request.headers().iter()
+                        .find(|h| h.field.equiv("Authorization"))
+                        .map(|h| h.value.to_string()),
+                    body: request_body,
+                }).unwrap();
+                request.respond(tiny_http::Response::from_string(body).with_status_code(status)).unwrap();
+            }
+        });
+
+        (base_url, receiver)
+    }
+
+    fn client(base_url: &str) -> Box<dyn Backend> {
+        let resources = Resources {
+            database_id: "db-id".to_string(),
+            database_name: "db".to_string(),
+            bucket_name: "bucket".to_string(),
+        };
+        Box::new(CloudflareClient::new(base_url, "account", "secret", resources))
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Row {
+        slug: String,
+        word_count: u32,
+    }
+
+    #[test]
+    fn test_query_sends_parameters_and_parses_rows() {
+        let (base_url, requests) = mock_server(vec![(200, r#"{
+            "success": true, "errors": [], "messages": [],
+            "result": [{"success": true, "meta": {}, "results": [{"slug": "hello", "word_count": 42}]}]
+        }"#)]);
+
+        let statement = Statement::new("SELECT slug, word_count FROM posts WHERE slug = ?").bind("hello");
+        let rows: Vec<Row> = client(&base_url).query(&statement).unwrap();
+        assert_eq!(rows, vec![Row { slug: "hello".to_string(), word_count: 42 }]);
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.url, "/accounts/account/d1/database/db-id/query");
+        assert_eq!(request.authorization.as_deref(), Some("Bearer secret"));
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(body, serde_json::json!({
+            "sql": "SELECT slug, word_count FROM posts WHERE slug = ?",
+            "params": ["hello"],
+        }));
+    }
+
+    #[test]
+    fn test_batch_sends_all_statements() {
+        let (base_url, requests) = mock_server(vec![(200, r#"{"success": true, "errors": [], "result": []}"#)]);
+
+        let statements = vec![
+            Statement::new("INSERT INTO tags (name) VALUES (?)").bind("rust"),
+            Statement::new("DELETE FROM tags WHERE id = ?").bind(7),
+        ];
+        client(&base_url).batch(&statements).unwrap();
+
+        let body: Value = serde_json::from_slice(&requests.recv().unwrap().body).unwrap();
+        assert_eq!(body, serde_json::json!({"batch": [
+            {"sql": "INSERT INTO tags (name) VALUES (?)", "params": ["rust"]},
+            {"sql": "DELETE FROM tags WHERE id = ?", "params": [7]},
+        ]}));
+    }
+
+    #[test]
+    fn test_api_errors_are_reported() {
+        let (base_url, _requests) = mock_server(vec![(400, r#"{
+            "success": false, "result": null,
+            "errors": [{"code": 7500, "message": "no such table: postz"}]
+        }"#)]);
+
+        let error = client(&base_url).execute(Statement::new("SELECT * FROM postz")).unwrap_err();
+        assert!(format!("{:#}", error).contains("no such table: postz (code 7500)"));
+    }
+
+    #[test]
+    fn test_r2_get_put_delete() {
+        let (base_url, requests) = mock_server(vec![
+            (200, "# Hello"),
+            (404, r#"{"success": false, "errors": [{"code": 10007, "message": "not found"}]}"#),
+            (200, r#"{"success": true, "errors": [], "result": {}}"#),
+            (204, ""),
+        ]);
+        let backend = client(&base_url);
+
+        assert_eq!(backend.r2_get_text("posts/abc.md").unwrap(), "# Hello");
+        assert_eq!(backend.r2_get("posts/missing.md").unwrap(), None);
+        backend.r2_put("posts/my post/a b.png", b"png", "image/png").unwrap();
+        backend.r2_delete("posts/abc.md").unwrap();
+
+        let urls: Vec<(String, String)> = requests.iter().take(4).map(|r| (r.method, r.url)).collect();
+        assert_eq!(urls, vec![
+            ("GET".to_string(), "/accounts/account/r2/buckets/bucket/objects/posts/abc.md".to_string()),
+            ("GET".to_string(), "/accounts/account/r2/buckets/bucket/objects/posts/missing.md".to_string()),
+            ("PUT".to_string(), "/accounts/account/r2/buckets/bucket/objects/posts/my%20post/a%20b.png".to_string()),
+            ("DELETE".to_string(), "/accounts/account/r2/buckets/bucket/objects/posts/abc.md".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_r2_list_follows_cursor() {
+        let (base_url, requests) = mock_server(vec![
+            (200, r#"{"success": true, "errors": [], "result": [{"key": "posts/a.md"}],
+                      "result_info": {"cursor": "next", "is_truncated": true}}"#),
+            (200, r#"{"success": true, "errors": [], "result": [{"key": "posts/b.md"}],
+                      "result_info": {"is_truncated": false}}"#),
+        ]);
+
+        let keys = client(&base_url).r2_list("posts/").unwrap().unwrap();
+        assert_eq!(keys, vec!["posts/a.md", "posts/b.md"]);
+
+        let urls: Vec<String> = requests.iter().take(2).map(|r| r.url).collect();
+        assert_eq!(urls[0], "/accounts/account/r2/buckets/bucket/objects?prefix=posts%2F&per_page=1000");
+        assert!(urls[1].ends_with("&cursor=next"));
+    }
+
+    #[test]
+    fn test_kv_put() {
+        let (base_url, requests) = mock_server(vec![(200, r#"{"success": true, "errors": [], "result": null}"#)]);
+
+        client(&base_url).kv_put("ns", "resume", b"{}").unwrap();
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.url, "/accounts/account/storage/kv/namespaces/ns/values/resume");
+        assert_eq!(request.body, b"{}");
+    }
+
+    #[test]
+    fn test_purge_cache() {
+        let (base_url, requests) = mock_server(vec![(200, r#"{"success": true, "errors": [], "result": {"id": "x"}}"#)]);
+
+        assert!(!client(&base_url).purge_cache(&["api-posts"]).unwrap());
+
+        let resources = Resources {
+            database_id: "db-id".to_string(),
+            database_name: "db".to_string(),
+            bucket_name: "bucket".to_string(),
+        };
+        let zoned = CloudflareClient::new(base_url.as_str(), "account", "secret", resources).with_zone(Some("zone".to_string()));
+        assert!(zoned.purge_cache(&["api-posts", "api-tags"]).unwrap());
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.url, "/zones/zone/purge_cache");
+        assert_eq!(serde_json::from_slice::<Value>(&request.body).unwrap(), serde_json::json!({ "tags": ["api-posts", "api-tags"] }));
+    }
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(encode_path("posts/abc.md"), "posts/abc.md");
+        assert_eq!(encode_path("posts/a b/ü?.png"), "posts/a%20b/%C3%BC%3F.png");
+    }
+}
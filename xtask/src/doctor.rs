@@ -0,0 +1,351 @@
+// Consistency checks between the D1 tables and the R2 objects they reference
+//
+// Publishing writes D1 and R2 in separate requests, so an interrupted
+// publish or delete can leave one side without the other.
+
+use anyhow::Result;
+use models::Tag;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use crate::cloudflare::{Backend, Statement};
+use crate::{connect, extract_relative_images, status, tags};
+
+#[derive(Debug, Deserialize)]
+struct PostRow {
+    content_id: String,
+    slug: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AliasRow {
+    slug: String,
+    target_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionRow {
+    post_id: String,
+    version: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagRow {
+    id: i64,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CountRow {
+    count: u32,
+}
+
+/// A single inconsistency found by the checker
+#[derive(Debug)]
+enum Issue {
+    /// Post row whose markdown is missing; restorable if a saved version exists
+    MissingContent { content_id: String, slug: String, restore_from: Option<u32> },
+    /// Object under `posts/` or `post-versions/` that no post owns
+    OrphanedObject { key: String },
+    /// Tag no post or project uses
+    UnusedTag { id: i64, name: String },
+    /// Tag name that normalizes differently ("Web Dev"); merged into the canonical tag if there is one
+    NonCanonicalTag { id: i64, name: String, canonical: Option<String> },
+    /// `post_tags` rows pointing at a deleted post or tag
+    DanglingTagLinks { count: u32 },
+    /// Relative image in a post that is not in R2; copyable if an old slug still has it
+    BrokenImage { key: String, copy_from: Option<String> },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::MissingContent { content_id, slug, restore_from } => {
+                write!(f, "missing content: post '{}' has no posts/{}.md", slug, content_id)?;
+                match restore_from {
+                    Some(version) => write!(f, " (fix: restore version {})", version),
+                    None => write!(f, " (fix: remove the post row)"),
+                }
+            }
+            Issue::OrphanedObject { key } => write!(f, "orphaned object: {} (fix: delete)", key),
+            Issue::UnusedTag { name, .. } => write!(f, "unused tag: {} (fix: delete)", name),
+            Issue::NonCanonicalTag { name, canonical, .. } => match canonical {
+                Some(canonical) => write!(f, "non-canonical tag: '{}' (fix: merge into {})", name, canonical),
+                None => write!(f, "non-canonical tag: '{}' (rename it with `tags rename '{}' <new-name>`)", name, name),
+            },
+            Issue::DanglingTagLinks { count } => {
+                write!(f, "dangling tag links: {} post_tags row(s) (fix: delete)", count)
+            }
+            Issue::BrokenImage { key, copy_from } => match copy_from {
+                Some(from) => write!(f, "broken image: {} (fix: copy from {})", key, from),
+                None => write!(f, "broken image: {} (re-publish the post to upload it)", key),
+            },
+        }
+    }
+}
+
+/// Check D1 against R2 and print every inconsistency; with `fix`, repair them
+///
+/// Fails when problems remain, so it can gate scripts.
+pub fn run(workspace_root: &Path, fix: bool, remote: bool) -> Result<()> {
+    let location = if remote { "remote" } else { "local" };
+    status!("Checking", "D1 and R2 consistency ({})", location);
+
+    let cf = connect(workspace_root, remote)?;
+    let cf = cf.as_ref();
+
+    let store = ObjectStore::new(cf)?;
+    let issues = find_issues(cf, &store)?;
+
+    if issues.is_empty() {
+        status!("Finished", "no problems found");
+        return Ok(());
+    }
+
+    for issue in &issues {
+        println!("  {}", issue);
+    }
+
+    if !fix {
+        anyhow::bail!("{} problem(s) found (run with --fix to repair)", issues.len());
+    }
+
+    let unfixed = fix_issues(cf, &issues);
+    if unfixed > 0 {
+        anyhow::bail!("{} problem(s) could not be repaired", unfixed);
+    }
+
+    status!("Finished", "repaired {} problem(s)", issues.len());
+    Ok(())
+}
+
+/// Existence checks for R2 keys, from a full listing when one is available
+struct ObjectStore<'a> {
+    cf: &'a dyn Backend,
+    /// Keys under `posts/` and `post-versions/`; `None` when listing is unavailable
+    keys: Option<HashSet<String>>,
+}
+
+impl<'a> ObjectStore<'a> {
+    fn new(cf: &'a dyn Backend) -> Result<Self> {
+        let keys = match (cf.r2_list("posts/")?, cf.r2_list("post-versions/")?) {
+            (Some(posts), Some(versions)) => Some(posts.into_iter().chain(versions).collect()),
+            _ => {
+                // Objects are probed one by one and orphaned objects are not reported
+                eprintln!("  Warning: {} cannot list R2, skipping the orphaned object check", cf.describe());
+                None
+            }
+        };
+
+        Ok(Self { cf, keys })
+    }
+
+    fn exists(&self, key: &str) -> Result<bool> {
+        match &self.keys {
+            Some(keys) => Ok(keys.contains(key)),
+            None => Ok(self.cf.r2_get(key)?.is_some()),
+        }
+    }
+}
+
+fn find_issues(cf: &dyn Backend, store: &ObjectStore) -> Result<Vec<Issue>> {
+    let posts: Vec<PostRow> = cf.query(&Statement::new("SELECT content_id, slug FROM posts"))?;
+    let aliases: Vec<AliasRow> = cf.query(&Statement::new(
+        "SELECT old_slug as slug, target_id FROM slug_aliases WHERE kind = 'post'",
+    ))?;
+    let versions: Vec<VersionRow> = cf.query(&Statement::new(
+        "SELECT post_id, version FROM post_versions ORDER BY version DESC",
+    ))?;
+
+    let mut issues = Vec::new();
+
+    // Every post needs its markdown; images it references must exist under its slug
+    for post in &posts {
+        let content_key = format!("posts/{}.md", post.content_id);
+        if !store.exists(&content_key)? {
+            let mut restore_from = None;
+            for v in versions.iter().filter(|v| v.post_id == post.content_id) {
+                if store.exists(&format!("post-versions/{}/{}.md", post.content_id, v.version))? {
+                    restore_from = Some(v.version);
+                    break;
+                }
+            }
+            issues.push(Issue::MissingContent {
+                content_id: post.content_id.clone(),
+                slug: post.slug.clone(),
+                restore_from,
+            });
+            continue;
+        }
+
+        let markdown = cf.r2_get_text(&content_key)?;
+        let old_slugs: Vec<&str> = aliases.iter()
+            .filter(|alias| alias.target_id == post.content_id)
+            .map(|alias| alias.slug.as_str())
+            .collect();
+
+        for image in extract_relative_images(&markdown) {
+            let clean_path = image.strip_prefix("./").unwrap_or(&image);
+            let key = format!("posts/{}/{}", post.slug, clean_path);
+            if store.exists(&key)? {
+                continue;
+            }
+
+            let mut copy_from = None;
+            for slug in &old_slugs {
+                let old_key = format!("posts/{}/{}", slug, clean_path);
+                if store.exists(&old_key)? {
+                    copy_from = Some(old_key);
+                    break;
+                }
+            }
+            issues.push(Issue::BrokenImage { key, copy_from });
+        }
+    }
+
+    // Every object under the post prefixes needs an owner
+    if let Some(keys) = &store.keys {
+        let content_ids: HashSet<&str> = posts.iter().map(|p| p.content_id.as_str()).collect();
+        let slugs: HashSet<&str> = posts.iter().map(|p| p.slug.as_str())
+            .chain(aliases.iter().map(|a| a.slug.as_str()))
+            .collect();
+        let mut saved_versions: HashMap<&str, HashSet<u32>> = HashMap::new();
+        for v in &versions {
+            saved_versions.entry(v.post_id.as_str()).or_default().insert(v.version);
+        }
+
+        let mut orphans: Vec<&String> = keys.iter()
+            .filter(|key| !is_owned(key, &content_ids, &slugs, &saved_versions))
+            .collect();
+        orphans.sort();
+        issues.extend(orphans.into_iter().map(|key| Issue::OrphanedObject { key: key.clone() }));
+    }
+
+    // Tags and tag links
+    // Tags given metadata are kept even while nothing uses them
+    let unused_tags: Vec<TagRow> = cf.query(&Statement::new(
+        "SELECT t.id, t.name FROM tags t \
+         WHERE NOT EXISTS (SELECT 1 FROM post_tags pt WHERE pt.tag_id = t.id) \
+         AND NOT EXISTS (SELECT 1 FROM project_tags pt WHERE pt.tag_id = t.id) \
+         AND t.display_name IS NULL AND t.description IS NULL AND t.color IS NULL \
+         ORDER BY t.name",
+    ))?;
+    let unused_ids: HashSet<i64> = unused_tags.iter().map(|tag| tag.id).collect();
+    issues.extend(unused_tags.into_iter().map(|tag| Issue::UnusedTag { id: tag.id, name: tag.name }));
+
+    // Older tooling accepted names such as "web dev"; unused ones are deleted instead
+    let tags: Vec<TagRow> = cf.query(&Statement::new("SELECT id, name FROM tags ORDER BY name"))?;
+    for tag in tags.into_iter().filter(|tag| !Tag::is_canonical(&tag.name) && !unused_ids.contains(&tag.id)) {
+        let canonical = match Tag::normalize(&tag.name) {
+            Ok(canonical) => Some(tags::resolve_alias(cf, canonical.as_str())?),
+            Err(_) => None,
+        };
+        issues.push(Issue::NonCanonicalTag { id: tag.id, name: tag.name, canonical });
+    }
+
+    let dangling: Vec<CountRow> = cf.query(&Statement::new(format!(
+        "SELECT COUNT(*) as count FROM post_tags WHERE {}",
+        DANGLING_TAG_LINKS
+    )))?;
+    if let Some(CountRow { count }) = dangling.into_iter().next().filter(|row| row.count > 0) {
+        issues.push(Issue::DanglingTagLinks { count });
+    }
+
+    Ok(issues)
+}
+
+/// Condition matching `post_tags` rows whose post or tag no longer exists
+const DANGLING_TAG_LINKS: &str =
+    "post_id NOT IN (SELECT content_id FROM posts) OR tag_id NOT IN (SELECT id FROM tags)";
+
+/// Whether an R2 key belongs to an existing post
+///
+/// - `posts/{content_id}.md` needs the post
+/// - `posts/{slug}/...` needs a post with that slug, current or old
+/// - `post-versions/{content_id}/{version}.md` needs the saved version row
+fn is_owned(
+    key: &str,
+    content_ids: &HashSet<&str>,
+    slugs: &HashSet<&str>,
+    saved_versions: &HashMap<&str, HashSet<u32>>,
+) -> bool {
+    if let Some(rest) = key.strip_prefix("posts/") {
+        return match rest.split_once('/') {
+            Some((slug, _)) => slugs.contains(slug),
+            None => rest.strip_suffix(".md").is_some_and(|id| content_ids.contains(id)),
+        };
+    }
+
+    if let Some(rest) = key.strip_prefix("post-versions/") {
+        return rest.split_once('/')
+            .and_then(|(id, file)| {
+                let version = file.strip_suffix(".md")?.parse::<u32>().ok()?;
+                Some(saved_versions.get(id)?.contains(&version))
+            })
+            .unwrap_or(false);
+    }
+
+    true
+}
+
+/// Apply the fix for each issue, returning how many could not be fixed
+fn fix_issues(cf: &dyn Backend, issues: &[Issue]) -> usize {
+    let mut unfixed = 0;
+    let mut statements = Vec::new();
+
+    for issue in issues {
+        let result = match issue {
+            Issue::MissingContent { content_id, restore_from: Some(version), .. } => {
+                status!("Restoring", "posts/{}.md from version {}", content_id, version);
+                cf.r2_copy(
+                    &format!("post-versions/{}/{}.md", content_id, version),
+                    &format!("posts/{}.md", content_id),
+                )
+            }
+            Issue::MissingContent { content_id, slug, restore_from: None } => {
+                status!("Removing", "post {}", slug);
+                statements.push(Statement::new("DELETE FROM posts WHERE content_id = ?").bind(content_id.as_str()));
+                Ok(())
+            }
+            Issue::OrphanedObject { key } => {
+                status!("Deleting", "{}", key);
+                cf.r2_delete(key)
+            }
+            Issue::UnusedTag { id, name } => {
+                status!("Deleting", "tag {}", name);
+                statements.push(Statement::new("DELETE FROM tags WHERE id = ?").bind(*id));
+                Ok(())
+            }
+            Issue::NonCanonicalTag { id, name, canonical: Some(canonical) } => {
+                status!("Merging", "tag '{}' into {}", name, canonical);
+                statements.extend(tags::merge_statements(*id, name, canonical));
+                Ok(())
+            }
+            Issue::NonCanonicalTag { canonical: None, .. } => Err(anyhow::anyhow!("the name has no canonical form")),
+            Issue::DanglingTagLinks { count } => {
+                status!("Deleting", "{} dangling tag link(s)", count);
+                statements.push(Statement::new(format!("DELETE FROM post_tags WHERE {}", DANGLING_TAG_LINKS)));
+                Ok(())
+            }
+            Issue::BrokenImage { key, copy_from: Some(from) } => {
+                status!("Copying", "{} to {}", from, key);
+                cf.r2_copy(from, key)
+            }
+            Issue::BrokenImage { copy_from: None, .. } => Err(anyhow::anyhow!("no copy of the image in R2")),
+        };
+
+        if let Err(e) = result {
+            eprintln!("  Error: Could not fix {}: {:#}", issue, e);
+            unfixed += 1;
+        }
+    }
+
+    if let Err(e) = cf.batch(&statements) {
+        eprintln!("  Error: Could not apply D1 repairs: {:#}", e);
+        unfixed += statements.len();
+    }
+
+    unfixed
+}
@@ -0,0 +1,331 @@
+// `post list`, `post history` and `projects list`: typed rows printed as a table, JSON or CSV
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use models::post::{Post, PostStatus};
+use models::project::{Project, ProjectStage};
+use models::{Slug, Tag};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::cloudflare::Statement;
+use crate::{connect, status};
+
+/// Output format of the list commands
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+/// Saved version as listed by `post history`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionRow {
+    pub version: u32,
+    pub title: String,
+    pub status: String,
+    pub saved_at: String,
+}
+
+/// Row that can be printed as a table or CSV line
+trait Row: Serialize {
+    const HEADERS: &'static [&'static str];
+
+    fn cells(&self) -> Vec<String>;
+}
+
+impl Row for Post {
+    const HEADERS: &'static [&'static str] = &["slug", "title", "status", "published_at", "tags"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.slug.clone(),
+            self.title.clone(),
+            self.status.as_str().to_string(),
+            self.published_at.clone(),
+            self.tags.join(", "),
+        ]
+    }
+}
+
+impl Row for Project {
+    const HEADERS: &'static [&'static str] = &["slug", "name", "stage", "updated_at", "tags"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.slug.clone(),
+            self.name.clone(),
+            self.stage.as_str().to_string(),
+            self.updated_at.clone(),
+            self.tags.join(", "),
+        ]
+    }
+}
+
+impl Row for VersionRow {
+    const HEADERS: &'static [&'static str] = &["version", "title", "status", "saved_at"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.version.to_string(), self.title.clone(), self.status.clone(), self.saved_at.clone()]
+    }
+}
+
+/// Filters shared by the list commands
+#[derive(Debug, Default)]
+pub struct Filters {
+    pub tag: Option<String>,
+    /// Post status or project stage
+    pub state: Option<String>,
+    /// RFC 3339 timestamp or `YYYY-MM-DD`
+    pub since: Option<String>,
+}
+
+/// `WHERE` clause and parameters built from the filters
+struct Conditions {
+    clauses: Vec<String>,
+    statement: Statement,
+}
+
+impl Conditions {
+    fn new(sql: &str) -> Self {
+        Self { clauses: Vec::new(), statement: Statement::new(sql) }
+    }
+
+    fn add(&mut self, clause: String, value: String) {
+        self.clauses.push(clause);
+        self.statement.params.push(value.into());
+    }
+
+    fn finish(mut self, suffix: &str) -> Statement {
+        if !self.clauses.is_empty() {
+            self.statement.sql.push_str(" WHERE ");
+            self.statement.sql.push_str(&self.clauses.join(" AND "));
+        }
+        self.statement.sql.push(' ');
+        self.statement.sql.push_str(suffix);
+        self.statement
+    }
+}
+
+pub fn posts(workspace_root: &Path, filters: Filters, format: Format, remote: bool) -> Result<()> {
+    let mut query = Conditions::new(
+        "SELECT p.content_id, p.slug, p.title, p.summary, p.status, p.published_at, p.updated_at, \
+         p.created_at, p.external_url, p.word_count, p.read_time_minutes, \
+         (SELECT json_group_array(t.name) FROM post_tags pt \
+          INNER JOIN tags t ON pt.tag_id = t.id \
+          WHERE pt.post_id = p.content_id) as tags \
+         FROM posts p",
+    );
+
+    if let Some(tag) = filters.tag {
+        query.add(
+            "EXISTS (SELECT 1 FROM post_tags pt INNER JOIN tags t ON pt.tag_id = t.id \
+             LEFT JOIN tag_aliases a ON a.tag_id = t.id \
+             WHERE pt.post_id = p.content_id AND ? IN (t.name, a.alias))".to_string(),
+            Tag::normalize(&tag)?.as_str().to_string(),
+        );
+    }
+    if let Some(status) = filters.state {
+        query.add("p.status = ?".to_string(), PostStatus::parse(&status)?.as_str().to_string());
+    }
+    if let Some(since) = filters.since {
+        query.add("p.published_at >= ?".to_string(), parse_since(&since)?);
+    }
+
+    if format == Format::Table {
+        let location = if remote { "remote" } else { "local" };
+        status!("Listing", "posts ({})", location);
+    }
+
+    let cf = connect(workspace_root, remote)?;
+    let rows: Vec<Post> = cf.query(&query.finish("ORDER BY p.published_at DESC"))
+        .context("Failed to query posts")?;
+
+    print_rows(&rows, format)
+}
+
+pub fn projects(workspace_root: &Path, filters: Filters, format: Format, remote: bool) -> Result<()> {
+    let mut query = Conditions::new(
+        "SELECT p.id, p.slug, p.name, p.description, p.stage, p.open_to_contributors, p.readme_url, \
+         p.created_at, p.updated_at, \
+         (SELECT json_group_array(t.name) FROM project_tags pt \
+          INNER JOIN tags t ON pt.tag_id = t.id \
+          WHERE pt.project_id = p.id) as tags, \
+         (SELECT json_group_array(json_object('label', u.label, 'url', u.url)) FROM project_urls u \
+          WHERE u.project_id = p.id) as urls \
+         FROM projects p",
+    );
+
+    if let Some(tag) = filters.tag {
+        query.add(
+            "EXISTS (SELECT 1 FROM project_tags pt INNER JOIN tags t ON pt.tag_id = t.id \
+             LEFT JOIN tag_aliases a ON a.tag_id = t.id \
+             WHERE pt.project_id = p.id AND ? IN (t.name, a.alias))".to_string(),
+            Tag::normalize(&tag)?.as_str().to_string(),
+        );
+    }
+    if let Some(stage) = filters.state {
+        query.add("p.stage = ?".to_string(), ProjectStage::parse(&stage)?.as_str().to_string());
+    }
+    if let Some(since) = filters.since {
+        query.add("p.updated_at >= ?".to_string(), parse_since(&since)?);
+    }
+
+    if format == Format::Table {
+        let location = if remote { "remote" } else { "local" };
+        status!("Listing", "portfolio projects ({})", location);
+    }
+
+    let cf = connect(workspace_root, remote)?;
+    let rows: Vec<Project> = cf.query(&query.finish("ORDER BY p.updated_at DESC"))
+        .context("Failed to query projects")?;
+
+    print_rows(&rows, format)
+}
+
+pub fn history(workspace_root: &Path, slug: &str, remote: bool) -> Result<()> {
+    let slug = Slug::parse(slug)?;
+
+    status!("Listing", "versions of {}", slug.as_str());
+
+    let query = Statement::new(
+        "SELECT v.version, v.title, v.status, v.created_at as saved_at \
+         FROM post_versions v \
+         INNER JOIN posts p ON p.content_id = v.post_id \
+         WHERE p.slug = ? \
+         ORDER BY v.version DESC",
+    )
+    .bind(slug.as_str());
+
+    let cf = connect(workspace_root, remote)?;
+    let rows: Vec<VersionRow> = cf.query(&query)
+        .context("Failed to query post versions")?;
+
+    print_rows(&rows, Format::Table)
+}
+
+/// Normalize `--since` to SQLite's datetime format, so it compares as text
+fn parse_since(since: &str) -> Result<String> {
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+        return Ok(format!("{} 00:00:00", date));
+    }
+
+    let at = chrono::DateTime::parse_from_rfc3339(since)
+        .context("Invalid --since value (expected YYYY-MM-DD or RFC 3339)")?
+        .with_timezone(&chrono::Utc);
+    Ok(at.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+fn print_rows<T: Row>(rows: &[T], format: Format) -> Result<()> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(rows)?),
+        Format::Csv => print!("{}", render_csv(rows)),
+        Format::Table if rows.is_empty() => println!("  (none)"),
+        Format::Table => print!("{}", render_table(rows)),
+    }
+    Ok(())
+}
+
+fn render_table<T: Row>(rows: &[T]) -> String {
+    let cells: Vec<Vec<String>> = rows.iter().map(Row::cells).collect();
+    let widths: Vec<usize> = T::HEADERS.iter()
+        .enumerate()
+        .map(|(i, header)| {
+            cells.iter()
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(header.len())
+        })
+        .collect();
+
+    let mut out = String::new();
+    let headers: Vec<String> = T::HEADERS.iter().map(|h| h.to_uppercase()).collect();
+    for line in std::iter::once(&headers).chain(&cells) {
+        let padded: Vec<String> = line.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        out.push_str(padded.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn render_csv<T: Row>(rows: &[T]) -> String {
+    let mut out = T::HEADERS.join(",");
+    out.push('\n');
+    for row in rows {
+        let fields: Vec<String> = row.cells().iter().map(|cell| csv_field(cell)).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quote a field if it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(slug: &str, title: &str, tags: &[&str]) -> Post {
+        serde_json::from_value(serde_json::json!({
+            "content_id": "id", "slug": slug, "title": title, "status": "published",
+            "published_at": "2025-01-01 00:00:00", "updated_at": "2025-01-01 00:00:00",
+            "created_at": "2025-01-01 00:00:00", "tags": tags,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rows_from_d1() {
+        let row: Project = serde_json::from_value(serde_json::json!({
+            "id": "p1", "slug": "crate", "name": "Crate", "description": "", "stage": "active",
+            "open_to_contributors": 1, "readme_url": "", "created_at": "c", "updated_at": "u",
+            "tags": "[\"rust\",\"web\"]", "urls": "[{\"label\":\"GitHub\",\"url\":\"https://github.com\"}]"
+        }))
+        .unwrap();
+        assert_eq!(row.stage, ProjectStage::Active);
+        assert!(row.open_to_contributors);
+        assert_eq!(row.tags, vec!["rust", "web"]);
+        assert_eq!(row.urls[0].label, "GitHub");
+    }
+
+    #[test]
+    fn test_render_table() {
+        let rows = [post("a", "Hello", &["rust"]), post("longer-slug", "Hi", &[])];
+        assert_eq!(
+            render_table(&rows),
+            "SLUG         TITLE  STATUS     PUBLISHED_AT         TAGS\n\
+             a            Hello  published  2025-01-01 00:00:00  rust\n\
+             longer-slug  Hi     published  2025-01-01 00:00:00\n"
+        );
+    }
+
+    #[test]
+    fn test_render_csv_quotes_fields() {
+        let rows = [post("a", "Say \"hi\", world", &["rust", "web"])];
+        assert_eq!(
+            render_csv(&rows),
+            "slug,title,status,published_at,tags\n\
+             a,\"Say \"\"hi\"\", world\",published,2025-01-01 00:00:00,\"rust, web\"\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("2025-03-01").unwrap(), "2025-03-01 00:00:00");
+        assert_eq!(parse_since("2025-03-01T10:00:00+02:00").unwrap(), "2025-03-01 08:00:00");
+        assert!(parse_since("last week").is_err());
+    }
+}
@@ -5,9 +5,18 @@ use serde::Deserialize;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-// ANSI color codes
-const GREEN: &str = "\x1b[32m";
-const RESET: &str = "\x1b[0m";
+use cloudflare::{content_type_for, Backend, Statement};
+use models::post::{reading_stats, PostStatus};
+use models::project::{ProjectPatch, ProjectStage, ProjectUrl};
+use models::{Slug, Tag};
+use sql::{json_list, Delete, Insert, Update};
+
+mod cloudflare;
+mod doctor;
+mod list;
+mod sql;
+mod tags;
+mod wrangler;
 
 // Structs for parsing wrangler.toml
 #[derive(Debug, Deserialize)]
@@ -21,7 +30,6 @@ struct WranglerConfig {
 struct D1Database {
     binding: String,
     database_name: String,
-    #[allow(dead_code)]
     database_id: String,
 }
 
@@ -75,12 +83,29 @@ enum Commands {
         command: ProjectsCommands,
     },
 
+    /// Tag metadata, merge and rename commands
+    Tags {
+        #[command(subcommand)]
+        command: TagsCommands,
+    },
+
     /// Resume management commands
     Resume {
         #[command(subcommand)]
         command: ResumeCommands,
     },
 
+    /// Check that D1 rows and R2 objects agree, optionally repairing them
+    Doctor {
+        /// Repair what can be repaired (restore or remove posts, delete orphans and unused tags)
+        #[arg(long)]
+        fix: bool,
+
+        /// Check remote (default is local)
+        #[arg(long)]
+        remote: bool,
+    },
+
     /// Apply database migrations
     Migrate {
         /// Apply to remote database (default is local)
@@ -107,18 +132,21 @@ enum ProjectCommands {
 
 #[derive(Subcommand)]
 enum PostCommands {
-    /// Publish a post
+    /// Publish a post, or every post in a directory
+    ///
+    /// Metadata is read from YAML (`---`) or TOML (`+++`) front matter;
+    /// flags override it. The front matter is not uploaded.
     Publish {
-        /// Path to markdown file
-        file: String,
+        /// Path to a markdown file, or a directory of `*.md` files / `<slug>/index.md` folders
+        path: String,
 
-        /// URL slug for the post
+        /// URL slug for the post (default: front matter, then the file or folder name)
         #[arg(long)]
-        slug: String,
+        slug: Option<String>,
 
-        /// Post title
+        /// Post title (default: front matter)
         #[arg(long)]
-        title: String,
+        title: Option<String>,
 
         /// Post summary
         #[arg(long)]
@@ -132,23 +160,144 @@ enum PostCommands {
         #[arg(long)]
         external_url: Option<String>,
 
+        /// Post status (draft, published, unlisted; default: published)
+        #[arg(long)]
+        status: Option<String>,
+
         /// Publish to remote (default is local)
         #[arg(long)]
         remote: bool,
     },
 
-    /// List all published posts
+    /// Replace an existing post's markdown and metadata, keeping its content_id
+    ///
+    /// The previous version is saved and can be restored with `post rollback`.
+    /// Front matter and flags work as in `post publish`; fields left unset keep their current values.
+    Update {
+        /// Slug of the post to update
+        slug: String,
+
+        /// Path to the new markdown file
+        path: String,
+
+        /// Post title
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Post summary
+        #[arg(long)]
+        summary: Option<String>,
+
+        /// Tags (comma-separated, replaces the current tags)
+        #[arg(long)]
+        tags: Option<String>,
+
+        /// External URL (for cross-posted content)
+        #[arg(long)]
+        external_url: Option<String>,
+
+        /// Post status (draft, published, unlisted)
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Update remote (default is local)
+        #[arg(long)]
+        remote: bool,
+    },
+
+    /// Restore a post to a previously saved version
+    Rollback {
+        /// Post slug
+        slug: String,
+
+        /// Version to restore (default: the most recent one)
+        #[arg(long)]
+        to: Option<u32>,
+
+        /// Roll back in remote (default is local)
+        #[arg(long)]
+        remote: bool,
+    },
+
+    /// List the saved versions of a post
+    History {
+        /// Post slug
+        slug: String,
+
+        /// Read from remote database (default is local)
+        #[arg(long)]
+        remote: bool,
+    },
+
+    /// Schedule a post to go live at a future time
+    Schedule {
+        /// Post slug to schedule
+        slug: String,
+
+        /// Publish time (RFC 3339, e.g. 2025-12-01T09:00:00Z)
+        #[arg(long)]
+        at: String,
+
+        /// Schedule in remote database (default is local)
+        #[arg(long)]
+        remote: bool,
+    },
+
+    /// Recompute word counts, read times and search bodies from R2 content
+    Reindex {
+        /// Only reindex this post (default is every post)
+        slug: Option<String>,
+
+        /// Reindex remote database (default is local)
+        #[arg(long)]
+        remote: bool,
+    },
+
+    /// List all posts (every status)
     List {
+        /// Only posts with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Only posts with this status (draft, scheduled, published, unlisted)
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Only posts published on or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: list::Format,
+
         /// List from remote database (default is local)
         #[arg(long)]
         remote: bool,
     },
 
-    /// Delete a post
+    /// Change a post's slug; the old slug keeps redirecting to the new one
+    Rename {
+        /// Current post slug
+        slug: String,
+
+        /// New post slug
+        new_slug: String,
+
+        /// Rename in remote (default is local)
+        #[arg(long)]
+        remote: bool,
+    },
+
+    /// Delete a post with its markdown, saved versions and images
     Delete {
         /// Post slug to delete
         slug: String,
 
+        /// Show what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+
         /// Delete from remote (default is local)
         #[arg(long)]
         remote: bool,
@@ -196,13 +345,88 @@ enum ProjectsCommands {
         remote: bool,
     },
 
+    /// Update a portfolio project in place
+    Update {
+        /// Project slug
+        slug: String,
+
+        /// Project name
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Project description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// New stage; must be reachable from the current one (e.g. wip -> active)
+        #[arg(long)]
+        stage: Option<String>,
+
+        /// README URL (GitHub raw URL)
+        #[arg(long)]
+        readme_url: Option<String>,
+
+        /// Open to contributors (true or false)
+        #[arg(long)]
+        open_to_contributors: Option<bool>,
+
+        /// Add a URL in format "label:url", replacing any URL with the same label (repeatable)
+        #[arg(long = "add-url")]
+        add_urls: Vec<String>,
+
+        /// Remove the URL with this label (repeatable)
+        #[arg(long = "remove-url")]
+        remove_urls: Vec<String>,
+
+        /// Add a tag (repeatable)
+        #[arg(long = "add-tag")]
+        add_tags: Vec<String>,
+
+        /// Remove a tag (repeatable)
+        #[arg(long = "remove-tag")]
+        remove_tags: Vec<String>,
+
+        /// Update remote database (default is local)
+        #[arg(long)]
+        remote: bool,
+    },
+
     /// List all portfolio projects
     List {
+        /// Only projects with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Only projects in this stage (planned, wip, active, maintained, archived, shelved)
+        #[arg(long)]
+        stage: Option<String>,
+
+        /// Only projects updated on or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: list::Format,
+
         /// List from remote database (default is local)
         #[arg(long)]
         remote: bool,
     },
 
+    /// Change a project's slug; the old slug keeps redirecting to the new one
+    Rename {
+        /// Current project slug
+        slug: String,
+
+        /// New project slug
+        new_slug: String,
+
+        /// Rename in remote database (default is local)
+        #[arg(long)]
+        remote: bool,
+    },
+
     /// Delete a portfolio project
     Delete {
         /// Project slug to delete
@@ -214,6 +438,57 @@ enum ProjectsCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum TagsCommands {
+    /// Set a tag's display name, description or color (an empty value clears the field)
+    Set {
+        /// Tag name
+        name: String,
+
+        /// Name shown instead of the tag itself
+        #[arg(long)]
+        display_name: Option<String>,
+
+        /// Short description of the tag
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Color as #rrggbb
+        #[arg(long)]
+        color: Option<String>,
+
+        /// Update remote database (default is local)
+        #[arg(long)]
+        remote: bool,
+    },
+
+    /// Move every post and project from one tag to another, keeping the old name as an alias
+    Merge {
+        /// Tag to merge away
+        from: String,
+
+        /// Tag to merge into (created if missing)
+        into: String,
+
+        /// Update remote database (default is local)
+        #[arg(long)]
+        remote: bool,
+    },
+
+    /// Rename a tag, keeping the old name as an alias
+    Rename {
+        /// Current tag name
+        name: String,
+
+        /// New tag name
+        new_name: String,
+
+        /// Update remote database (default is local)
+        #[arg(long)]
+        remote: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum ResumeCommands {
     /// Update resume data in KV from cloud.werdxz.info
@@ -234,24 +509,141 @@ fn main() -> Result<()> {
             ProjectCommands::Deploy { project, production } => deploy(&workspace_root, &project, production),
         },
         Commands::Post { command } => match command {
-            PostCommands::Publish { file, slug, title, summary, tags, external_url, remote } => {
-                publish_post(&workspace_root, &file, &slug, &title, summary.as_deref(), tags.as_deref(), external_url.as_deref(), remote)
+            PostCommands::Publish { path, slug, title, summary, tags, external_url, status, remote } => {
+                let flags = PostMetadata {
+                    title,
+                    slug,
+                    summary,
+                    tags: tags.as_deref().map(split_tags),
+                    external_url,
+                    status,
+                };
+                publish_posts(&workspace_root, &path, flags, remote)
+                    .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_POSTS], remote))
+            }
+            PostCommands::Update { slug, path, title, summary, tags, external_url, status, remote } => {
+                let flags = PostMetadata {
+                    title,
+                    slug: None,
+                    summary,
+                    tags: tags.as_deref().map(split_tags),
+                    external_url,
+                    status,
+                };
+                update_post(&workspace_root, &slug, &path, flags, remote)
+                    .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_POSTS], remote))
             }
-            PostCommands::List { remote } => list_posts(&workspace_root, remote),
-            PostCommands::Delete { slug, remote } => delete_post(&workspace_root, &slug, remote),
+            PostCommands::Rollback { slug, to, remote } => rollback_post(&workspace_root, &slug, to, remote)
+                .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_POSTS], remote)),
+            PostCommands::History { slug, remote } => list::history(&workspace_root, &slug, remote),
+            PostCommands::Schedule { slug, at, remote } => schedule_post(&workspace_root, &slug, &at, remote)
+                .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_POSTS], remote)),
+            PostCommands::Reindex { slug, remote } => reindex_posts(&workspace_root, slug.as_deref(), remote)
+                .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_POSTS], remote)),
+            PostCommands::Rename { slug, new_slug, remote } => rename_post(&workspace_root, &slug, &new_slug, remote)
+                .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_POSTS], remote)),
+            PostCommands::List { tag, status, since, format, remote } => {
+                let filters = list::Filters { tag, state: status, since };
+                list::posts(&workspace_root, filters, format, remote)
+            }
+            PostCommands::Delete { slug, dry_run, remote } => delete_post(&workspace_root, &slug, dry_run, remote)
+                .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_POSTS], remote)),
         },
         Commands::Projects { command } => match command {
             ProjectsCommands::Create { slug, name, description, stage, readme_url, tags, urls, open_to_contributors, remote } => {
-                create_portfolio_project(&workspace_root, &slug, &name, &description, &stage, &readme_url, tags.as_deref(), &urls, open_to_contributors, remote)
+                let project = NewProject { slug, name, description, stage, readme_url, tags, urls, open_to_contributors };
+                create_portfolio_project(&workspace_root, &project, remote)
+                    .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_PROJECTS], remote))
+            }
+            ProjectsCommands::Update {
+                slug,
+                name,
+                description,
+                stage,
+                readme_url,
+                open_to_contributors,
+                add_urls,
+                remove_urls,
+                add_tags,
+                remove_tags,
+                remote,
+            } => {
+                let patch = ProjectPatch {
+                    name,
+                    description,
+                    stage: stage.as_deref().map(ProjectStage::parse).transpose()?,
+                    readme_url,
+                    open_to_contributors,
+                    add_urls: add_urls.iter().map(|url| parse_project_url(url)).collect::<Result<_>>()?,
+                    remove_urls,
+                    add_tags,
+                    remove_tags,
+                };
+                update_portfolio_project(&workspace_root, &slug, patch, remote)
+                    .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_PROJECTS], remote))
+            }
+            ProjectsCommands::List { tag, stage, since, format, remote } => {
+                let filters = list::Filters { tag, state: stage, since };
+                list::projects(&workspace_root, filters, format, remote)
+            }
+            ProjectsCommands::Rename { slug, new_slug, remote } => rename_portfolio_project(&workspace_root, &slug, &new_slug, remote)
+                .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_PROJECTS], remote)),
+            ProjectsCommands::Delete { slug, remote } => delete_portfolio_project(&workspace_root, &slug, remote)
+                .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_PROJECTS], remote)),
+        },
+        Commands::Tags { command } => match command {
+            TagsCommands::Set { name, display_name, description, color, remote } => {
+                let metadata = tags::TagMetadata { display_name, description, color };
+                tags::set(&workspace_root, &name, metadata, remote)
+                    .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_TAGS], remote))
+            }
+            TagsCommands::Merge { from, into, remote } => {
+                tags::merge(&workspace_root, &from, &into, remote)
+                    .and_then(|()| purge_api_cache(&workspace_root, ALL_CACHE_TAGS, remote))
+            }
+            TagsCommands::Rename { name, new_name, remote } => {
+                tags::rename(&workspace_root, &name, &new_name, remote)
+                    .and_then(|()| purge_api_cache(&workspace_root, ALL_CACHE_TAGS, remote))
             }
-            ProjectsCommands::List { remote } => list_portfolio_projects(&workspace_root, remote),
-            ProjectsCommands::Delete { slug, remote } => delete_portfolio_project(&workspace_root, &slug, remote),
         },
         Commands::Resume { command } => match command {
-            ResumeCommands::Update { remote } => update_resume(&workspace_root, remote),
+            ResumeCommands::Update { remote } => update_resume(&workspace_root, remote)
+                .and_then(|()| purge_api_cache(&workspace_root, &[CACHE_TAG_RESUME], remote)),
         },
         Commands::Migrate { remote } => migrate(&workspace_root, remote),
+        Commands::Doctor { fix, remote } => doctor::run(&workspace_root, fix, remote).and_then(|()| match fix {
+            true => purge_api_cache(&workspace_root, ALL_CACHE_TAGS, remote),
+            false => Ok(()),
+        }),
+    }
+}
+
+/// Cache tags of the API's edge-cached responses (see `cache_policy` in `api/src/middleware.rs`)
+const CACHE_TAG_POSTS: &str = "api-posts";
+const CACHE_TAG_PROJECTS: &str = "api-projects";
+const CACHE_TAG_TAGS: &str = "api-tags";
+const CACHE_TAG_RESUME: &str = "api-resume";
+const ALL_CACHE_TAGS: &[&str] = &[CACHE_TAG_POSTS, CACHE_TAG_PROJECTS, CACHE_TAG_TAGS];
+
+/// Purge the API responses cached at the edge that depend on `tags`
+///
+/// Only remote changes are cached. Failing to purge does not fail the command,
+/// which has already saved its change; cached responses expire on their own.
+fn purge_api_cache(workspace_root: &Path, tags: &[&str], remote: bool) -> Result<()> {
+    if !remote {
+        return Ok(());
+    }
+
+    let cf = connect(workspace_root, remote)?;
+    match cf.purge_cache(tags) {
+        Ok(true) => status!("Purged", "API cache ({})", tags.join(", ")),
+        Ok(false) => eprintln!(
+            "  Warning: {} cannot purge the API cache (set CLOUDFLARE_ZONE_ID); cached responses expire within an hour",
+            cf.describe()
+        ),
+        Err(e) => eprintln!("  Warning: Failed to purge the API cache: {:#}", e),
     }
+    Ok(())
 }
 
 /// Load wrangler.toml configuration
@@ -372,55 +764,27 @@ fn update_resume(workspace_root: &Path, remote: bool) -> Result<()> {
     // Load wrangler config
     let config = load_wrangler_config(workspace_root)?;
     let kv_namespace_id = config.kv_namespaces
+        .as_ref()
         .and_then(|namespaces| {
             namespaces.iter()
                 .find(|ns| ns.binding == "RESUME_KV")
                 .map(|ns| ns.id.clone())
         })
         .context("RESUME_KV namespace not found in wrangler.toml")?;
+    let cf = cloudflare::connect(workspace_root, &config, remote)?;
 
     // Fetch resume from cloud.werdxz.info
-    let resume_json = Command::new("curl")
-        .args(["-s", "https://cloud.werdxz.info/resume/public/resume.json"])
-        .output()
-        .context("Failed to fetch resume")?;
-
-    if !resume_json.status.success() {
-        anyhow::bail!("Failed to download resume");
-    }
-
-    // Save to temp file using cross-platform temp directory
-    let temp_dir = dirs::cache_dir()
-        .or_else(|| std::env::temp_dir().into())
-        .context("Failed to get temp directory")?;
-    let temp_file = temp_dir.join("resume.json");
-    std::fs::write(&temp_file, &resume_json.stdout)
-        .context("Failed to write temp file")?;
+    let mut resume_json = Vec::new();
+    ureq::get("https://cloud.werdxz.info/resume/public/resume.json")
+        .call()
+        .context("Failed to fetch resume")?
+        .into_reader()
+        .read_to_end(&mut resume_json)
+        .context("Failed to download resume")?;
 
     // Upload to KV
-    let mut cmd = Command::new("npx");
-    cmd.arg("wrangler")
-        .arg("kv")
-        .arg("key")
-        .arg("put")
-        .arg("resume")
-        .arg("--namespace-id")
-        .arg(&kv_namespace_id)
-        .arg("--path")
-        .arg(&temp_file);
-
-    if remote {
-        cmd.arg("--remote");
-    }
-
-    cmd.current_dir(workspace_root.join("api"));
-
-    let kv_status = cmd.status()
-        .context("Failed to run wrangler")?;
-
-    if !kv_status.success() {
-        anyhow::bail!("Failed to upload resume to KV");
-    }
+    cf.kv_put(&kv_namespace_id, "resume", &resume_json)
+        .context("Failed to upload resume to KV")?;
 
     status!("Finished", "resume update");
     Ok(())
@@ -482,14 +846,7 @@ fn extract_relative_images(content: &str) -> Vec<String> {
 }
 
 /// Upload images referenced in markdown to R2
-fn upload_post_images(
-    workspace_root: &Path,
-    markdown_dir: &Path,
-    slug: &str,
-    bucket_name: &str,
-    images: &[String],
-    remote: bool,
-) -> Result<()> {
+fn upload_post_images(cf: &dyn Backend, markdown_dir: &Path, slug: &str, images: &[String]) -> Result<()> {
     for image_path in images {
         // Remove leading ./ if present
         let clean_path = image_path.strip_prefix("./").unwrap_or(image_path);
@@ -504,612 +861,1208 @@ fn upload_post_images(
 
         // Upload to R2 at posts/{slug}/{filename}
         let r2_key = format!("posts/{}/{}", slug, clean_path);
-        let r2_path = format!("{}/{}", bucket_name, r2_key);
 
         status!("Uploading", "image: {}", clean_path);
 
-        let mut cmd = Command::new("npx");
-        cmd.args(["wrangler", "r2", "object", "put", &r2_path]);
-
-        if remote {
-            cmd.arg("--remote");
-        }
+        let bytes = std::fs::read(&image_file)
+            .with_context(|| format!("Failed to read image: {}", image_file.display()))?;
+        cf.r2_put(&r2_key, &bytes, content_type_for(&r2_key))
+            .with_context(|| format!("Failed to upload image: {}", clean_path))?;
+    }
 
-        cmd.arg("--file")
-            .arg(&image_file)
-            .current_dir(workspace_root.join("api"));
+    Ok(())
+}
 
-        let status = cmd.status()
-            .context(format!("Failed to upload image: {}", clean_path))?;
+/// Post metadata, from front matter and/or CLI flags
+#[derive(Debug, Clone, Default, Deserialize)]
+struct PostMetadata {
+    title: Option<String>,
+    slug: Option<String>,
+    summary: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_tag_list")]
+    tags: Option<Vec<String>>,
+    external_url: Option<String>,
+    status: Option<String>,
+}
 
-        if !status.success() {
-            anyhow::bail!("Failed to upload image: {}", clean_path);
+impl PostMetadata {
+    /// Fill fields that are unset here from `fallback`
+    fn or(self, fallback: PostMetadata) -> PostMetadata {
+        PostMetadata {
+            title: self.title.or(fallback.title),
+            slug: self.slug.or(fallback.slug),
+            summary: self.summary.or(fallback.summary),
+            tags: self.tags.or(fallback.tags),
+            external_url: self.external_url.or(fallback.external_url),
+            status: self.status.or(fallback.status),
         }
     }
-
-    Ok(())
 }
 
-fn publish_post(
-    workspace_root: &Path,
-    file: &str,
-    slug: &str,
-    title: &str,
-    summary: Option<&str>,
-    tags: Option<&str>,
-    external_url: Option<&str>,
-    remote: bool,
-) -> Result<()> {
-    let content_id = uuid::Uuid::new_v4();
+/// Accept tags as a list (`tags: [rust, wasm]`) or a comma-separated string (`tags: rust, wasm`)
+fn deserialize_tag_list<'de, D>(deserializer: D) -> std::result::Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum TagList {
+        List(Vec<String>),
+        Csv(String),
+    }
 
-    status!("Publishing", "{}", title);
-    println!("             slug: {}", slug);
-    println!("       content_id: {}", content_id);
+    Ok(Option::<TagList>::deserialize(deserializer)?.map(|tags| match tags {
+        TagList::List(tags) => tags,
+        TagList::Csv(tags) => split_tags(&tags),
+    }))
+}
 
-    // Load wrangler config
-    let config = load_wrangler_config(workspace_root)?;
-    let db_name = config.d1_databases
-        .as_ref()
-        .and_then(|dbs| {
-            dbs.iter()
-                .find(|db| db.binding == "DB")
-                .map(|db| db.database_name.clone())
-        })
-        .context("DB database not found in wrangler.toml")?;
+/// Split a comma-separated tag list, dropping empty entries
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(String::from)
+        .collect()
+}
 
-    let bucket_name = config.r2_buckets
-        .as_ref()
-        .and_then(|buckets| {
-            buckets.iter()
-                .find(|b| b.binding == "CONTENT_BUCKET")
-                .map(|b| b.bucket_name.clone())
-        })
-        .context("CONTENT_BUCKET not found in wrangler.toml")?;
+/// Split markdown into front matter metadata and body
+fn parse_front_matter(markdown: &str) -> Result<(PostMetadata, &str)> {
+    let Some((delimiter, raw, body)) = split_front_matter(markdown) else {
+        return Ok((PostMetadata::default(), markdown));
+    };
 
-    // Resolve file path relative to workspace root before changing directories
-    let file_path = if Path::new(file).is_absolute() {
-        PathBuf::from(file)
+    let metadata = if raw.trim().is_empty() {
+        PostMetadata::default()
+    } else if delimiter == "---" {
+        serde_yaml::from_str(raw).context("Invalid YAML front matter")?
     } else {
-        workspace_root.join(file)
+        toml::from_str(raw).context("Invalid TOML front matter")?
     };
 
-    // Get the directory containing the markdown file (for resolving relative image paths)
-    let markdown_dir = file_path.parent()
-        .context("Could not determine markdown file directory")?;
-
-    // Read markdown content to find images
-    let content = std::fs::read_to_string(&file_path)
-        .context("Failed to read markdown file")?;
-
-    // Extract and upload any relative images
-    let images = extract_relative_images(&content);
-    if !images.is_empty() {
-        status!("Found", "{} image(s) to upload", images.len());
-        upload_post_images(workspace_root, markdown_dir, slug, &bucket_name, &images, remote)?;
-    }
+    Ok((metadata, body.trim_start_matches(['\r', '\n'])))
+}
 
-    // 1. Upload markdown to R2
-    let r2_key = format!("posts/{}.md", content_id);
-    let r2_path = format!("{}/{}", bucket_name, r2_key);
+/// Publish a single markdown file, or every post in a directory
+fn publish_posts(workspace_root: &Path, path: &str, flags: PostMetadata, remote: bool) -> Result<()> {
+    // Resolve path relative to workspace root before changing directories
+    let path = if Path::new(path).is_absolute() {
+        PathBuf::from(path)
+    } else {
+        workspace_root.join(path)
+    };
 
-    status!("Uploading", "markdown content");
+    let cf = connect(workspace_root, remote)?;
+    let cf = cf.as_ref();
 
-    let mut r2_cmd = Command::new("npx");
-    r2_cmd.args(["wrangler", "r2", "object", "put", &r2_path]);
+    if !path.is_dir() {
+        return publish_post(cf, &path, flags);
+    }
 
-    if remote {
-        r2_cmd.arg("--remote");
+    if flags.slug.is_some() || flags.title.is_some() {
+        anyhow::bail!("--slug and --title can only be used when publishing a single file");
     }
 
-    r2_cmd.arg("--file")
-        .arg(&file_path)
-        .current_dir(workspace_root.join("api"));
+    let files = discover_post_files(&path)?;
+    if files.is_empty() {
+        anyhow::bail!("No markdown posts found in {}", path.display());
+    }
 
-    let r2_status = r2_cmd.status()
-        .context("Failed to upload to R2")?;
+    status!("Found", "{} post(s) in {}", files.len(), path.display());
 
-    if !r2_status.success() {
-        anyhow::bail!("Failed to upload content to R2");
+    let mut failed = 0;
+    for file in &files {
+        if let Err(e) = publish_post(cf, file, flags.clone()) {
+            eprintln!("  Error: {}: {:#}", file.display(), e);
+            failed += 1;
+        }
     }
 
-    // 2. Insert metadata into D1
-    let summary_str = summary.unwrap_or("");
-    let external_url_str = external_url.map(|s| format!(", '{}'", s)).unwrap_or_else(|| ", NULL".to_string());
+    if failed > 0 {
+        anyhow::bail!("{} of {} post(s) failed to publish", failed, files.len());
+    }
 
-    // Insert post without tags (tags will be handled separately)
-    // Escape single quotes in SQL strings by doubling them
-    let escaped_title = title.replace('\'', "''");
-    let escaped_summary = summary_str.replace('\'', "''");
+    Ok(())
+}
 
-    let sql = format!(
-        "INSERT INTO posts (content_id, slug, title, summary, published_at, external_url) \
-         VALUES ('{}', '{}', '{}', '{}', datetime('now'){});",
-        content_id, slug, escaped_title, escaped_summary, external_url_str
-    );
+/// Find posts in a directory: `*.md` files and `<slug>/index.md` folders (README.md is skipped)
+fn discover_post_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
 
-    let mut db_cmd = Command::new("npx");
-    db_cmd.args(["wrangler", "d1", "execute", &db_name]);
+    for entry in std::fs::read_dir(dir).context("Failed to read post directory")? {
+        let path = entry?.path();
 
-    if remote {
-        db_cmd.arg("--remote");
+        if path.is_dir() {
+            let index = path.join("index.md");
+            if index.is_file() {
+                files.push(index);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "md")
+            && !path.file_name().is_some_and(|name| name.eq_ignore_ascii_case("README.md"))
+        {
+            files.push(path);
+        }
     }
 
-    db_cmd.arg("--command")
-        .arg(&sql)
-        .current_dir(workspace_root.join("api"));
+    files.sort();
+    Ok(files)
+}
 
-    let db_status = db_cmd.status()
-        .context("Failed to insert into D1")?;
+/// Derive a slug from a post's path: `my-post.md` or `my-post/index.md` -> `my-post`
+fn slug_from_path(file_path: &Path) -> Option<String> {
+    let stem = file_path.file_stem()?.to_str()?;
+    if stem == "index" {
+        file_path.parent()?.file_name()?.to_str().map(String::from)
+    } else {
+        Some(stem.to_string())
+    }
+}
 
-    if !db_status.success() {
-        anyhow::bail!("Failed to insert post metadata");
+fn publish_post(cf: &dyn Backend, file_path: &Path, flags: PostMetadata) -> Result<()> {
+    // Read markdown and merge its front matter with the CLI flags
+    let raw_content = std::fs::read_to_string(file_path)
+        .with_context(|| format!("Failed to read markdown file: {}", file_path.display()))?;
+    let (front_matter, content) = parse_front_matter(&raw_content)?;
+    let metadata = flags.or(front_matter);
+
+    let slug = metadata.slug.clone()
+        .or_else(|| slug_from_path(file_path))
+        .context("Could not determine slug (set `slug` in front matter or pass --slug)")?;
+    let slug = Slug::parse(slug)?;
+
+    // Republishing an existing slug updates it in place
+    if let Some(existing) = find_post(cf, &slug)? {
+        status!("Found", "existing post {}, updating it", slug.as_str());
+        return update_existing_post(cf, file_path, content, metadata, &existing);
     }
 
-    // 3. Handle tags if provided
-    if let Some(tags_str) = tags {
-        let tag_names: Vec<&str> = tags_str.split(',').map(str::trim).collect();
+    let content_id = uuid::Uuid::new_v4();
+    let title = metadata.title
+        .context("Missing title (set `title` in front matter or pass --title)")?;
+    let status = metadata.status.as_deref().unwrap_or("published");
+    validate_publish_status(status)?;
+    let tags = validate_tags(metadata.tags.unwrap_or_default())?;
 
-        for tag_name in tag_names {
-            if tag_name.is_empty() {
-                continue;
-            }
+    status!("Publishing", "{}", title);
+    println!("             slug: {}", slug.as_str());
+    println!("       content_id: {}", content_id);
 
-            // Insert tag (ignore if exists)
-            let insert_tag_sql = format!(
-                "INSERT OR IGNORE INTO tags (name) VALUES ('{}');",
-                tag_name
-            );
+    // Get the directory containing the markdown file (for resolving relative image paths)
+    let markdown_dir = file_path.parent()
+        .context("Could not determine markdown file directory")?;
 
-            let mut tag_cmd = Command::new("npx");
-            tag_cmd.args(["wrangler", "d1", "execute", &db_name]);
+    // Extract and upload any relative images
+    let images = extract_relative_images(content);
+    if !images.is_empty() {
+        status!("Found", "{} image(s) to upload", images.len());
+        upload_post_images(cf, markdown_dir, slug.as_str(), &images)?;
+    }
 
-            if remote {
-                tag_cmd.arg("--remote");
-            }
-
-            tag_cmd.arg("--command")
-                .arg(&insert_tag_sql)
-                .current_dir(workspace_root.join("api"));
-
-            tag_cmd.status()
-                .context("Failed to insert tag")?;
-
-            // Get tag ID and insert into post_tags junction table
-            let link_tag_sql = format!(
-                "INSERT INTO post_tags (post_id, tag_id) \
-                 SELECT '{}', id FROM tags WHERE name = '{}';",
-                content_id, tag_name
-            );
+    // 1. Upload markdown (without front matter) to R2
+    let r2_key = format!("posts/{}.md", content_id);
 
-            let mut link_cmd = Command::new("npx");
-            link_cmd.args(["wrangler", "d1", "execute", &db_name]);
+    status!("Uploading", "markdown content");
 
-            if remote {
-                link_cmd.arg("--remote");
-            }
+    put_r2_text(cf, &r2_key, content)
+        .context("Failed to upload content to R2")?;
+
+    // 2. Insert metadata, tags and the search index into D1 as one batch
+    let stats = reading_stats(content);
+    println!("            words: {} ({} min read)", stats.word_count, stats.read_time_minutes);
+
+    let content_id = content_id.to_string();
+    let mut statements = vec![
+        Insert::new("posts")
+            .value("content_id", content_id.as_str())
+            .value("slug", slug.as_str())
+            .value("title", title.as_str())
+            .value("summary", metadata.summary.as_deref().unwrap_or(""))
+            .value("status", status)
+            .value_expr("published_at", "datetime('now')")
+            .value("external_url", metadata.external_url)
+            .value("word_count", stats.word_count)
+            .value("read_time_minutes", stats.read_time_minutes)
+            .build(),
+    ];
+    statements.extend(link_tags("post_tags", "post_id", &content_id, &tags));
+
+    // The posts_fts row is created by a trigger on insert
+    statements.push(
+        Update::new("posts_fts")
+            .set("body", content)
+            .where_eq("content_id", content_id.as_str())
+            .build(),
+    );
 
-            link_cmd.arg("--command")
-                .arg(&link_tag_sql)
-                .current_dir(workspace_root.join("api"));
+    status!("Indexing", "post for search");
 
-            link_cmd.status()
-                .context("Failed to link tag to post")?;
+    if let Err(e) = cf.batch(&statements) {
+        // Nothing was written to D1, so the markdown would be orphaned
+        if let Err(cleanup) = cf.r2_delete(&r2_key) {
+            eprintln!("  Warning: Could not remove {}: {:#}", r2_key, cleanup);
         }
+        return Err(e.context("Failed to insert post metadata"));
     }
 
     status!("Finished", "publishing");
-    println!("              url: /posts/{}", slug);
+    println!("           status: {}", status);
+    println!("              url: /posts/{}", slug.as_str());
     Ok(())
 }
 
-fn list_posts(workspace_root: &Path, remote: bool) -> Result<()> {
-    let mode = if remote { "--remote" } else { "--local" };
-    let location = if remote { "remote" } else { "local" };
-
-    status!("Listing", "posts ({})", location);
-
-    // Load wrangler config
-    let config = load_wrangler_config(workspace_root)?;
-    let db_name = config.d1_databases
-        .and_then(|dbs| {
-            dbs.iter()
-                .find(|db| db.binding == "DB")
-                .map(|db| db.database_name.clone())
-        })
-        .context("DB database not found in wrangler.toml")?;
-
-    // Query posts with tags from junction table
-    let query = "SELECT p.slug, p.title, p.published_at, \
-                 GROUP_CONCAT(t.name, ', ') as tags \
-                 FROM posts p \
-                 LEFT JOIN post_tags pt ON p.content_id = pt.post_id \
-                 LEFT JOIN tags t ON pt.tag_id = t.id \
-                 GROUP BY p.content_id \
-                 ORDER BY p.published_at DESC;";
-
-    let output = Command::new("npx")
-        .args(["wrangler", "d1", "execute", &db_name, mode])
-        .arg("--command")
-        .arg(query)
-        .current_dir(workspace_root.join("api"))
-        .output()
-        .context("Failed to query posts")?;
-
-    println!("{}", String::from_utf8_lossy(&output.stdout));
-
+/// Ensure a status can be set directly (scheduling goes through `post schedule`)
+fn validate_publish_status(status: &str) -> Result<()> {
+    if PostStatus::parse(status)? == PostStatus::Scheduled {
+        anyhow::bail!("Use `post schedule` for scheduled posts");
+    }
     Ok(())
 }
 
-fn delete_post(workspace_root: &Path, slug: &str, remote: bool) -> Result<()> {
-    let mode = if remote { "--remote" } else { "--local" };
+/// Normalize tags ("Web Dev" becomes `web-dev`), dropping duplicates
+fn validate_tags(tags: Vec<String>) -> Result<Vec<Tag>> {
+    let mut valid: Vec<Tag> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = Tag::normalize(&tag)?;
+        if !valid.contains(&tag) {
+            valid.push(tag);
+        }
+    }
+    Ok(valid)
+}
 
-    status!("Deleting", "post: {}", slug);
+/// Statements that create any missing `tags` and link them to a post or project
+///
+/// A tag that is an alias links the tag it points at instead of being created.
+fn link_tags(link_table: &'static str, owner_column: &'static str, owner_id: &str, tags: &[Tag]) -> Vec<Statement> {
+    let link_sql = format!(
+        "INSERT OR IGNORE INTO {} ({}, tag_id) SELECT ?, {}",
+        link_table, owner_column, tags::TAG_ID_BY_NAME
+    );
 
-    // Load wrangler config
-    let config = load_wrangler_config(workspace_root)?;
-    let db_name = config.d1_databases
-        .as_ref()
-        .and_then(|dbs| {
-            dbs.iter()
-                .find(|db| db.binding == "DB")
-                .map(|db| db.database_name.clone())
+    tags.iter()
+        .flat_map(|tag| {
+            [
+                Statement::new(
+                    "INSERT OR IGNORE INTO tags (name) SELECT ? WHERE NOT EXISTS \
+                     (SELECT 1 FROM tag_aliases WHERE alias = ?)",
+                )
+                .bind(tag.as_str())
+                .bind(tag.as_str()),
+                Statement::new(link_sql.as_str()).bind(owner_id).bind(tag.as_str()).bind(tag.as_str()),
+            ]
         })
-        .context("DB database not found in wrangler.toml")?;
+        .collect()
+}
 
-    let bucket_name = config.r2_buckets
+/// Name of the R2 bucket bound as `CONTENT_BUCKET`
+fn content_bucket_name(config: &WranglerConfig) -> Result<String> {
+    config.r2_buckets
         .as_ref()
         .and_then(|buckets| {
             buckets.iter()
                 .find(|b| b.binding == "CONTENT_BUCKET")
                 .map(|b| b.bucket_name.clone())
         })
-        .context("CONTENT_BUCKET not found in wrangler.toml")?;
-
-    // 1. Get content_id from D1 (for future R2 deletion)
-    let _output = Command::new("npx")
-        .args(["wrangler", "d1", "execute", &db_name, mode])
-        .arg("--command")
-        .arg(format!("SELECT content_id FROM posts WHERE slug = '{}';", slug))
-        .current_dir(workspace_root.join("api"))
-        .output()
-        .context("Failed to get content_id")?;
-
-    // TODO: Parse content_id from output for automatic R2 deletion
-    // For now, we just delete the DB entry and note that R2 cleanup is manual
-
-    // 2. Delete from D1 (cascades to post_tags due to foreign key)
-    let delete_status = Command::new("npx")
-        .args(["wrangler", "d1", "execute", &db_name, mode])
-        .arg("--command")
-        .arg(format!("DELETE FROM posts WHERE slug = '{}';", slug))
-        .current_dir(workspace_root.join("api"))
-        .status()
-        .context("Failed to delete from D1")?;
-
-    if !delete_status.success() {
-        anyhow::bail!("Failed to delete post from D1");
+        .context("CONTENT_BUCKET not found in wrangler.toml")
+}
+
+/// Current metadata of a post, as needed for updates and rollbacks
+#[derive(Debug, Deserialize)]
+struct ExistingPostRow {
+    content_id: String,
+    title: String,
+    summary: Option<String>,
+    status: String,
+    external_url: Option<String>,
+    /// JSON array of tag names
+    tags: String,
+}
+
+impl ExistingPostRow {
+    fn tag_names(&self) -> Vec<String> {
+        serde_json::from_str(&self.tags).unwrap_or_default()
     }
+}
 
-    status!("Finished", "deletion");
-    println!("        Note: R2 content not deleted automatically");
-    println!("              Use: npx wrangler r2 object delete {} posts/<content_id>.md", bucket_name);
+/// Look up a post by slug, regardless of status
+fn find_post(cf: &dyn Backend, slug: &Slug) -> Result<Option<ExistingPostRow>> {
+    let query = Statement::new(
+        "SELECT p.content_id, p.title, p.summary, p.status, p.external_url, \
+         (SELECT json_group_array(t.name) FROM post_tags pt \
+          INNER JOIN tags t ON pt.tag_id = t.id \
+          WHERE pt.post_id = p.content_id) as tags \
+         FROM posts p WHERE p.slug = ?",
+    )
+    .bind(slug.as_str());
+
+    let rows: Vec<ExistingPostRow> = cf.query(&query)?;
+    Ok(rows.into_iter().next())
+}
 
-    Ok(())
+/// Row holding a post version number
+#[derive(Debug, Deserialize)]
+struct VersionRow {
+    version: u32,
 }
 
-/// Validated project slug (newtype pattern for security)
-/// Ensures slugs are safe for SQL queries and URLs
-#[derive(Debug, Clone)]
-struct ProjectSlug(String);
+fn next_post_version(cf: &dyn Backend, content_id: &str) -> Result<u32> {
+    let query = Statement::new("SELECT COALESCE(MAX(version), 0) + 1 as version FROM post_versions WHERE post_id = ?")
+        .bind(content_id);
 
-impl ProjectSlug {
-    /// Create a new validated project slug
-    ///
-    /// # Security
-    /// - Prevents SQL injection by validating character set
-    /// - Prevents path traversal attacks
-    /// - Enforces reasonable length limits
-    fn new(s: impl Into<String>) -> Result<Self> {
-        let slug = s.into();
-
-        if slug.is_empty() {
-            anyhow::bail!("Slug cannot be empty");
-        }
+    let rows: Vec<VersionRow> = cf.query(&query)?;
+    Ok(rows.first().map_or(1, |row| row.version))
+}
 
-        if slug.len() > 100 {
-            anyhow::bail!("Slug must be 100 characters or less (got {})", slug.len());
-        }
+/// A post's state saved by `snapshot_post`
+struct PostSnapshot {
+    /// Records the metadata; must run before the post row is changed
+    statement: Statement,
+    /// The markdown that was live, to put back if the D1 update fails
+    previous: Option<Vec<u8>>,
+}
 
-        if !slug.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
-            anyhow::bail!("Slug can only contain alphanumeric characters, hyphens, and underscores");
+/// Save a post's current markdown and metadata as `version`
+fn snapshot_post(cf: &dyn Backend, content_id: &str, version: u32) -> Result<PostSnapshot> {
+    // Only a missing object skips the backup; failing to read it must not lose the markdown
+    let previous = cf.r2_get(&format!("posts/{}.md", content_id))
+        .context("Failed to read the current markdown")?;
+    match &previous {
+        Some(previous) => {
+            let version_key = format!("post-versions/{}/{}.md", content_id, version);
+            cf.r2_put(&version_key, previous, content_type_for(&version_key))
+                .context("Failed to save previous version")?;
         }
-
-        Ok(Self(slug))
+        None => eprintln!("  Warning: posts/{}.md does not exist, no previous content saved", content_id),
     }
 
-    /// Get the slug as a string slice
-    fn as_str(&self) -> &str {
-        &self.0
-    }
+    let statement = Statement::new(
+        "INSERT INTO post_versions (post_id, version, title, summary, status, published_at, external_url, tags) \
+         SELECT p.content_id, ?, p.title, p.summary, p.status, p.published_at, p.external_url, \
+         (SELECT json_group_array(t.name) FROM post_tags pt \
+          INNER JOIN tags t ON pt.tag_id = t.id \
+          WHERE pt.post_id = p.content_id) \
+         FROM posts p WHERE p.content_id = ?",
+    )
+    .bind(version)
+    .bind(content_id);
+
+    Ok(PostSnapshot { statement, previous })
+}
+
+/// Run a post's D1 update after its new markdown went live, putting the
+/// snapshot's markdown back if the update fails so R2 and D1 stay in step
+fn commit_post_update(cf: &dyn Backend, content_id: &str, previous: Option<&[u8]>, statements: &[Statement]) -> Result<()> {
+    let Err(e) = cf.batch(statements) else { return Ok(()) };
 
-    /// Get SQL-escaped version (single quotes doubled)
-    /// Note: This is defense in depth - the validation already prevents injection
-    fn as_sql_escaped(&self) -> String {
-        self.0.replace('\'', "''")
+    let key = format!("posts/{}.md", content_id);
+    if let Some(previous) = previous {
+        status!("Restoring", "{}", key);
+        if let Err(restore) = cf.r2_put(&key, previous, content_type_for(&key)) {
+            eprintln!("  Error: Could not restore {}: {:#}", key, restore);
+        }
     }
+    Err(e)
 }
 
-/// Validated tag name (newtype pattern for security)
-/// Ensures tag names are safe for SQL queries
-#[derive(Debug, Clone)]
-struct TagName(String);
+/// Statements that make `tags` the exact tag set of a post, leaving unchanged links alone
+fn replace_post_tags(content_id: &str, tags: &[Tag]) -> Vec<Statement> {
+    let tags_json = json_list(tags.iter().map(Tag::as_str));
+    let mut statements = vec![
+        Statement::new(format!("DELETE FROM post_tags WHERE post_id = ? AND tag_id NOT IN ({})", tags::TAG_IDS_IN_JSON))
+            .bind(content_id)
+            .bind(tags_json.clone())
+            .bind(tags_json),
+    ];
+    statements.extend(link_tags("post_tags", "post_id", content_id, tags));
+    statements
+}
 
-impl TagName {
-    /// Create a new validated tag name
-    ///
-    /// # Security
-    /// - Prevents SQL injection by validating and escaping
-    /// - Enforces reasonable length limits
-    fn new(s: impl Into<String>) -> Result<Self> {
-        let tag = s.into();
-
-        if tag.is_empty() {
-            anyhow::bail!("Tag name cannot be empty");
-        }
+/// Print which tags a change adds and removes
+fn print_tag_diff(old: &[String], new: &[Tag]) {
+    let added: Vec<&str> = new.iter()
+        .map(|tag| tag.as_str())
+        .filter(|tag| !old.iter().any(|o| o == tag))
+        .collect();
+    let removed: Vec<&str> = old.iter()
+        .map(String::as_str)
+        .filter(|tag| !new.iter().any(|n| n.as_str() == *tag))
+        .collect();
+
+    if !added.is_empty() {
+        println!("       tags added: {}", added.join(", "));
+    }
+    if !removed.is_empty() {
+        println!("     tags removed: {}", removed.join(", "));
+    }
+}
 
-        if tag.len() > 50 {
-            anyhow::bail!("Tag name must be 50 characters or less (got {})", tag.len());
-        }
+fn update_post(workspace_root: &Path, slug: &str, path: &str, flags: PostMetadata, remote: bool) -> Result<()> {
+    let slug = Slug::parse(slug)?;
 
-        // Allow alphanumeric, spaces, hyphens, underscores
-        if !tag.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_') {
-            anyhow::bail!("Tag name can only contain alphanumeric characters, spaces, hyphens, and underscores");
-        }
+    let file_path = if Path::new(path).is_absolute() {
+        PathBuf::from(path)
+    } else {
+        workspace_root.join(path)
+    };
 
-        Ok(Self(tag))
-    }
+    let raw_content = std::fs::read_to_string(&file_path)
+        .with_context(|| format!("Failed to read markdown file: {}", file_path.display()))?;
+    let (front_matter, content) = parse_front_matter(&raw_content)?;
 
-    /// Get the tag name as a string slice
-    fn as_str(&self) -> &str {
-        &self.0
+    if front_matter.slug.as_deref().is_some_and(|s| s != slug.as_str()) {
+        anyhow::bail!("Front matter slug does not match '{}'; update cannot change a post's slug", slug.as_str());
     }
+    let metadata = flags.or(front_matter);
 
-    /// Get SQL-escaped version (single quotes doubled)
-    fn as_sql_escaped(&self) -> String {
-        self.0.replace('\'', "''")
-    }
+    let cf = connect(workspace_root, remote)?;
+    let cf = cf.as_ref();
+
+    let existing = find_post(cf, &slug)?
+        .with_context(|| format!("Post '{}' not found (use `post publish` for new posts)", slug.as_str()))?;
+
+    update_existing_post(cf, &file_path, content, metadata, &existing)
 }
 
-fn create_portfolio_project(
-    workspace_root: &Path,
-    slug: &str,
-    name: &str,
-    description: &str,
-    stage: &str,
-    readme_url: &str,
-    tags: Option<&str>,
-    urls: &[String],
-    open_to_contributors: bool,
-    remote: bool,
+/// Overwrite a post's markdown and metadata in place, saving the previous version first
+///
+/// Metadata fields that are unset keep their current values.
+fn update_existing_post(
+    cf: &dyn Backend,
+    file_path: &Path,
+    content: &str,
+    metadata: PostMetadata,
+    existing: &ExistingPostRow,
 ) -> Result<()> {
-    let project_id = uuid::Uuid::new_v4();
+    let content_id = &existing.content_id;
+    let title = metadata.title.unwrap_or_else(|| existing.title.clone());
+    let summary = metadata.summary.or_else(|| existing.summary.clone());
+    let external_url = metadata.external_url.or_else(|| existing.external_url.clone());
+    let status = match metadata.status {
+        Some(status) => {
+            validate_publish_status(&status)?;
+            status
+        }
+        None => existing.status.clone(),
+    };
+    let old_tags = existing.tag_names();
+    let tags = validate_tags(metadata.tags.unwrap_or_else(|| old_tags.clone()))?;
 
-    // Validate and wrap slug for security
-    let validated_slug = ProjectSlug::new(slug)?;
+    status!("Updating", "{}", title);
+    println!("       content_id: {}", content_id);
+    print_tag_diff(&old_tags, &tags);
 
-    status!("Creating", "{}", name);
-    println!("             slug: {}", validated_slug.as_str());
-    println!("       project_id: {}", project_id);
+    let version = next_post_version(cf, content_id)?;
+    let PostSnapshot { statement, previous } = snapshot_post(cf, content_id, version)?;
+    let mut statements = vec![statement];
+    println!("    saved version: {}", version);
 
-    // Validate stage
-    let valid_stages = ["planned", "wip", "active", "maintained", "archived", "shelved"];
-    if !valid_stages.contains(&stage) {
-        anyhow::bail!("Invalid stage. Must be one of: {}", valid_stages.join(", "));
+    // Upload images and the new markdown (without front matter)
+    let markdown_dir = file_path.parent()
+        .context("Could not determine markdown file directory")?;
+    let slug = slug_from_row(cf, content_id)?;
+    let images = extract_relative_images(content);
+    if !images.is_empty() {
+        status!("Found", "{} image(s) to upload", images.len());
+        upload_post_images(cf, markdown_dir, &slug, &images)?;
     }
 
-    // Load wrangler config
-    let config = load_wrangler_config(workspace_root)?;
-    let db_name = config.d1_databases
-        .as_ref()
-        .and_then(|dbs| {
-            dbs.iter()
-                .find(|db| db.binding == "DB")
-                .map(|db| db.database_name.clone())
-        })
-        .context("DB database not found in wrangler.toml")?;
+    status!("Uploading", "markdown content");
+    put_r2_text(cf, &format!("posts/{}.md", content_id), content)?;
+
+    let stats = reading_stats(content);
+    statements.push(
+        Update::new("posts")
+            .set("title", title.as_str())
+            .set("summary", summary)
+            .set("status", status)
+            .set("external_url", external_url)
+            .set("word_count", stats.word_count)
+            .set("read_time_minutes", stats.read_time_minutes)
+            .set_expr("updated_at", "datetime('now')")
+            .where_eq("content_id", content_id.as_str())
+            .build(),
+    );
+    statements.extend(replace_post_tags(content_id, &tags));
+    statements.push(
+        Update::new("posts_fts")
+            .set("body", content)
+            .where_eq("content_id", content_id.as_str())
+            .build(),
+    );
 
-    // 1. Insert project
-    let escaped_name = name.replace('\'', "''");
-    let escaped_description = description.replace('\'', "''");
-    let escaped_readme_url = readme_url.replace('\'', "''");
-    let open_to_contributors_int = if open_to_contributors { 1 } else { 0 };
-
-    let sql = format!(
-        "INSERT INTO projects (id, slug, name, description, stage, open_to_contributors, readme_url) \
-         VALUES ('{}', '{}', '{}', '{}', '{}', {}, '{}');",
-        project_id, validated_slug.as_sql_escaped(), escaped_name, escaped_description, stage, open_to_contributors_int, escaped_readme_url
+    commit_post_update(cf, content_id, previous.as_deref(), &statements)
+        .context("Failed to update post metadata")?;
+
+    status!("Finished", "updating (roll back with `cargo xtask post rollback {}`)", slug);
+    Ok(())
+}
+
+/// Row holding a post's slug
+#[derive(Debug, Deserialize)]
+struct SlugRow {
+    slug: String,
+}
+
+fn slug_from_row(cf: &dyn Backend, content_id: &str) -> Result<String> {
+    let query = Statement::new("SELECT slug FROM posts WHERE content_id = ?").bind(content_id);
+    let rows: Vec<SlugRow> = cf.query(&query)?;
+    rows.into_iter().next().map(|row| row.slug).context("Post not found")
+}
+
+/// A saved version of a post's metadata
+#[derive(Debug, Deserialize)]
+struct PostVersionRow {
+    version: u32,
+    title: String,
+    summary: Option<String>,
+    status: String,
+    published_at: String,
+    external_url: Option<String>,
+    /// JSON array of tag names
+    tags: String,
+}
+
+fn rollback_post(workspace_root: &Path, slug: &str, to: Option<u32>, remote: bool) -> Result<()> {
+    let slug = Slug::parse(slug)?;
+
+    let cf = connect(workspace_root, remote)?;
+    let cf = cf.as_ref();
+
+    let existing = find_post(cf, &slug)?
+        .with_context(|| format!("Post '{}' not found", slug.as_str()))?;
+    let content_id = &existing.content_id;
+
+    let columns = "SELECT version, title, summary, status, published_at, external_url, tags FROM post_versions";
+    let query = match to {
+        Some(version) => Statement::new(format!("{} WHERE post_id = ? AND version = ?", columns))
+            .bind(content_id.as_str())
+            .bind(version),
+        None => Statement::new(format!("{} WHERE post_id = ? ORDER BY version DESC LIMIT 1", columns))
+            .bind(content_id.as_str()),
+    };
+    let target: PostVersionRow = cf.query(&query)?
+        .into_iter()
+        .next()
+        .with_context(|| match to {
+            Some(v) => format!("Version {} of '{}' not found", v, slug.as_str()),
+            None => format!("'{}' has no saved versions", slug.as_str()),
+        })?;
+
+    status!("Rolling back", "{} to version {}", slug.as_str(), target.version);
+
+    let content = cf.r2_get_text(&format!("post-versions/{}/{}.md", content_id, target.version))
+        .context("Saved markdown for this version is missing")?;
+    let saved_tags = serde_json::from_str(&target.tags)
+        .context("Saved tags for this version are corrupt")?;
+    let tags = validate_tags(saved_tags)?;
+
+    // The current state becomes a new version, so the rollback itself can be undone
+    let version = next_post_version(cf, content_id)?;
+    let PostSnapshot { statement, previous } = snapshot_post(cf, content_id, version)?;
+    let mut statements = vec![statement];
+    println!("    saved version: {}", version);
+
+    put_r2_text(cf, &format!("posts/{}.md", content_id), &content)?;
+
+    print_tag_diff(&existing.tag_names(), &tags);
+
+    let stats = reading_stats(&content);
+    statements.push(
+        Update::new("posts")
+            .set("title", target.title)
+            .set("summary", target.summary)
+            .set("status", target.status)
+            .set("published_at", target.published_at)
+            .set("external_url", target.external_url)
+            .set("word_count", stats.word_count)
+            .set("read_time_minutes", stats.read_time_minutes)
+            .set_expr("updated_at", "datetime('now')")
+            .where_eq("content_id", content_id.as_str())
+            .build(),
+    );
+    statements.extend(replace_post_tags(content_id, &tags));
+    statements.push(
+        Update::new("posts_fts")
+            .set("body", content)
+            .where_eq("content_id", content_id.as_str())
+            .build(),
     );
 
-    let mut db_cmd = Command::new("npx");
-    db_cmd.args(["wrangler", "d1", "execute", &db_name]);
+    commit_post_update(cf, content_id, previous.as_deref(), &statements)
+        .context("Failed to restore post metadata")?;
 
-    if remote {
-        db_cmd.arg("--remote");
+    status!("Finished", "rollback");
+    Ok(())
+}
+
+fn schedule_post(workspace_root: &Path, slug: &str, at: &str, remote: bool) -> Result<()> {
+    let validated_slug = Slug::parse(slug)?;
+
+    let publish_at = chrono::DateTime::parse_from_rfc3339(at)
+        .context("Invalid --at value (expected RFC 3339, e.g. 2025-12-01T09:00:00Z)")?
+        .with_timezone(&chrono::Utc);
+
+    if publish_at <= chrono::Utc::now() {
+        anyhow::bail!("Scheduled time must be in the future (got {})", publish_at.to_rfc3339());
     }
 
-    db_cmd.arg("--command")
-        .arg(&sql)
-        .current_dir(workspace_root.join("api"));
+    let cf = connect(workspace_root, remote)?;
+    let cf = cf.as_ref();
 
-    let db_status = db_cmd.status()
-        .context("Failed to insert into D1")?;
+    let existing = find_post(cf, &validated_slug)?
+        .with_context(|| format!("Post '{}' not found", validated_slug.as_str()))?;
 
-    if !db_status.success() {
-        anyhow::bail!("Failed to insert project");
+    status!("Scheduling", "post: {}", validated_slug.as_str());
+    println!("     publish_at: {}", publish_at.to_rfc3339());
+
+    // Stored in SQLite's datetime format so it compares against datetime('now')
+    let schedule = Update::new("posts")
+        .set("status", "scheduled")
+        .set("published_at", publish_at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .set_expr("updated_at", "datetime('now')")
+        .where_eq("content_id", existing.content_id.as_str())
+        .build();
+
+    cf.execute(schedule)
+        .context("Failed to schedule post")?;
+
+    status!("Finished", "scheduling");
+    Ok(())
+}
+
+/// Row returned when listing posts to reindex
+#[derive(Debug, Deserialize)]
+struct PostContentRow {
+    content_id: String,
+    slug: String,
+}
+
+fn reindex_posts(workspace_root: &Path, slug: Option<&str>, remote: bool) -> Result<()> {
+    let location = if remote { "remote" } else { "local" };
+
+    let cf = connect(workspace_root, remote)?;
+
+    let query = match slug {
+        Some(slug) => Statement::new("SELECT content_id, slug FROM posts WHERE slug = ?")
+            .bind(Slug::parse(slug)?.as_str()),
+        None => Statement::new("SELECT content_id, slug FROM posts ORDER BY published_at"),
+    };
+
+    status!("Reindexing", "posts ({})", location);
+
+    let posts: Vec<PostContentRow> = cf.query(&query)?;
+    if posts.is_empty() {
+        anyhow::bail!("No posts found");
     }
 
-    // 2. Handle tags if provided
-    if let Some(tags_str) = tags {
-        let tag_names: Vec<&str> = tags_str.split(',').map(str::trim).collect();
+    let mut statements = Vec::new();
+    let mut skipped = 0;
 
-        for tag_name in tag_names {
-            if tag_name.is_empty() {
+    for post in &posts {
+        let key = format!("posts/{}.md", post.content_id);
+        let content = match cf.r2_get_text(&key) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("  Warning: Skipping {}: {:#}", post.slug, e);
+                skipped += 1;
                 continue;
             }
+        };
 
-            // Validate and wrap tag name for security
-            let validated_tag = TagName::new(tag_name)?;
+        let stats = reading_stats(&content);
+        println!("  {:<40} {:>6} words {:>3} min", post.slug, stats.word_count, stats.read_time_minutes);
 
-            // Insert tag (ignore if exists)
-            let insert_tag_sql = format!(
-                "INSERT OR IGNORE INTO tags (name) VALUES ('{}');",
-                validated_tag.as_sql_escaped()
-            );
+        statements.push(
+            Update::new("posts")
+                .set("word_count", stats.word_count)
+                .set("read_time_minutes", stats.read_time_minutes)
+                .where_eq("content_id", post.content_id.as_str())
+                .build(),
+        );
+        statements.push(
+            Update::new("posts_fts")
+                .set("body", content)
+                .where_eq("content_id", post.content_id.as_str())
+                .build(),
+        );
+    }
+
+    cf.batch(&statements)
+        .context("Failed to write reading stats")?;
+
+    status!("Finished", "reindexing {} post(s)", posts.len() - skipped);
+    if skipped > 0 {
+        println!("        Note: {} post(s) skipped (content missing from R2)", skipped);
+    }
+
+    Ok(())
+}
 
-            let mut tag_cmd = Command::new("npx");
-            tag_cmd.args(["wrangler", "d1", "execute", &db_name]);
+/// Split a leading front matter block into (delimiter, raw front matter, body)
+fn split_front_matter(markdown: &str) -> Option<(&'static str, &str, &str)> {
+    for delimiter in ["---", "+++"] {
+        let Some(rest) = markdown.strip_prefix(delimiter) else { continue };
+        let Some(rest) = rest.strip_prefix('\n').or_else(|| rest.strip_prefix("\r\n")) else { continue };
 
-            if remote {
-                tag_cmd.arg("--remote");
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim_end() == delimiter {
+                return Some((delimiter, &rest[..offset], &rest[offset + line.len()..]));
             }
+            offset += line.len();
+        }
+    }
+    None
+}
 
-            tag_cmd.arg("--command")
-                .arg(&insert_tag_sql)
-                .current_dir(workspace_root.join("api"));
+/// Connect to the D1 database, R2 bucket and KV namespaces in `api/wrangler.toml`
+fn connect(workspace_root: &Path, remote: bool) -> Result<Box<dyn Backend>> {
+    let config = load_wrangler_config(workspace_root)?;
+    cloudflare::connect(workspace_root, &config, remote)
+}
 
-            tag_cmd.status()
-                .context("Failed to insert tag")?;
+/// Upload text to an R2 object, overwriting it
+fn put_r2_text(cf: &dyn Backend, key: &str, contents: &str) -> Result<()> {
+    cf.r2_put(key, contents.as_bytes(), content_type_for(key))
+}
 
-            // Link tag to project
-            let link_tag_sql = format!(
-                "INSERT INTO project_tags (project_id, tag_id) \
-                 SELECT '{}', id FROM tags WHERE name = '{}';",
-                project_id, validated_tag.as_sql_escaped()
-            );
+/// Write contents to a uniquely named temporary file for wrangler `--file` arguments
+/// The caller is responsible for removing it
+fn write_temp_file(contents: impl AsRef<[u8]>, extension: &str) -> Result<PathBuf> {
+    let temp_dir = dirs::cache_dir()
+        .or_else(|| std::env::temp_dir().into())
+        .context("Failed to get temp directory")?;
+    let path = temp_dir.join(format!("xtask-{}.{}", uuid::Uuid::new_v4(), extension));
+    std::fs::write(&path, contents)
+        .context("Failed to write temporary file")?;
+    Ok(path)
+}
 
-            let mut link_cmd = Command::new("npx");
-            link_cmd.args(["wrangler", "d1", "execute", &db_name]);
+/// Statements that move a post or project to `new_slug` and record `old_slug` as an alias
+///
+/// An alias equal to the new slug is dropped, so renaming back and forth works.
+fn rename_slug(
+    kind: &'static str,
+    table: &'static str,
+    id_column: &'static str,
+    id: &str,
+    old_slug: &Slug,
+    new_slug: &Slug,
+) -> Vec<Statement> {
+    vec![
+        Delete::new("slug_aliases")
+            .where_eq("kind", kind)
+            .where_eq("old_slug", new_slug.as_str())
+            .build(),
+        Insert::new("slug_aliases")
+            .or_replace()
+            .value("kind", kind)
+            .value("old_slug", old_slug.as_str())
+            .value("target_id", id)
+            .build(),
+        Update::new(table)
+            .set("slug", new_slug.as_str())
+            .where_eq(id_column, id)
+            .build(),
+    ]
+}
 
-            if remote {
-                link_cmd.arg("--remote");
-            }
+fn rename_post(workspace_root: &Path, slug: &str, new_slug: &str, remote: bool) -> Result<()> {
+    let slug = Slug::parse(slug)?;
+    let new_slug = Slug::parse(new_slug)?;
 
-            link_cmd.arg("--command")
-                .arg(&link_tag_sql)
-                .current_dir(workspace_root.join("api"));
+    let cf = connect(workspace_root, remote)?;
+    let cf = cf.as_ref();
 
-            link_cmd.status()
-                .context("Failed to link tag to project")?;
-        }
+    let existing = find_post(cf, &slug)?
+        .with_context(|| format!("Post '{}' not found", slug.as_str()))?;
+    if find_post(cf, &new_slug)?.is_some() {
+        anyhow::bail!("A post with slug '{}' already exists", new_slug.as_str());
     }
 
-    // 3. Handle URLs
-    for url_pair in urls {
-        let parts: Vec<&str> = url_pair.splitn(2, ':').collect();
-        if parts.len() != 2 {
-            eprintln!("Warning: Skipping invalid URL format '{}' (expected 'label:url')", url_pair);
+    status!("Renaming", "post {} to {}", slug.as_str(), new_slug.as_str());
+
+    // Images are stored under the slug, so copy them to the new prefix. The
+    // old copies stay in place for feeds and caches that still reference them.
+    // Images the markdown names but that were never uploaded are skipped.
+    let content = cf.r2_get_text(&format!("posts/{}.md", existing.content_id))?;
+    for image in extract_relative_images(&content) {
+        let clean_path = image.strip_prefix("./").unwrap_or(&image);
+        let from = format!("posts/{}/{}", slug.as_str(), clean_path);
+        let Some(bytes) = cf.r2_get(&from)? else {
+            eprintln!("  Warning: Image not found in R2: {}", from);
             continue;
+        };
+
+        status!("Copying", "image: {}", clean_path);
+        let to = format!("posts/{}/{}", new_slug.as_str(), clean_path);
+        cf.r2_put(&to, &bytes, content_type_for(&to))
+            .with_context(|| format!("Failed to copy {} to {}", from, to))?;
+    }
+
+    cf.batch(&rename_slug("post", "posts", "content_id", &existing.content_id, &slug, &new_slug))
+        .context("Failed to rename post")?;
+
+    status!("Finished", "renaming");
+    println!("              url: /posts/{}", new_slug.as_str());
+    println!("                   /posts/{} now redirects here", slug.as_str());
+    Ok(())
+}
+
+/// Delete a post from D1 together with every R2 object it owns
+///
+/// R2 objects are downloaded to a backup folder first. If an R2 delete fails the
+/// objects removed so far are restored and D1 is left alone; if the D1 delete
+/// fails every object is restored. Images are everything under `posts/{slug}/`
+/// for the current slug and every old slug, so a backend that can't list the
+/// bucket (wrangler) refuses to delete rather than leave images behind.
+fn delete_post(workspace_root: &Path, slug: &str, dry_run: bool, remote: bool) -> Result<()> {
+    let slug = Slug::parse(slug)?;
+
+    let cf = connect(workspace_root, remote)?;
+    let cf = cf.as_ref();
+
+    // 1. Find the post and everything stored under it
+    let existing = find_post(cf, &slug)?
+        .with_context(|| format!("Post '{}' not found", slug.as_str()))?;
+    let content_id = &existing.content_id;
+
+    status!("Deleting", "post: {}", slug.as_str());
+    println!("       content_id: {}", content_id);
+
+    let versions: Vec<VersionRow> = cf.query(
+        &Statement::new("SELECT version FROM post_versions WHERE post_id = ?").bind(content_id.as_str()),
+    )?;
+    let old_slugs: Vec<SlugRow> = cf.query(
+        &Statement::new("SELECT old_slug as slug FROM slug_aliases WHERE kind = 'post' AND target_id = ?")
+            .bind(content_id.as_str()),
+    )?;
+
+    let mut keys = vec![format!("posts/{}.md", content_id)];
+    keys.extend(versions.iter().map(|v| format!("post-versions/{}/{}.md", content_id, v.version)));
+
+    // Every image uploaded under the current or an old slug, referenced or not
+    let slugs = std::iter::once(slug.as_str().to_string()).chain(old_slugs.into_iter().map(|row| row.slug));
+    for post_slug in slugs {
+        let prefix = format!("posts/{}/", post_slug);
+        let Some(images) = cf.r2_list(&prefix)? else {
+            anyhow::bail!(
+                "{} cannot list R2, so images under {} would be left behind; \
+                 set CLOUDFLARE_API_TOKEN and CLOUDFLARE_ACCOUNT_ID to delete all images",
+                cf.describe(), prefix
+            );
+        };
+        keys.extend(images);
+    }
+
+    // 2. Back up everything before deleting anything
+    let backup_dir = dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(format!("xtask-delete-{}", content_id));
+    std::fs::create_dir_all(&backup_dir)
+        .context("Failed to create backup directory")?;
+
+    status!("Backing up", "R2 objects to {}", backup_dir.display());
+
+    let mut backups: Vec<(String, PathBuf)> = Vec::new();
+    for key in keys {
+        if let Some(file) = backup_r2_object(cf, &key, &backup_dir, backups.len())? {
+            backups.push((key, file));
+        }
+    }
+
+    for (key, _) in &backups {
+        println!("               r2: {}", key);
+    }
+    println!("               d1: posts row, tags, versions, aliases and search index");
+
+    if dry_run {
+        let _ = std::fs::remove_dir_all(&backup_dir);
+        status!("Finished", "dry run ({} R2 object(s), nothing deleted)", backups.len());
+        return Ok(());
+    }
+
+    // 3. Delete from R2, restoring what was deleted if any delete fails
+    for (index, (key, _)) in backups.iter().enumerate() {
+        if let Err(e) = cf.r2_delete(key) {
+            restore_r2_objects(cf, &backups[..index]);
+            return Err(e).context("R2 deletion failed; restored deleted objects and left D1 untouched");
         }
+    }
 
-        let label = parts[0].trim();
-        let url = parts[1].trim();
+    // 4. Delete from D1 (cascades to tags and versions; triggers clear search and aliases)
+    let delete = Delete::new("posts").where_eq("content_id", content_id.as_str()).build();
+    if let Err(e) = cf.execute(delete) {
+        restore_r2_objects(cf, &backups);
+        return Err(e).context("D1 deletion failed; restored R2 objects");
+    }
 
-        let escaped_label = label.replace('\'', "''");
-        let escaped_url = url.replace('\'', "''");
+    let _ = std::fs::remove_dir_all(&backup_dir);
+    status!("Finished", "deletion ({} R2 object(s) removed)", backups.len());
 
-        let url_sql = format!(
-            "INSERT INTO project_urls (project_id, label, url) VALUES ('{}', '{}', '{}');",
-            project_id, escaped_label, escaped_url
-        );
+    Ok(())
+}
 
-        let mut url_cmd = Command::new("npx");
-        url_cmd.args(["wrangler", "d1", "execute", &db_name]);
+/// Download an R2 object into `backup_dir`, returning `None` if it does not exist
+fn backup_r2_object(cf: &dyn Backend, key: &str, backup_dir: &Path, index: usize) -> Result<Option<PathBuf>> {
+    let Some(bytes) = cf.r2_get(key)? else { return Ok(None) };
 
-        if remote {
-            url_cmd.arg("--remote");
+    let file = backup_dir.join(index.to_string());
+    std::fs::write(&file, bytes)
+        .with_context(|| format!("Failed to back up {}", key))?;
+    Ok(Some(file))
+}
+
+/// Re-upload backed up objects, reporting (not failing on) objects that can't be restored
+fn restore_r2_objects(cf: &dyn Backend, backups: &[(String, PathBuf)]) {
+    for (key, file) in backups {
+        status!("Restoring", "{}", key);
+        let restored = std::fs::read(file)
+            .context("Failed to read backup")
+            .and_then(|bytes| cf.r2_put(key, &bytes, content_type_for(key)));
+        if let Err(e) = restored {
+            eprintln!("  Error: Could not restore {} from {}: {:#}", key, file.display(), e);
         }
+    }
+}
+
+/// Fields of a new project, as given to `projects create`
+#[derive(Debug, Clone)]
+struct NewProject {
+    slug: String,
+    name: String,
+    description: String,
+    stage: String,
+    readme_url: String,
+    /// Comma-separated
+    tags: Option<String>,
+    /// `label:url` pairs
+    urls: Vec<String>,
+    open_to_contributors: bool,
+}
+
+fn create_portfolio_project(workspace_root: &Path, project: &NewProject, remote: bool) -> Result<()> {
+    let NewProject { slug, name, description, stage, readme_url, tags, urls, open_to_contributors } = project;
+    let project_id = uuid::Uuid::new_v4();
+
+    // Validate and wrap slug for security
+    let validated_slug = Slug::parse(slug)?;
+
+    status!("Creating", "{}", name);
+    println!("             slug: {}", validated_slug.as_str());
+    println!("       project_id: {}", project_id);
+
+    // Validate stage
+    let stage = ProjectStage::parse(stage)?;
+
+    // Validate and wrap tag names for security
+    let tags = validate_tags(
+        tags.as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(String::from)
+            .collect(),
+    )?;
+
+    // Same `label:url` rules as `projects update --add-url`
+    let urls = urls.iter().map(|pair| parse_project_url(pair)).collect::<Result<Vec<_>>>()?;
 
-        url_cmd.arg("--command")
-            .arg(&url_sql)
-            .current_dir(workspace_root.join("api"));
+    let cf = connect(workspace_root, remote)?;
+    let project_id = project_id.to_string();
+
+    // 1. Insert project
+    let mut statements = vec![
+        Insert::new("projects")
+            .value("id", project_id.as_str())
+            .value("slug", validated_slug.as_str())
+            .value("name", name.as_str())
+            .value("description", description.as_str())
+            .value("stage", stage.as_str())
+            .value("open_to_contributors", i64::from(*open_to_contributors))
+            .value("readme_url", readme_url.as_str())
+            .build(),
+    ];
 
-        url_cmd.status()
-            .context("Failed to insert project URL")?;
+    // 2. Handle tags if provided
+    statements.extend(link_tags("project_tags", "project_id", &project_id, &tags));
+
+    // 3. Handle URLs
+    for url in &urls {
+        statements.push(
+            Insert::new("project_urls")
+                .value("project_id", project_id.as_str())
+                .value("label", url.label.as_str())
+                .value("url", url.url.as_str())
+                .build(),
+        );
     }
 
+    // Everything is written in one transaction, so a failure leaves no partial project
+    cf.batch(&statements)
+        .context("Failed to insert project")?;
+
     status!("Finished", "creating project");
     println!("              url: /projects/{}", validated_slug.as_str());
     Ok(())
 }
 
-fn list_portfolio_projects(workspace_root: &Path, remote: bool) -> Result<()> {
-    let mode = if remote { "--remote" } else { "--local" };
-    let location = if remote { "remote" } else { "local" };
+/// Row holding a project's id
+#[derive(Debug, Deserialize)]
+struct ProjectIdRow {
+    id: String,
+}
 
-    status!("Listing", "portfolio projects ({})", location);
+/// Row holding a project's id and current stage
+#[derive(Debug, Deserialize)]
+struct ProjectStageRow {
+    id: String,
+    stage: ProjectStage,
+}
 
-    // Load wrangler config
-    let config = load_wrangler_config(workspace_root)?;
-    let db_name = config.d1_databases
-        .and_then(|dbs| {
-            dbs.iter()
-                .find(|db| db.binding == "DB")
-                .map(|db| db.database_name.clone())
-        })
-        .context("DB database not found in wrangler.toml")?;
+/// Parse a `label:url` pair as given to `--url` and `--add-url`
+fn parse_project_url(pair: &str) -> Result<ProjectUrl> {
+    let (label, url) = pair.split_once(':')
+        .with_context(|| format!("Invalid URL format '{}' (expected 'label:url')", pair))?;
+    let (label, url) = (label.trim(), url.trim());
+    if label.is_empty() || !(url.starts_with("https://") || url.starts_with("http://")) {
+        anyhow::bail!("Invalid URL format '{}' (expected 'label:url' with an http(s) URL)", pair);
+    }
+    Ok(ProjectUrl { label: label.to_string(), url: url.to_string() })
+}
+
+fn update_portfolio_project(workspace_root: &Path, slug: &str, mut patch: ProjectPatch, remote: bool) -> Result<()> {
+    let slug = Slug::parse(slug)?;
+    if patch.is_empty() {
+        anyhow::bail!("Nothing to update (see `cargo xtask projects update --help`)");
+    }
+    // Same rules as `PATCH /v1/projects/:slug`
+    patch.validate()?;
+
+    // Validate and wrap tag names for security
+    let add_tags = validate_tags(patch.add_tags.clone())?;
+    let remove_tags = validate_tags(patch.remove_tags.clone())?;
+
+    let cf = connect(workspace_root, remote)?;
+    let cf = cf.as_ref();
+
+    let query = Statement::new("SELECT id, stage FROM projects WHERE slug = ?").bind(slug.as_str());
+    let rows: Vec<ProjectStageRow> = cf.query(&query)?;
+    let project = rows.into_iter()
+        .next()
+        .with_context(|| format!("Project '{}' not found", slug.as_str()))?;
+
+    status!("Updating", "project {}", slug.as_str());
+
+    if let Some(stage) = patch.stage {
+        project.stage.transition_to(stage)?;
+        println!("            stage: {} -> {}", project.stage, stage);
+    }
+
+    // 1. Fields (every update bumps updated_at)
+    let mut update = Update::new("projects");
+    if let Some(name) = &patch.name {
+        update = update.set("name", name.as_str());
+    }
+    if let Some(description) = &patch.description {
+        update = update.set("description", description.as_str());
+    }
+    if let Some(stage) = patch.stage {
+        update = update.set("stage", stage.as_str());
+    }
+    if let Some(readme_url) = &patch.readme_url {
+        update = update.set("readme_url", readme_url.as_str());
+    }
+    if let Some(open) = patch.open_to_contributors {
+        update = update.set("open_to_contributors", i64::from(open));
+    }
+    let mut statements = vec![
+        update.set_expr("updated_at", "datetime('now')")
+            .where_eq("id", project.id.as_str())
+            .build(),
+    ];
+
+    // 2. Tags
+    if !remove_tags.is_empty() {
+        let tags_json = json_list(remove_tags.iter().map(Tag::as_str));
+        statements.push(
+            Statement::new(format!("DELETE FROM project_tags WHERE project_id = ? AND tag_id IN ({})", tags::TAG_IDS_IN_JSON))
+                .bind(project.id.as_str())
+                .bind(tags_json.clone())
+                .bind(tags_json),
+        );
+    }
+    statements.extend(link_tags("project_tags", "project_id", &project.id, &add_tags));
+
+    // 3. URLs, matched by label
+    if patch.replaced_url_labels().next().is_some() {
+        statements.push(
+            Statement::new("DELETE FROM project_urls WHERE project_id = ? AND label IN (SELECT value FROM json_each(?))")
+                .bind(project.id.as_str())
+                .bind(json_list(patch.replaced_url_labels())),
+        );
+    }
+    for url in &patch.add_urls {
+        statements.push(
+            Insert::new("project_urls")
+                .value("project_id", project.id.as_str())
+                .value("label", url.label.as_str())
+                .value("url", url.url.as_str())
+                .build(),
+        );
+    }
 
-    // Query projects with tags
-    let query = "SELECT p.slug, p.name, p.stage, p.updated_at, \
-                 GROUP_CONCAT(t.name, ', ') as tags \
-                 FROM projects p \
-                 LEFT JOIN project_tags pt ON p.id = pt.project_id \
-                 LEFT JOIN tags t ON pt.tag_id = t.id \
-                 GROUP BY p.id \
-                 ORDER BY p.updated_at DESC;";
-
-    let output = Command::new("npx")
-        .args(["wrangler", "d1", "execute", &db_name, mode])
-        .arg("--command")
-        .arg(query)
-        .current_dir(workspace_root.join("api"))
-        .output()
-        .context("Failed to query projects")?;
-
-    println!("{}", String::from_utf8_lossy(&output.stdout));
+    cf.batch(&statements)
+        .context("Failed to update project")?;
 
+    status!("Finished", "updating project");
+    println!("              url: /projects/{}", slug.as_str());
     Ok(())
 }
 
-fn delete_portfolio_project(workspace_root: &Path, slug: &str, remote: bool) -> Result<()> {
-    let mode = if remote { "--remote" } else { "--local" };
+fn rename_portfolio_project(workspace_root: &Path, slug: &str, new_slug: &str, remote: bool) -> Result<()> {
+    let slug = Slug::parse(slug)?;
+    let new_slug = Slug::parse(new_slug)?;
 
+    let cf = connect(workspace_root, remote)?;
+    let cf = cf.as_ref();
+
+    let find_project = |slug: &Slug| -> Result<Option<String>> {
+        let query = Statement::new("SELECT id FROM projects WHERE slug = ?").bind(slug.as_str());
+        let rows: Vec<ProjectIdRow> = cf.query(&query)?;
+        Ok(rows.into_iter().next().map(|row| row.id))
+    };
+
+    let project_id = find_project(&slug)?
+        .with_context(|| format!("Project '{}' not found", slug.as_str()))?;
+    if find_project(&new_slug)?.is_some() {
+        anyhow::bail!("A project with slug '{}' already exists", new_slug.as_str());
+    }
+
+    status!("Renaming", "project {} to {}", slug.as_str(), new_slug.as_str());
+
+    cf.batch(&rename_slug("project", "projects", "id", &project_id, &slug, &new_slug))
+        .context("Failed to rename project")?;
+
+    status!("Finished", "renaming");
+    println!("              url: /projects/{}", new_slug.as_str());
+    println!("                   /projects/{} now redirects here", slug.as_str());
+    Ok(())
+}
+
+fn delete_portfolio_project(workspace_root: &Path, slug: &str, remote: bool) -> Result<()> {
     // Validate and wrap slug for security
-    let validated_slug = ProjectSlug::new(slug)?;
+    let validated_slug = Slug::parse(slug)?;
 
     status!("Deleting", "portfolio project: {}", validated_slug.as_str());
 
-    // Load wrangler config
-    let config = load_wrangler_config(workspace_root)?;
-    let db_name = config.d1_databases
-        .and_then(|dbs| {
-            dbs.iter()
-                .find(|db| db.binding == "DB")
-                .map(|db| db.database_name.clone())
-        })
-        .context("DB database not found in wrangler.toml")?;
+    let cf = connect(workspace_root, remote)?;
 
     // Delete from D1 (cascades to project_tags and project_urls due to foreign keys)
-    let delete_status = Command::new("npx")
-        .args(["wrangler", "d1", "execute", &db_name, mode])
-        .arg("--command")
-        .arg(format!("DELETE FROM projects WHERE slug = '{}';", validated_slug.as_sql_escaped()))
-        .current_dir(workspace_root.join("api"))
-        .status()
-        .context("Failed to delete from D1")?;
-
-    if !delete_status.success() {
-        anyhow::bail!("Failed to delete project from D1");
-    }
+    let delete = Statement::new("DELETE FROM projects WHERE slug = ?").bind(validated_slug.as_str());
+    cf.execute(delete)
+        .context("Failed to delete project from D1")?;
 
     status!("Finished", "deletion");
 
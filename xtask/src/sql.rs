@@ -0,0 +1,201 @@
+// Builders for parameterized D1 statements
+//
+// Table and column names are `&'static str`, so only literals from this crate
+// end up in SQL text; every value is bound as a `?` parameter.
+
+use serde_json::Value;
+
+use crate::cloudflare::Statement;
+
+/// `INSERT INTO table (...) VALUES (...)`
+#[derive(Debug)]
+pub struct Insert {
+    table: &'static str,
+    conflict: &'static str,
+    columns: Vec<&'static str>,
+    exprs: Vec<&'static str>,
+    values: Vec<Value>,
+}
+
+impl Insert {
+    pub fn new(table: &'static str) -> Self {
+        Self { table, conflict: "", columns: Vec::new(), exprs: Vec::new(), values: Vec::new() }
+    }
+
+    /// Skip the row if it violates a unique constraint
+    pub fn or_ignore(mut self) -> Self {
+        self.conflict = " OR IGNORE";
+        self
+    }
+
+    /// Replace the existing row on a unique constraint violation
+    pub fn or_replace(mut self) -> Self {
+        self.conflict = " OR REPLACE";
+        self
+    }
+
+    pub fn value(mut self, column: &'static str, value: impl Into<Value>) -> Self {
+        self.columns.push(column);
+        self.exprs.push("?");
+        self.values.push(value.into());
+        self
+    }
+
+    /// Insert a SQL expression, such as `datetime('now')`
+    pub fn value_expr(mut self, column: &'static str, expr: &'static str) -> Self {
+        self.columns.push(column);
+        self.exprs.push(expr);
+        self
+    }
+
+    pub fn build(self) -> Statement {
+        let sql = format!(
+            "INSERT{} INTO {} ({}) VALUES ({})",
+            self.conflict,
+            self.table,
+            self.columns.join(", "),
+            self.exprs.join(", ")
+        );
+        self.values.into_iter().fold(Statement::new(sql), Statement::bind)
+    }
+}
+
+/// `UPDATE table SET ... WHERE ...`
+#[derive(Debug)]
+pub struct Update {
+    table: &'static str,
+    assignments: Vec<String>,
+    values: Vec<Value>,
+    filter: Filter,
+}
+
+impl Update {
+    pub fn new(table: &'static str) -> Self {
+        Self { table, assignments: Vec::new(), values: Vec::new(), filter: Filter::default() }
+    }
+
+    pub fn set(mut self, column: &'static str, value: impl Into<Value>) -> Self {
+        self.assignments.push(format!("{} = ?", column));
+        self.values.push(value.into());
+        self
+    }
+
+    /// Set a column to a SQL expression, such as `datetime('now')`
+    pub fn set_expr(mut self, column: &'static str, expr: &'static str) -> Self {
+        self.assignments.push(format!("{} = {}", column, expr));
+        self
+    }
+
+    pub fn where_eq(mut self, column: &'static str, value: impl Into<Value>) -> Self {
+        self.filter.eq(column, value);
+        self
+    }
+
+    pub fn build(self) -> Statement {
+        let sql = format!("UPDATE {} SET {}{}", self.table, self.assignments.join(", "), self.filter.sql());
+        self.values.into_iter()
+            .chain(self.filter.values)
+            .fold(Statement::new(sql), Statement::bind)
+    }
+}
+
+/// `DELETE FROM table WHERE ...`
+#[derive(Debug)]
+pub struct Delete {
+    table: &'static str,
+    filter: Filter,
+}
+
+impl Delete {
+    pub fn new(table: &'static str) -> Self {
+        Self { table, filter: Filter::default() }
+    }
+
+    pub fn where_eq(mut self, column: &'static str, value: impl Into<Value>) -> Self {
+        self.filter.eq(column, value);
+        self
+    }
+
+    pub fn build(self) -> Statement {
+        let sql = format!("DELETE FROM {}{}", self.table, self.filter.sql());
+        self.filter.values.into_iter().fold(Statement::new(sql), Statement::bind)
+    }
+}
+
+/// `column = ?` conditions joined with `AND`
+#[derive(Debug, Default)]
+struct Filter {
+    columns: Vec<&'static str>,
+    values: Vec<Value>,
+}
+
+impl Filter {
+    fn eq(&mut self, column: &'static str, value: impl Into<Value>) {
+        self.columns.push(column);
+        self.values.push(value.into());
+    }
+
+    fn sql(&self) -> String {
+        if self.columns.is_empty() {
+            return String::new();
+        }
+        let conditions: Vec<String> = self.columns.iter().map(|c| format!("{} = ?", c)).collect();
+        format!(" WHERE {}", conditions.join(" AND "))
+    }
+}
+
+/// A list bound as one parameter, for use with `IN (SELECT value FROM json_each(?))`
+pub fn json_list<'a>(items: impl IntoIterator<Item = &'a str>) -> Value {
+    Value::String(Value::from(items.into_iter().collect::<Vec<_>>()).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let statement = Insert::new("tags").or_ignore().value("name", "rust").build();
+        assert_eq!(statement.sql, "INSERT OR IGNORE INTO tags (name) VALUES (?)");
+        assert_eq!(statement.params, vec![Value::from("rust")]);
+
+        let statement = Insert::new("posts")
+            .value("slug", "it's")
+            .value_expr("published_at", "datetime('now')")
+            .value("external_url", None::<String>)
+            .value("word_count", 10)
+            .build();
+        assert_eq!(
+            statement.sql,
+            "INSERT INTO posts (slug, published_at, external_url, word_count) VALUES (?, datetime('now'), ?, ?)"
+        );
+        assert_eq!(statement.params, vec![Value::from("it's"), Value::Null, Value::from(10)]);
+    }
+
+    #[test]
+    fn test_update_binds_assignments_before_filter() {
+        let statement = Update::new("posts")
+            .set("title", "New")
+            .set_expr("updated_at", "datetime('now')")
+            .set("status", "draft")
+            .where_eq("content_id", "abc")
+            .build();
+        assert_eq!(
+            statement.sql,
+            "UPDATE posts SET title = ?, updated_at = datetime('now'), status = ? WHERE content_id = ?"
+        );
+        assert_eq!(statement.params, vec![Value::from("New"), Value::from("draft"), Value::from("abc")]);
+    }
+
+    #[test]
+    fn test_delete() {
+        let statement = Delete::new("post_tags").where_eq("post_id", "abc").where_eq("tag_id", 3).build();
+        assert_eq!(statement.sql, "DELETE FROM post_tags WHERE post_id = ? AND tag_id = ?");
+        assert_eq!(statement.params, vec![Value::from("abc"), Value::from(3)]);
+    }
+
+    #[test]
+    fn test_json_list() {
+        assert_eq!(json_list(["rust", "it's \"x\""]), Value::from(r#"["rust","it's \"x\""]"#));
+    }
+}
@@ -0,0 +1,320 @@
+// `tags set`, `tags merge` and `tags rename`: the tags shared by posts and projects
+//
+// Merged and renamed names stay behind in `tag_aliases`, so links and filters
+// that still use an old name reach the tag it now points at.
+
+use anyhow::{Context, Result};
+use models::Tag;
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::cloudflare::{Backend, Statement};
+use crate::sql::{Delete, Insert, Update};
+use crate::{connect, status};
+
+/// Id of the tag named by a `?`, following aliases; binds the name twice
+pub const TAG_ID_BY_NAME: &str =
+    "COALESCE((SELECT tag_id FROM tag_aliases WHERE alias = ?), (SELECT id FROM tags WHERE name = ?))";
+
+/// Ids of the tags named in a JSON list, following aliases; binds the list twice
+pub const TAG_IDS_IN_JSON: &str =
+    "SELECT id FROM tags WHERE name IN (SELECT value FROM json_each(?)) \
+     UNION SELECT tag_id FROM tag_aliases WHERE alias IN (SELECT value FROM json_each(?))";
+
+#[derive(Debug, Deserialize)]
+struct TagRow {
+    id: i64,
+    name: String,
+}
+
+/// Metadata flags; `None` leaves a field unchanged, an empty string clears it
+#[derive(Debug, Default)]
+pub struct TagMetadata {
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub color: Option<String>,
+}
+
+pub fn set(workspace_root: &Path, name: &str, metadata: TagMetadata, remote: bool) -> Result<()> {
+    let tag = Tag::normalize(name)?;
+    if metadata.display_name.is_none() && metadata.description.is_none() && metadata.color.is_none() {
+        anyhow::bail!("Nothing to set (use --display-name, --description or --color)");
+    }
+    if let Some(color) = metadata.color.as_deref().filter(|c| !c.is_empty()) {
+        validate_color(color)?;
+    }
+
+    status!("Updating", "tag {}", tag.as_str());
+
+    let mut update = Update::new("tags");
+    for (column, value) in [
+        ("display_name", &metadata.display_name),
+        ("description", &metadata.description),
+        ("color", &metadata.color),
+    ] {
+        if let Some(value) = value.as_deref().map(str::trim) {
+            update = update.set(column, (!value.is_empty()).then(|| value.to_string()));
+        }
+    }
+
+    // Metadata may be set before anything uses the tag
+    let statements: Vec<Statement> = vec![
+        Insert::new("tags").or_ignore().value("name", tag.as_str()).build(),
+        update.where_eq("name", tag.as_str()).build(),
+    ];
+
+    let cf = connect(workspace_root, remote)?;
+    cf.batch(&statements)
+        .context("Failed to update tag")?;
+
+    status!("Finished", "updating tag");
+    Ok(())
+}
+
+/// Move the posts, projects, aliases and missing metadata of `from` to `into`, then delete `from`
+///
+/// `from` is looked up by its stored name first, so legacy names that do not
+/// normalize (such as "c++") can still be merged away. Its normalized form
+/// becomes an alias of `into`. If `into` is itself an alias, the tag it points
+/// at is used.
+pub fn merge(workspace_root: &Path, from: &str, into: &str, remote: bool) -> Result<()> {
+    let into = Tag::normalize(into)?;
+
+    let cf = connect(workspace_root, remote)?;
+    let cf = cf.as_ref();
+
+    let Some(source) = find_stored_tag(cf, from)? else {
+        let alias = Tag::normalize(from).ok();
+        match alias.map(|alias| find_alias_target(cf, alias.as_str())).transpose()?.flatten() {
+            Some(target) => anyhow::bail!("'{}' is already an alias of '{}'", from.trim(), target.name),
+            None => anyhow::bail!("Tag '{}' not found", from.trim()),
+        }
+    };
+    let into = resolve_alias(cf, into.as_str())?;
+    if source.name == into {
+        anyhow::bail!("Cannot merge '{}' into itself", source.name);
+    }
+
+    status!("Merging", "tag {} into {}", source.name, into);
+
+    cf.batch(&merge_statements(source.id, &source.name, &into))
+        .context("Failed to merge tag")?;
+
+    status!("Finished", "merging tag");
+    Ok(())
+}
+
+/// Rename a tag in place, keeping its links, aliases and metadata
+///
+/// The tag is looked up by its stored name first, like `merge`. The old name's
+/// normalized form becomes an alias. Renaming onto an existing tag is a merge.
+pub fn rename(workspace_root: &Path, name: &str, new_name: &str, remote: bool) -> Result<()> {
+    let new_name = Tag::normalize(new_name)?;
+
+    let cf = connect(workspace_root, remote)?;
+    let cf = cf.as_ref();
+
+    let Some(tag) = find_stored_tag(cf, name)? else {
+        anyhow::bail!("Tag '{}' not found", name.trim());
+    };
+    if tag.name == new_name.as_str() {
+        anyhow::bail!("'{}' is already the tag's name", tag.name);
+    }
+    if find_tag(cf, new_name.as_str())?.is_some() {
+        anyhow::bail!(
+            "Tag '{}' already exists (use `tags merge '{}' {}`)",
+            new_name.as_str(), tag.name, new_name.as_str()
+        );
+    }
+    if let Some(target) = find_alias_target(cf, new_name.as_str())?.filter(|target| target.id != tag.id) {
+        anyhow::bail!("'{}' is an alias of '{}'", new_name.as_str(), target.name);
+    }
+
+    status!("Renaming", "tag '{}' to {}", tag.name, new_name.as_str());
+
+    cf.batch(&rename_statements(tag.id, &tag.name, new_name.as_str()))
+        .context("Failed to rename tag")?;
+
+    status!("Finished", "renaming tag");
+    Ok(())
+}
+
+/// Statements for `merge`, in order; also used by `doctor --fix` for non-canonical tags
+///
+/// `from_name` is the stored name; the alias left behind is its normalized form.
+pub fn merge_statements(from_id: i64, from_name: &str, into: &str) -> Vec<Statement> {
+    let mut statements = vec![Insert::new("tags").or_ignore().value("name", into).build()];
+
+    for (table, owner_column) in [("post_tags", "post_id"), ("project_tags", "project_id")] {
+        statements.push(
+            Statement::new(format!(
+                "INSERT OR IGNORE INTO {table} ({owner_column}, tag_id) \
+                 SELECT {owner_column}, (SELECT id FROM tags WHERE name = ?) FROM {table} WHERE tag_id = ?"
+            ))
+            .bind(into)
+            .bind(from_id),
+        );
+    }
+
+    statements.push(
+        Statement::new("UPDATE tag_aliases SET tag_id = (SELECT id FROM tags WHERE name = ?) WHERE tag_id = ?")
+            .bind(into)
+            .bind(from_id),
+    );
+    if let Some(alias) = alias_for(from_name, into) {
+        statements.push(
+            Statement::new("INSERT OR REPLACE INTO tag_aliases (alias, tag_id) SELECT ?, id FROM tags WHERE name = ?")
+                .bind(alias)
+                .bind(into),
+        );
+    }
+    statements.extend([
+        // Metadata already set on `into` wins
+        Statement::new(
+            "UPDATE tags SET \
+             display_name = COALESCE(display_name, (SELECT display_name FROM tags WHERE id = ?)), \
+             description = COALESCE(description, (SELECT description FROM tags WHERE id = ?)), \
+             color = COALESCE(color, (SELECT color FROM tags WHERE id = ?)) \
+             WHERE name = ?",
+        )
+        .bind(from_id)
+        .bind(from_id)
+        .bind(from_id)
+        .bind(into),
+        Delete::new("post_tags").where_eq("tag_id", from_id).build(),
+        Delete::new("project_tags").where_eq("tag_id", from_id).build(),
+        Delete::new("tags").where_eq("id", from_id).build(),
+    ]);
+    statements
+}
+
+/// Statements for `rename`; `name` is the stored name
+fn rename_statements(id: i64, name: &str, new_name: &str) -> Vec<Statement> {
+    let mut statements = vec![
+        // The new name may have been an alias of this same tag
+        Delete::new("tag_aliases").where_eq("alias", new_name).build(),
+        Update::new("tags").set("name", new_name).where_eq("id", id).build(),
+    ];
+    if let Some(alias) = alias_for(name, new_name) {
+        statements.push(Insert::new("tag_aliases").or_replace().value("alias", alias).value("tag_id", id).build());
+    }
+    statements
+}
+
+/// Alias to keep for a tag's old stored name, or `None` if nothing could look it up
+///
+/// Lookups always normalize their input, so only the normalized form can match.
+/// Names with no normalized form, or that normalize to the new name, get none.
+fn alias_for(old_name: &str, new_name: &str) -> Option<String> {
+    Tag::normalize(old_name)
+        .ok()
+        .map(|tag| tag.as_str().to_string())
+        .filter(|alias| alias != new_name)
+}
+
+/// A tag by its exact stored name, falling back to the normalized form of `name`
+fn find_stored_tag(cf: &dyn Backend, name: &str) -> Result<Option<TagRow>> {
+    if let Some(tag) = find_tag(cf, name.trim())? {
+        return Ok(Some(tag));
+    }
+    match Tag::normalize(name) {
+        Ok(tag) => find_tag(cf, tag.as_str()),
+        Err(_) => Ok(None),
+    }
+}
+
+fn find_tag(cf: &dyn Backend, name: &str) -> Result<Option<TagRow>> {
+    let rows: Vec<TagRow> = cf.query(&Statement::new("SELECT id, name FROM tags WHERE name = ?").bind(name))?;
+    Ok(rows.into_iter().next())
+}
+
+/// Name of the tag `name` points at if it is an alias, otherwise `name` itself
+pub fn resolve_alias(cf: &dyn Backend, name: &str) -> Result<String> {
+    Ok(find_alias_target(cf, name)?.map_or_else(|| name.to_string(), |target| target.name))
+}
+
+/// The tag `alias` points at, if it is an alias
+fn find_alias_target(cf: &dyn Backend, alias: &str) -> Result<Option<TagRow>> {
+    let rows: Vec<TagRow> = cf.query(
+        &Statement::new(
+            "SELECT t.id, t.name FROM tag_aliases a INNER JOIN tags t ON a.tag_id = t.id WHERE a.alias = ?",
+        )
+        .bind(alias),
+    )?;
+    Ok(rows.into_iter().next())
+}
+
+/// Colors are stored as `#rrggbb`, matching the CHECK constraint on `tags.color`
+fn validate_color(color: &str) -> Result<()> {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        anyhow::bail!("Invalid color '{}' (expected #rrggbb)", color);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn test_validate_color() {
+        assert!(validate_color("#dea584").is_ok());
+        assert!(validate_color("#DEA584").is_ok());
+        assert!(validate_color("dea584").is_err());
+        assert!(validate_color("#dea58").is_err());
+        assert!(validate_color("#gggggg").is_err());
+    }
+
+    #[test]
+    fn test_merge_statements_move_links_before_deleting() {
+        let statements = merge_statements(7, "webdev", "web-dev");
+        let sql: Vec<&str> = statements.iter().map(|s| s.sql.as_str()).collect();
+
+        assert_eq!(sql[0], "INSERT OR IGNORE INTO tags (name) VALUES (?)");
+        assert!(sql[1].starts_with("INSERT OR IGNORE INTO post_tags (post_id, tag_id)"));
+        assert!(sql[2].starts_with("INSERT OR IGNORE INTO project_tags (project_id, tag_id)"));
+        assert_eq!(sql.last(), Some(&"DELETE FROM tags WHERE id = ?"));
+
+        let alias = statements.iter()
+            .find(|s| s.sql.starts_with("INSERT OR REPLACE INTO tag_aliases"))
+            .unwrap();
+        assert_eq!(alias.params, vec![Value::from("webdev"), Value::from("web-dev")]);
+    }
+
+    #[test]
+    fn test_merge_statements_alias_normalized_stored_name() {
+        let alias_params = |statements: Vec<Statement>| {
+            statements.into_iter()
+                .find(|s| s.sql.starts_with("INSERT OR REPLACE INTO tag_aliases"))
+                .map(|s| s.params)
+        };
+
+        // A legacy name with a space is removed by id, and its normalized form
+        // is the alias, since that is what lookups search for
+        let statements = merge_statements(7, "Web Dev", "frontend");
+        assert_eq!(statements.last().unwrap().params, vec![Value::from(7)]);
+        assert_eq!(alias_params(statements), Some(vec![Value::from("web-dev"), Value::from("frontend")]));
+
+        // Merging into its own canonical form needs no alias
+        assert_eq!(alias_params(merge_statements(7, "web dev", "web-dev")), None);
+
+        // Names with no normalized form cannot be looked up, so get no alias
+        assert_eq!(alias_params(merge_statements(7, "c++", "cpp")), None);
+    }
+
+    #[test]
+    fn test_rename_statements_keep_old_name_as_alias() {
+        let statements = rename_statements(7, "webdev", "web-dev");
+        assert_eq!(statements[1].sql, "UPDATE tags SET name = ? WHERE id = ?");
+        assert_eq!(statements[2].sql, "INSERT OR REPLACE INTO tag_aliases (alias, tag_id) VALUES (?, ?)");
+        assert_eq!(statements[2].params, vec![Value::from("webdev"), Value::from(7)]);
+
+        let statements = rename_statements(7, "Web Dev", "webdev");
+        assert_eq!(statements[2].params, vec![Value::from("web-dev"), Value::from(7)]);
+        assert_eq!(rename_statements(7, "c++", "cpp").len(), 2);
+    }
+}
@@ -0,0 +1,249 @@
+// Backend that shells out to `npx wrangler`
+//
+// Used for local development (wrangler's miniflare storage, shared with
+// `wrangler dev`) and as the remote fallback when no API token is configured.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use crate::cloudflare::{Backend, Resources, Statement};
+use crate::write_temp_file;
+
+pub struct WranglerBackend {
+    api_dir: PathBuf,
+    resources: Resources,
+    remote: bool,
+}
+
+/// One statement's result in `wrangler d1 execute --json` output
+#[derive(Debug, Deserialize)]
+struct D1JsonResult {
+    #[serde(default)]
+    results: Vec<Value>,
+}
+
+impl WranglerBackend {
+    pub fn new(workspace_root: &Path, resources: Resources, remote: bool) -> Self {
+        Self {
+            api_dir: workspace_root.join("api"),
+            resources,
+            remote,
+        }
+    }
+
+    fn wrangler(&self) -> Command {
+        let mut cmd = Command::new("npx");
+        cmd.arg("wrangler").current_dir(&self.api_dir);
+        cmd
+    }
+
+    fn d1_execute(&self) -> Command {
+        let mut cmd = self.wrangler();
+        cmd.args(["d1", "execute", &self.resources.database_name]);
+        cmd.arg(if self.remote { "--remote" } else { "--local" });
+        cmd
+    }
+
+    fn r2_object(&self, action: &str, key: &str) -> Command {
+        let mut cmd = self.wrangler();
+        cmd.args(["r2", "object", action, &format!("{}/{}", self.resources.bucket_name, key)]);
+        if self.remote {
+            cmd.arg("--remote");
+        }
+        cmd
+    }
+}
+
+/// Fail with wrangler's stderr if the command did not succeed
+fn check(output: Output, action: &str) -> Result<Output> {
+    if !output.status.success() {
+        anyhow::bail!("{}: {}", action, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(output)
+}
+
+impl Backend for WranglerBackend {
+    fn query_rows(&self, statement: &Statement) -> Result<Vec<Value>> {
+        let output = self.d1_execute()
+            .arg("--json")
+            .arg("--command")
+            .arg(inline_params(statement)?)
+            .output()
+            .context("Failed to run wrangler d1 execute")?;
+        let output = check(output, "D1 query failed")?;
+
+        let results: Vec<D1JsonResult> = serde_json::from_slice(&output.stdout)
+            .context("Failed to parse wrangler JSON output")?;
+        Ok(results.into_iter().flat_map(|r| r.results).collect())
+    }
+
+    fn batch(&self, statements: &[Statement]) -> Result<()> {
+        if statements.is_empty() {
+            return Ok(());
+        }
+
+        // Statements can exceed command-line length limits, so they go through a file
+        let mut script = String::new();
+        for statement in statements {
+            script.push_str(inline_params(statement)?.trim_end().trim_end_matches(';'));
+            script.push_str(";\n");
+        }
+
+        let file = write_temp_file(&script, "sql")?;
+        let output = self.d1_execute()
+            .arg("--file")
+            .arg(&file)
+            .output();
+        let _ = std::fs::remove_file(&file);
+
+        check(output.context("Failed to run wrangler d1 execute")?, "D1 statements failed")?;
+        Ok(())
+    }
+
+    fn r2_get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let output = self.r2_object("get", key)
+            .arg("--pipe")
+            .output()
+            .context("Failed to run wrangler r2 object get")?;
+
+        if output.status.success() {
+            return Ok(Some(output.stdout));
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).to_lowercase();
+        if stderr.contains("does not exist") || stderr.contains("not found") {
+            return Ok(None);
+        }
+        anyhow::bail!("Failed to download {}: {}", key, stderr.trim())
+    }
+
+    fn r2_put(&self, key: &str, body: &[u8], content_type: &str) -> Result<()> {
+        let file = write_temp_file(body, "bin")?;
+        let output = self.r2_object("put", key)
+            .arg("--file")
+            .arg(&file)
+            .args(["--content-type", content_type])
+            .output();
+        let _ = std::fs::remove_file(&file);
+
+        check(output.context("Failed to run wrangler r2 object put")?, &format!("Failed to upload {}", key))?;
+        Ok(())
+    }
+
+    fn r2_delete(&self, key: &str) -> Result<()> {
+        let output = self.r2_object("delete", key)
+            .output()
+            .context("Failed to run wrangler r2 object delete")?;
+        check(output, &format!("Failed to delete {}", key))?;
+        Ok(())
+    }
+
+    fn r2_list(&self, _prefix: &str) -> Result<Option<Vec<String>>> {
+        // Wrangler has no command for listing objects
+        Ok(None)
+    }
+
+    fn purge_cache(&self, _tags: &[&str]) -> Result<bool> {
+        // Wrangler has no command for purging the cache
+        Ok(false)
+    }
+
+    fn kv_put(&self, namespace_id: &str, key: &str, value: &[u8]) -> Result<()> {
+        let file = write_temp_file(value, "bin")?;
+        let mut cmd = self.wrangler();
+        cmd.args(["kv", "key", "put", key, "--namespace-id", namespace_id])
+            .arg("--path")
+            .arg(&file);
+        if self.remote {
+            cmd.arg("--remote");
+        }
+
+        let output = cmd.output();
+        let _ = std::fs::remove_file(&file);
+
+        check(output.context("Failed to run wrangler kv key put")?, &format!("Failed to write KV key {}", key))?;
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!("wrangler ({})", if self.remote { "remote" } else { "local" })
+    }
+}
+
+/// Render a statement as plain SQL with its parameters as literals
+///
+/// `wrangler d1 execute` has no parameter binding. Placeholders inside quoted
+/// strings or identifiers are left alone; strings are quoted with `'` doubled.
+fn inline_params(statement: &Statement) -> Result<String> {
+    let mut sql = String::with_capacity(statement.sql.len());
+    let mut params = statement.params.iter();
+    let mut quote: Option<char> = None;
+
+    for c in statement.sql.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => {
+                quote = None;
+                sql.push(c);
+            }
+            (Some(_), c) => sql.push(c),
+            (None, '\'' | '"' | '`') => {
+                quote = Some(c);
+                sql.push(c);
+            }
+            (None, '?') => {
+                let param = params.next()
+                    .with_context(|| format!("Missing parameter in: {}", statement.sql))?;
+                sql.push_str(&sql_literal(param));
+            }
+            (None, c) => sql.push(c),
+        }
+    }
+
+    if params.next().is_some() {
+        anyhow::bail!("Too many parameters for: {}", statement.sql);
+    }
+    Ok(sql)
+}
+
+fn sql_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Bool(b) => if *b { "1" } else { "0" }.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format!("'{}'", s.replace('\'', "''")),
+        other => format!("'{}'", other.to_string().replace('\'', "''")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_params() {
+        let statement = Statement::new("INSERT INTO posts (slug, title, word_count, external_url) VALUES (?, ?, ?, ?)")
+            .bind("hello")
+            .bind("It's here")
+            .bind(42)
+            .bind(Value::Null);
+        assert_eq!(
+            inline_params(&statement).unwrap(),
+            "INSERT INTO posts (slug, title, word_count, external_url) VALUES ('hello', 'It''s here', 42, NULL)"
+        );
+    }
+
+    #[test]
+    fn test_inline_params_skips_quoted_placeholders() {
+        let statement = Statement::new("SELECT '?' as q, \"a?b\" FROM t WHERE x = ?").bind("y");
+        assert_eq!(inline_params(&statement).unwrap(), "SELECT '?' as q, \"a?b\" FROM t WHERE x = 'y'");
+    }
+
+    #[test]
+    fn test_inline_params_count_mismatch() {
+        assert!(inline_params(&Statement::new("SELECT ?")).is_err());
+        assert!(inline_params(&Statement::new("SELECT 1").bind(1)).is_err());
+    }
+}